@@ -0,0 +1,291 @@
+#ifndef NAARIS_H
+#define NAARIS_H
+
+/* Generated by cbindgen from capi/src, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define NAARIS_BOARD_WIDTH 10
+
+#define NAARIS_BOARD_HEIGHT 40
+
+#define NAARIS_NEXT_COUNT 3
+
+/**
+ * Cells in a big piece, the most any piece has.
+ */
+#define NAARIS_MAX_CELLS 16
+
+/**
+ * Button bits for `naaris_game_set_input`, packed like `Input::to_bits`.
+ */
+#define NAARIS_INPUT_LEFT (1 << 0)
+
+#define NAARIS_INPUT_RIGHT (1 << 1)
+
+#define NAARIS_INPUT_HARD_DROP (1 << 2)
+
+#define NAARIS_INPUT_SOFT_DROP (1 << 3)
+
+#define NAARIS_INPUT_CW (1 << 4)
+
+#define NAARIS_INPUT_CCW (1 << 5)
+
+#define NAARIS_INPUT_HOLD (1 << 6)
+
+/**
+ * Board cell colors, `Empty` for no block.
+ */
+enum NaarisCell
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+  NAARIS_CELL_EMPTY,
+  NAARIS_CELL_BLACK,
+  NAARIS_CELL_WHITE,
+  NAARIS_CELL_RED,
+  NAARIS_CELL_ORANGE,
+  NAARIS_CELL_YELLOW,
+  NAARIS_CELL_GREEN,
+  NAARIS_CELL_CYAN,
+  NAARIS_CELL_BLUE,
+  NAARIS_CELL_PURPLE,
+  NAARIS_CELL_GLAY,
+};
+#ifndef __cplusplus
+typedef uint8_t NaarisCell;
+#endif // __cplusplus
+
+typedef enum NaarisEventKind {
+  NAARIS_EVENT_KIND_LINE_CLEARED,
+  NAARIS_EVENT_KIND_LINE_SHRINKED,
+  NAARIS_EVENT_KIND_PIECE_SPAWNED,
+  NAARIS_EVENT_KIND_PIECE_LOCKED,
+  NAARIS_EVENT_KIND_T_SPIN,
+  NAARIS_EVENT_KIND_SOFT_DROPPED,
+  NAARIS_EVENT_KIND_HARD_DROPPED,
+  NAARIS_EVENT_KIND_GARBAGE_INSERTED,
+  NAARIS_EVENT_KIND_GARBAGE_RECEIVED,
+  NAARIS_EVENT_KIND_TOPPED_OUT,
+  NAARIS_EVENT_KIND_STATUS_CHANGE,
+  NAARIS_EVENT_KIND_COOL,
+  NAARIS_EVENT_KIND_REGRET,
+  NAARIS_EVENT_KIND_GRADE_UP,
+} NaarisEventKind;
+
+/**
+ * Single player modes with their default settings.
+ */
+typedef enum NaarisMode {
+  NAARIS_MODE_FREE,
+  NAARIS_MODE_TGM3_MASTER,
+  NAARIS_MODE_TGM3_SHIRASE,
+  NAARIS_MODE_TGM1_MASTER,
+  NAARIS_MODE_TGM2_MASTER,
+  NAARIS_MODE_DEATH,
+  NAARIS_MODE_SPRINT,
+  NAARIS_MODE_ULTRA,
+  NAARIS_MODE_MARATHON,
+  NAARIS_MODE_DIG,
+} NaarisMode;
+
+typedef enum NaarisPiece {
+  NAARIS_PIECE_I,
+  NAARIS_PIECE_O,
+  NAARIS_PIECE_T,
+  NAARIS_PIECE_L,
+  NAARIS_PIECE_J,
+  NAARIS_PIECE_S,
+  NAARIS_PIECE_Z,
+} NaarisPiece;
+
+typedef enum NaarisRotation {
+  NAARIS_ROTATION_NORTH,
+  NAARIS_ROTATION_EAST,
+  NAARIS_ROTATION_SOUTH,
+  NAARIS_ROTATION_WEST,
+} NaarisRotation;
+
+typedef enum NaarisSound {
+  NAARIS_SOUND_BOTTOM,
+  NAARIS_SOUND_HOLD,
+  NAARIS_SOUND_LOCK,
+  NAARIS_SOUND_ERASE,
+  NAARIS_SOUND_FALL,
+  NAARIS_SOUND_PIECE_I,
+  NAARIS_SOUND_PIECE_O,
+  NAARIS_SOUND_PIECE_T,
+  NAARIS_SOUND_PIECE_L,
+  NAARIS_SOUND_PIECE_J,
+  NAARIS_SOUND_PIECE_S,
+  NAARIS_SOUND_PIECE_Z,
+  NAARIS_SOUND_RANK_UP,
+  NAARIS_SOUND_COOL,
+  NAARIS_SOUND_GAME_CLEAR,
+  NAARIS_SOUND_GRADE_UP,
+} NaarisSound;
+
+typedef enum NaarisStatus {
+  NAARIS_STATUS_GAME,
+  NAARIS_STATUS_CLEAR,
+  NAARIS_STATUS_ROLL,
+  NAARIS_STATUS_END,
+} NaarisStatus;
+
+/**
+ * Opaque handle to a running game.
+ */
+typedef struct NaarisGame NaarisGame;
+
+typedef struct NaarisPoint {
+  int32_t x;
+  int32_t y;
+} NaarisPoint;
+
+/**
+ * A piece on the board, `x` and `y` being its rotation center. Only the
+ * first `cell_count` of `cells` are set: 4, or 16 for a `big` piece.
+ */
+typedef struct NaarisFallingPiece {
+  enum NaarisPiece piece;
+  enum NaarisRotation rotation;
+  int32_t x;
+  int32_t y;
+  bool big;
+  size_t cell_count;
+  struct NaarisPoint cells[NAARIS_MAX_CELLS];
+} NaarisFallingPiece;
+
+/**
+ * `value` is the line or row count, the number of lines cleared for
+ * `LineShrinked`, the `NaarisPiece` for `PieceSpawned`, 1 for a full
+ * `TSpin` or 0 for a mini, the `NaarisStatus` for `StatusChange` and the
+ * new grade for `GradeUp`. `piece` is only set for `PieceLocked`.
+ */
+typedef struct NaarisEvent {
+  enum NaarisEventKind kind;
+  size_t value;
+  struct NaarisFallingPiece piece;
+} NaarisEvent;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Starts a game of `mode`. Free it with `naaris_game_free`.
+ */
+struct NaarisGame *naaris_game_new(enum NaarisMode mode);
+
+/**
+ * # Safety
+ *
+ * `game` must come from `naaris_game_new` and not be used afterwards. Null is
+ * ignored.
+ */
+void naaris_game_free(struct NaarisGame *game);
+
+/**
+ * Holds `buttons`, a mask of `NAARIS_INPUT_*`, from the next step on.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new`.
+ */
+void naaris_game_set_input(struct NaarisGame *game, uint8_t buttons);
+
+/**
+ * Advances `frames` frames at 60 fps.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new`.
+ */
+void naaris_game_step(struct NaarisGame *game, size_t frames);
+
+/**
+ * `Free` ends when it tops out, the other modes when they say so.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new`.
+ */
+enum NaarisStatus naaris_game_status(const struct NaarisGame *game);
+
+/**
+ * The locked cell at `x`, `y`, `Empty` outside the board.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new`.
+ */
+NaarisCell naaris_game_get_cell(const struct NaarisGame *game, size_t x, size_t y);
+
+/**
+ * Copies the locked cells row by row, without the falling piece.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new` and `cells` must have room
+ * for `NAARIS_BOARD_WIDTH * NAARIS_BOARD_HEIGHT` cells.
+ */
+void naaris_game_get_board(const struct NaarisGame *game, NaarisCell *cells);
+
+/**
+ * Writes the falling piece to `piece`, false when there is none.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new` and `piece` writable.
+ */
+bool naaris_game_get_current_piece(const struct NaarisGame *game, struct NaarisFallingPiece *piece);
+
+/**
+ * Writes the hold piece to `piece`, false when there is none.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new` and `piece` writable.
+ */
+bool naaris_game_get_hold(const struct NaarisGame *game, enum NaarisPiece *piece);
+
+/**
+ * Copies the next queue, nearest first.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new` and `next` must have room
+ * for `NAARIS_NEXT_COUNT` pieces.
+ */
+void naaris_game_get_next(const struct NaarisGame *game, enum NaarisPiece *next);
+
+/**
+ * Takes the oldest event since the last poll into `event`, false when there
+ * are none left. Events pile up until polled.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new` and `event` writable.
+ */
+bool naaris_game_poll_event(struct NaarisGame *game, struct NaarisEvent *event);
+
+/**
+ * Takes the oldest sound since the last poll into `sound`, false when there
+ * are none left. Sounds pile up until polled.
+ *
+ * # Safety
+ *
+ * `game` must be a live game from `naaris_game_new` and `sound` writable.
+ */
+bool naaris_game_poll_sound(struct NaarisGame *game, enum NaarisSound *sound);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* NAARIS_H */
@@ -0,0 +1,627 @@
+//! C API for embedding the engine. Building the crate writes the header to
+//! `include/naaris.h` next to `libnaaris`:
+//!
+//! ```sh
+//! cargo build --release -p tetris-capi
+//! ```
+//!
+//! A game comes from `naaris_game_new`, is driven with `naaris_game_set_input`
+//! and `naaris_game_step` and goes back with `naaris_game_free`. Queries copy
+//! into memory owned by the caller. Board rows count from 0 at the top like
+//! `Board::cells`.
+
+use std::{collections::VecDeque, mem};
+
+use tetris::{
+    Cell, Death, Dig, FallingPiece, Game, GameState, Input, Marathon, Piece, Rotation, Sound,
+    Sprint, Status, TGM1Master, TGM2Master, TGM3Event, TGM3Master, TGM3Shirase, TGM3Sound, TSpin,
+    TetrisEvent, Ultra, DEFAULT_DIG_ROWS, DEFAULT_SPRINT_GOAL, DEFAULT_ULTRA_FRAMES,
+};
+
+pub const NAARIS_BOARD_WIDTH: usize = 10;
+pub const NAARIS_BOARD_HEIGHT: usize = 40;
+pub const NAARIS_NEXT_COUNT: usize = 3;
+/// Cells in a big piece, the most any piece has.
+pub const NAARIS_MAX_CELLS: usize = 16;
+
+/// Button bits for `naaris_game_set_input`, packed like `Input::to_bits`.
+pub const NAARIS_INPUT_LEFT: u8 = 1 << 0;
+pub const NAARIS_INPUT_RIGHT: u8 = 1 << 1;
+pub const NAARIS_INPUT_HARD_DROP: u8 = 1 << 2;
+pub const NAARIS_INPUT_SOFT_DROP: u8 = 1 << 3;
+pub const NAARIS_INPUT_CW: u8 = 1 << 4;
+pub const NAARIS_INPUT_CCW: u8 = 1 << 5;
+pub const NAARIS_INPUT_HOLD: u8 = 1 << 6;
+
+/// Single player modes with their default settings.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisMode {
+    Free,
+    Tgm3Master,
+    Tgm3Shirase,
+    Tgm1Master,
+    Tgm2Master,
+    Death,
+    Sprint,
+    Ultra,
+    Marathon,
+    Dig,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisStatus {
+    Game,
+    Clear,
+    Roll,
+    End,
+}
+
+impl From<Status> for NaarisStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Game => NaarisStatus::Game,
+            Status::Clear => NaarisStatus::Clear,
+            Status::Roll(_) => NaarisStatus::Roll,
+            Status::End => NaarisStatus::End,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisPiece {
+    I,
+    O,
+    T,
+    L,
+    J,
+    S,
+    Z,
+}
+
+impl From<Piece> for NaarisPiece {
+    fn from(piece: Piece) -> Self {
+        match piece {
+            Piece::I => NaarisPiece::I,
+            Piece::O => NaarisPiece::O,
+            Piece::T => NaarisPiece::T,
+            Piece::L => NaarisPiece::L,
+            Piece::J => NaarisPiece::J,
+            Piece::S => NaarisPiece::S,
+            Piece::Z => NaarisPiece::Z,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisRotation {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl From<Rotation> for NaarisRotation {
+    fn from(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::North => NaarisRotation::North,
+            Rotation::East => NaarisRotation::East,
+            Rotation::South => NaarisRotation::South,
+            Rotation::West => NaarisRotation::West,
+        }
+    }
+}
+
+/// Board cell colors, `Empty` for no block.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisCell {
+    Empty,
+    Black,
+    White,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Cyan,
+    Blue,
+    Purple,
+    Glay,
+}
+
+impl From<Option<Cell>> for NaarisCell {
+    fn from(cell: Option<Cell>) -> Self {
+        match cell {
+            None => NaarisCell::Empty,
+            Some(Cell::Black) => NaarisCell::Black,
+            Some(Cell::White) => NaarisCell::White,
+            Some(Cell::Red) => NaarisCell::Red,
+            Some(Cell::Orange) => NaarisCell::Orange,
+            Some(Cell::Yellow) => NaarisCell::Yellow,
+            Some(Cell::Green) => NaarisCell::Green,
+            Some(Cell::Cyan) => NaarisCell::Cyan,
+            Some(Cell::Blue) => NaarisCell::Blue,
+            Some(Cell::Purple) => NaarisCell::Purple,
+            Some(Cell::Glay) => NaarisCell::Glay,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NaarisPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A piece on the board, `x` and `y` being its rotation center. Only the
+/// first `cell_count` of `cells` are set: 4, or 16 for a `big` piece.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NaarisFallingPiece {
+    pub piece: NaarisPiece,
+    pub rotation: NaarisRotation,
+    pub x: i32,
+    pub y: i32,
+    pub big: bool,
+    pub cell_count: usize,
+    pub cells: [NaarisPoint; NAARIS_MAX_CELLS],
+}
+
+const NO_PIECE: NaarisFallingPiece = NaarisFallingPiece {
+    piece: NaarisPiece::I,
+    rotation: NaarisRotation::North,
+    x: 0,
+    y: 0,
+    big: false,
+    cell_count: 0,
+    cells: [NaarisPoint { x: 0, y: 0 }; NAARIS_MAX_CELLS],
+};
+
+impl From<&FallingPiece> for NaarisFallingPiece {
+    fn from(piece: &FallingPiece) -> Self {
+        let (x, y) = (piece.piece_position.0 as i32, piece.piece_position.1 as i32);
+        let mut cells = [NaarisPoint { x: 0, y: 0 }; NAARIS_MAX_CELLS];
+        let mut cell_count = 0;
+        for (cell, (rel_x, rel_y)) in cells.iter_mut().zip(piece.piece_state.get_cells()) {
+            *cell = NaarisPoint {
+                x: x + rel_x as i32,
+                y: y - rel_y as i32,
+            };
+            cell_count += 1;
+        }
+        Self {
+            piece: piece.piece_state.get_kind().into(),
+            rotation: piece.piece_state.get_rotation().into(),
+            x,
+            y,
+            big: piece.piece_state.is_big(),
+            cell_count,
+            cells,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisEventKind {
+    LineCleared,
+    LineShrinked,
+    PieceSpawned,
+    PieceLocked,
+    TSpin,
+    SoftDropped,
+    HardDropped,
+    GarbageInserted,
+    GarbageReceived,
+    ToppedOut,
+    StatusChange,
+    Cool,
+    Regret,
+    GradeUp,
+}
+
+/// `value` is the line or row count, the number of lines cleared for
+/// `LineShrinked`, the `NaarisPiece` for `PieceSpawned`, 1 for a full
+/// `TSpin` or 0 for a mini, the `NaarisStatus` for `StatusChange` and the
+/// new grade for `GradeUp`. `piece` is only set for `PieceLocked`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NaarisEvent {
+    pub kind: NaarisEventKind,
+    pub value: usize,
+    pub piece: NaarisFallingPiece,
+}
+
+impl From<TetrisEvent> for NaarisEvent {
+    fn from(event: TetrisEvent) -> Self {
+        let mut piece = NO_PIECE;
+        let (kind, value) = match event {
+            TetrisEvent::LineCleared(lines) => (NaarisEventKind::LineCleared, lines),
+            // every empty row shrinks, `NaarisGame::step` fills in the
+            // lines cleared
+            TetrisEvent::LineShrinked(_) => (NaarisEventKind::LineShrinked, 0),
+            TetrisEvent::PieceSpawned(spawned) => (
+                NaarisEventKind::PieceSpawned,
+                NaarisPiece::from(spawned) as usize,
+            ),
+            TetrisEvent::PieceLocked(locked) => {
+                piece = (&locked).into();
+                (NaarisEventKind::PieceLocked, 0)
+            }
+            TetrisEvent::TSpin(t_spin) => {
+                (NaarisEventKind::TSpin, (t_spin == TSpin::Full) as usize)
+            }
+            TetrisEvent::SoftDropped(rows) => (NaarisEventKind::SoftDropped, rows),
+            TetrisEvent::HardDropped(rows) => (NaarisEventKind::HardDropped, rows),
+            TetrisEvent::GarbageInserted(rows) => (NaarisEventKind::GarbageInserted, rows),
+            TetrisEvent::GarbageReceived(rows) => (NaarisEventKind::GarbageReceived, rows),
+            TetrisEvent::ToppedOut => (NaarisEventKind::ToppedOut, 0),
+        };
+        Self { kind, value, piece }
+    }
+}
+
+impl From<TGM3Event> for NaarisEvent {
+    fn from(event: TGM3Event) -> Self {
+        let (kind, value) = match event {
+            TGM3Event::StatusChange(status) => (
+                NaarisEventKind::StatusChange,
+                NaarisStatus::from(status) as usize,
+            ),
+            TGM3Event::GotCool => (NaarisEventKind::Cool, 0),
+            TGM3Event::GotRegret => (NaarisEventKind::Regret, 0),
+            TGM3Event::GradeUp(grade) => (NaarisEventKind::GradeUp, grade),
+        };
+        Self {
+            kind,
+            value,
+            piece: NO_PIECE,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum NaarisSound {
+    Bottom,
+    Hold,
+    Lock,
+    Erase,
+    Fall,
+    PieceI,
+    PieceO,
+    PieceT,
+    PieceL,
+    PieceJ,
+    PieceS,
+    PieceZ,
+    RankUp,
+    Cool,
+    GameClear,
+    GradeUp,
+}
+
+impl From<Sound> for NaarisSound {
+    fn from(sound: Sound) -> Self {
+        match sound {
+            Sound::Bottom => NaarisSound::Bottom,
+            Sound::Hold => NaarisSound::Hold,
+            Sound::Lock => NaarisSound::Lock,
+            Sound::Erase => NaarisSound::Erase,
+            Sound::Fall => NaarisSound::Fall,
+            Sound::PieceI => NaarisSound::PieceI,
+            Sound::PieceO => NaarisSound::PieceO,
+            Sound::PieceT => NaarisSound::PieceT,
+            Sound::PieceL => NaarisSound::PieceL,
+            Sound::PieceJ => NaarisSound::PieceJ,
+            Sound::PieceS => NaarisSound::PieceS,
+            Sound::PieceZ => NaarisSound::PieceZ,
+            Sound::RankUp => NaarisSound::RankUp,
+        }
+    }
+}
+
+impl From<TGM3Sound> for NaarisSound {
+    fn from(sound: TGM3Sound) -> Self {
+        match sound {
+            TGM3Sound::Cool => NaarisSound::Cool,
+            TGM3Sound::GameClear => NaarisSound::GameClear,
+            TGM3Sound::GradeUp => NaarisSound::GradeUp,
+        }
+    }
+}
+
+enum Mode {
+    Free(Game),
+    TGM3Master(TGM3Master),
+    TGM3Shirase(TGM3Shirase),
+    TGM1Master(TGM1Master),
+    TGM2Master(TGM2Master),
+    Death(Death),
+    Sprint(Sprint),
+    Ultra(Ultra),
+    Marathon(Marathon),
+    Dig(Dig),
+}
+
+/// Runs `$body` with `$game` bound to the mode inside, `Free` taking the
+/// second body when one is given.
+macro_rules! with_mode {
+    ($mode:expr, $game:ident => $body:expr) => {
+        with_mode!($mode, $game => $body, $game => $body)
+    };
+    ($mode:expr, $game:ident => $body:expr, $free:ident => $free_body:expr) => {
+        match $mode {
+            Mode::Free($free) => $free_body,
+            Mode::TGM3Master($game) => $body,
+            Mode::TGM3Shirase($game) => $body,
+            Mode::TGM1Master($game) => $body,
+            Mode::TGM2Master($game) => $body,
+            Mode::Death($game) => $body,
+            Mode::Sprint($game) => $body,
+            Mode::Ultra($game) => $body,
+            Mode::Marathon($game) => $body,
+            Mode::Dig($game) => $body,
+        }
+    };
+}
+
+/// Opaque handle to a running game.
+pub struct NaarisGame {
+    mode: Mode,
+    events: VecDeque<NaarisEvent>,
+    sounds: VecDeque<NaarisSound>,
+    /// Lines in the last clear, reported when they shrink.
+    cleared: usize,
+}
+
+impl NaarisGame {
+    fn new(mode: NaarisMode) -> Self {
+        let mode = match mode {
+            NaarisMode::Free => Mode::Free(Game::new()),
+            NaarisMode::Tgm3Master => Mode::TGM3Master(TGM3Master::new()),
+            NaarisMode::Tgm3Shirase => Mode::TGM3Shirase(TGM3Shirase::new()),
+            NaarisMode::Tgm1Master => Mode::TGM1Master(TGM1Master::new()),
+            NaarisMode::Tgm2Master => Mode::TGM2Master(TGM2Master::new()),
+            NaarisMode::Death => Mode::Death(Death::new()),
+            NaarisMode::Sprint => Mode::Sprint(Sprint::new(DEFAULT_SPRINT_GOAL)),
+            NaarisMode::Ultra => Mode::Ultra(Ultra::new(DEFAULT_ULTRA_FRAMES)),
+            NaarisMode::Marathon => Mode::Marathon(Marathon::new(false)),
+            NaarisMode::Dig => Mode::Dig(Dig::new(DEFAULT_DIG_ROWS)),
+        };
+        Self {
+            mode,
+            events: VecDeque::new(),
+            sounds: VecDeque::new(),
+            cleared: 0,
+        }
+    }
+
+    fn state(&self) -> &dyn GameState {
+        with_mode!(&self.mode, game => game)
+    }
+
+    fn state_mut(&mut self) -> &mut dyn GameState {
+        with_mode!(&mut self.mode, game => game)
+    }
+
+    fn step(&mut self) {
+        self.state_mut().update();
+        let state = self.state_mut();
+        let events = state.get_event_queue().drain(..).collect::<Vec<_>>();
+        let sounds = state.get_sound_queue().drain(..).collect::<Vec<_>>();
+        for event in events {
+            if let TetrisEvent::LineCleared(lines) = event {
+                self.cleared = lines;
+            }
+            let mut event = NaarisEvent::from(event);
+            if let NaarisEventKind::LineShrinked = event.kind {
+                event.value = self.cleared;
+            }
+            self.events.push_back(event);
+        }
+        self.sounds
+            .extend(sounds.into_iter().map(NaarisSound::from));
+        // the mode's own events and sounds follow the frame's
+        let (events, sounds) = with_mode!(&mut self.mode, game => (
+            mem::take(game.get_tgm3events()),
+            mem::take(game.get_tgm3sounds()),
+        ), _game => (vec![], vec![]));
+        self.events
+            .extend(events.into_iter().map(NaarisEvent::from));
+        self.sounds
+            .extend(sounds.into_iter().map(NaarisSound::from));
+    }
+
+    fn status(&self) -> NaarisStatus {
+        self.state().get_status().into()
+    }
+}
+
+/// Starts a game of `mode`. Free it with `naaris_game_free`.
+#[no_mangle]
+pub extern "C" fn naaris_game_new(mode: NaarisMode) -> *mut NaarisGame {
+    Box::into_raw(Box::new(NaarisGame::new(mode)))
+}
+
+/// # Safety
+///
+/// `game` must come from `naaris_game_new` and not be used afterwards. Null is
+/// ignored.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_free(game: *mut NaarisGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Holds `buttons`, a mask of `NAARIS_INPUT_*`, from the next step on.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new`.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_set_input(game: *mut NaarisGame, buttons: u8) {
+    (*game).state_mut().set_input(Input::from_bits(buttons));
+}
+
+/// Advances `frames` frames at 60 fps.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new`.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_step(game: *mut NaarisGame, frames: usize) {
+    let game = &mut *game;
+    for _ in 0..frames {
+        game.step();
+    }
+}
+
+/// `Free` ends when it tops out, the other modes when they say so.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new`.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_status(game: *const NaarisGame) -> NaarisStatus {
+    (*game).status()
+}
+
+/// The locked cell at `x`, `y`, `Empty` outside the board.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new`.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_get_cell(
+    game: *const NaarisGame,
+    x: usize,
+    y: usize,
+) -> NaarisCell {
+    let board = (*game).state().get_board();
+    board
+        .cells
+        .get(y)
+        .and_then(|row| row.get(x))
+        .copied()
+        .flatten()
+        .into()
+}
+
+/// Copies the locked cells row by row, without the falling piece.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new` and `cells` must have room
+/// for `NAARIS_BOARD_WIDTH * NAARIS_BOARD_HEIGHT` cells.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_get_board(game: *const NaarisGame, cells: *mut NaarisCell) {
+    let board = (*game).state().get_board();
+    let cells = std::slice::from_raw_parts_mut(cells, NAARIS_BOARD_WIDTH * NAARIS_BOARD_HEIGHT);
+    for (out, &cell) in cells.iter_mut().zip(board.cells.iter().flatten()) {
+        *out = cell.into();
+    }
+}
+
+/// Writes the falling piece to `piece`, false when there is none.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new` and `piece` writable.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_get_current_piece(
+    game: *const NaarisGame,
+    piece: *mut NaarisFallingPiece,
+) -> bool {
+    match (*game).state().get_current_piece() {
+        Some(current) => {
+            *piece = (&current).into();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Writes the hold piece to `piece`, false when there is none.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new` and `piece` writable.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_get_hold(
+    game: *const NaarisGame,
+    piece: *mut NaarisPiece,
+) -> bool {
+    match (*game).state().get_hold() {
+        Some(hold) => {
+            *piece = hold.into();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Copies the next queue, nearest first.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new` and `next` must have room
+/// for `NAARIS_NEXT_COUNT` pieces.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_get_next(game: *const NaarisGame, next: *mut NaarisPiece) {
+    let state = (*game).state();
+    let pieces = [
+        state.get_next(),
+        state.get_next_next(),
+        state.get_next_next_next(),
+    ];
+    let next = std::slice::from_raw_parts_mut(next, NAARIS_NEXT_COUNT);
+    for (out, piece) in next.iter_mut().zip(pieces) {
+        *out = piece.into();
+    }
+}
+
+/// Takes the oldest event since the last poll into `event`, false when there
+/// are none left. Events pile up until polled.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new` and `event` writable.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_poll_event(
+    game: *mut NaarisGame,
+    event: *mut NaarisEvent,
+) -> bool {
+    match (*game).events.pop_front() {
+        Some(next) => {
+            *event = next;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Takes the oldest sound since the last poll into `sound`, false when there
+/// are none left. Sounds pile up until polled.
+///
+/// # Safety
+///
+/// `game` must be a live game from `naaris_game_new` and `sound` writable.
+#[no_mangle]
+pub unsafe extern "C" fn naaris_game_poll_sound(
+    game: *mut NaarisGame,
+    sound: *mut NaarisSound,
+) -> bool {
+    match (*game).sounds.pop_front() {
+        Some(next) => {
+            *sound = next;
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,393 @@
+use numpy::{
+    ndarray::{Array1, Array2, Array3},
+    IntoPyArray,
+};
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+use tetris::{
+    env::{Action, Env, Info, Observation, Rewards, VecEnv},
+    Game, Input, TGM3Master,
+};
+
+use crate::{t_spin_name, PyPlacement};
+
+enum AnyEnv {
+    Free(Box<Env<Game>>),
+    TGM3Master(Box<Env<TGM3Master>>),
+}
+
+enum AnyVecEnv {
+    Free(VecEnv<Game>),
+    TGM3Master(VecEnv<TGM3Master>),
+}
+
+/// Runs `$body` with `$env` bound to whichever mode's env is inside.
+macro_rules! with_env {
+    ($any:expr, $env:ident => $body:expr) => {
+        match $any {
+            AnyEnv::Free($env) => $body,
+            AnyEnv::TGM3Master($env) => $body,
+        }
+    };
+}
+
+macro_rules! with_vec_env {
+    ($any:expr, $env:ident => $body:expr) => {
+        match $any {
+            AnyVecEnv::Free($env) => $body,
+            AnyVecEnv::TGM3Master($env) => $body,
+        }
+    };
+}
+
+/// Settings shared by `Env` and `VecEnv`.
+struct Config {
+    mode: String,
+    placements: bool,
+    max_frames: Option<usize>,
+    rewards: Rewards,
+}
+
+impl Config {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        mode: &str,
+        action: &str,
+        max_frames: Option<usize>,
+        lines: Option<[f64; 5]>,
+        t_spins: Option<[f64; 4]>,
+        survival: Option<f64>,
+        holes: Option<f64>,
+        game_over: Option<f64>,
+    ) -> PyResult<Self> {
+        if !matches!(mode, "free" | "tgm3master") {
+            return Err(PyValueError::new_err(format!("unknown mode {mode:?}")));
+        }
+        let placements = match action {
+            "placement" => true,
+            "input" => false,
+            _ => return Err(PyValueError::new_err(format!("unknown action {action:?}"))),
+        };
+        let defaults = Rewards::default();
+        Ok(Self {
+            mode: mode.to_string(),
+            placements,
+            max_frames,
+            rewards: Rewards {
+                lines: lines.unwrap_or(defaults.lines),
+                t_spins: t_spins.unwrap_or(defaults.t_spins),
+                survival: survival.unwrap_or(defaults.survival),
+                holes: holes.unwrap_or(defaults.holes),
+                game_over: game_over.unwrap_or(defaults.game_over),
+            },
+        })
+    }
+
+    fn make_env(&self) -> AnyEnv {
+        let mut env = match self.mode.as_str() {
+            "tgm3master" => AnyEnv::TGM3Master(Box::new(Env::with_factory(TGM3Master::with_seed))),
+            _ => AnyEnv::Free(Box::new(Env::new())),
+        };
+        with_env!(&mut env, env => {
+            env.set_rewards(self.rewards.clone());
+            env.set_max_frames(self.max_frames);
+        });
+        env
+    }
+
+    fn action(&self, action: usize, legal: usize) -> PyResult<Action> {
+        if self.placements {
+            if action >= legal {
+                return Err(PyIndexError::new_err(format!(
+                    "placement {action} out of {legal}"
+                )));
+            }
+            Ok(Action::Place(action))
+        } else {
+            let bits = u8::try_from(action)
+                .ok()
+                .filter(|&bits| bits < 1 << 7)
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!("input bits {action} out of range"))
+                })?;
+            Ok(Action::Input(Input::from_bits(bits)))
+        }
+    }
+}
+
+/// A single game stepped by integer actions, gym style. With
+/// `action="placement"` an action indexes `legal_placements()`, with
+/// `action="input"` it is the buttons for one frame packed like
+/// `Input::to_bits`.
+///
+/// Observations are dicts of `board` (40x10 bool), `current` (piece,
+/// rotation, x, y or all -1), `hold` (piece or -1) and `queue` (3 pieces).
+#[pyclass(unsendable, name = "Env")]
+pub struct PyEnv {
+    inner: AnyEnv,
+    config: Config,
+}
+
+#[pymethods]
+impl PyEnv {
+    #[new]
+    #[pyo3(signature = (
+        mode="free", action="placement", max_frames=None, lines=None, t_spins=None,
+        survival=None, holes=None, game_over=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        mode: &str,
+        action: &str,
+        max_frames: Option<usize>,
+        lines: Option<[f64; 5]>,
+        t_spins: Option<[f64; 4]>,
+        survival: Option<f64>,
+        holes: Option<f64>,
+        game_over: Option<f64>,
+    ) -> PyResult<Self> {
+        let config = Config::new(
+            mode, action, max_frames, lines, t_spins, survival, holes, game_over,
+        )?;
+        Ok(Self {
+            inner: config.make_env(),
+            config,
+        })
+    }
+
+    #[pyo3(signature = (seed=0))]
+    fn reset<'py>(&mut self, py: Python<'py>, seed: u64) -> PyResult<Bound<'py, PyDict>> {
+        let observation = with_env!(&mut self.inner, env => env.reset(seed));
+        observation_dict(py, &[observation], false)
+    }
+
+    /// Returns `(observation, reward, done, info)`.
+    #[allow(clippy::type_complexity)]
+    fn step<'py>(
+        &mut self,
+        py: Python<'py>,
+        action: usize,
+    ) -> PyResult<(Bound<'py, PyDict>, f64, bool, Bound<'py, PyDict>)> {
+        let legal = self.legal_count();
+        let action = self.config.action(action, legal)?;
+        let (observation, reward, done, info) =
+            with_env!(&mut self.inner, env => py.detach(|| env.step(action)));
+        Ok((
+            observation_dict(py, &[observation], false)?,
+            reward,
+            done,
+            info_dict(py, &info)?,
+        ))
+    }
+
+    fn legal_placements(&mut self) -> Vec<PyPlacement> {
+        with_env!(&mut self.inner, env => placements(env.legal_placements()))
+    }
+
+    fn is_done(&self) -> bool {
+        with_env!(&self.inner, env => env.is_done())
+    }
+}
+
+impl PyEnv {
+    fn legal_count(&mut self) -> usize {
+        if !self.config.placements {
+            return 0;
+        }
+        with_env!(&mut self.inner, env => env.legal_placements().len())
+    }
+}
+
+/// Many `Env`s stepped together on all cores, with observations stacked
+/// along a first axis. Finished games restart right away, so the
+/// observation that comes with `done` is the first of the next game.
+#[pyclass(unsendable, name = "VecEnv")]
+pub struct PyVecEnv {
+    inner: AnyVecEnv,
+    config: Config,
+    next_seed: u64,
+}
+
+#[pymethods]
+impl PyVecEnv {
+    #[new]
+    #[pyo3(signature = (
+        n, mode="free", action="placement", max_frames=None, lines=None, t_spins=None,
+        survival=None, holes=None, game_over=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        n: usize,
+        mode: &str,
+        action: &str,
+        max_frames: Option<usize>,
+        lines: Option<[f64; 5]>,
+        t_spins: Option<[f64; 4]>,
+        survival: Option<f64>,
+        holes: Option<f64>,
+        game_over: Option<f64>,
+    ) -> PyResult<Self> {
+        let config = Config::new(
+            mode, action, max_frames, lines, t_spins, survival, holes, game_over,
+        )?;
+        let envs = (0..n).map(|_| config.make_env());
+        let inner = if mode == "tgm3master" {
+            AnyVecEnv::TGM3Master(VecEnv::new(
+                envs.map(|env| match env {
+                    AnyEnv::TGM3Master(env) => *env,
+                    AnyEnv::Free(_) => unreachable!(),
+                })
+                .collect(),
+            ))
+        } else {
+            AnyVecEnv::Free(VecEnv::new(
+                envs.map(|env| match env {
+                    AnyEnv::Free(env) => *env,
+                    AnyEnv::TGM3Master(_) => unreachable!(),
+                })
+                .collect(),
+            ))
+        };
+        Ok(Self {
+            inner,
+            config,
+            next_seed: 0,
+        })
+    }
+
+    fn __len__(&self) -> usize {
+        with_vec_env!(&self.inner, envs => envs.len())
+    }
+
+    /// Environment `i` gets `seed + i`, later games carry on counting.
+    #[pyo3(signature = (seed=0))]
+    fn reset<'py>(&mut self, py: Python<'py>, seed: u64) -> PyResult<Bound<'py, PyDict>> {
+        let n = self.__len__() as u64;
+        let seeds = (seed..seed + n).collect::<Vec<_>>();
+        self.next_seed = seed + n;
+        let observations = with_vec_env!(&mut self.inner, envs => py.detach(|| envs.reset(&seeds)));
+        observation_dict(py, &observations, true)
+    }
+
+    /// Returns `(observations, rewards, dones, infos)`.
+    #[allow(clippy::type_complexity)]
+    fn step<'py>(
+        &mut self,
+        py: Python<'py>,
+        actions: Vec<usize>,
+    ) -> PyResult<(
+        Bound<'py, PyDict>,
+        Bound<'py, PyAny>,
+        Bound<'py, PyAny>,
+        Vec<Bound<'py, PyDict>>,
+    )> {
+        let n = self.__len__();
+        if actions.len() != n {
+            return Err(PyValueError::new_err(format!(
+                "{} actions for {n} environments",
+                actions.len()
+            )));
+        }
+        let legal = if self.config.placements {
+            self.num_placements()
+        } else {
+            vec![0; n]
+        };
+        let actions = actions
+            .iter()
+            .zip(legal)
+            .map(|(&action, legal)| self.config.action(action, legal))
+            .collect::<PyResult<Vec<_>>>()?;
+        let base = self.next_seed;
+        self.next_seed += n as u64;
+        let results = with_vec_env!(&mut self.inner, envs => {
+            py.detach(|| envs.step(&actions, |i| base + i as u64))
+        });
+
+        let observations = results.iter().map(|r| r.0.clone()).collect::<Vec<_>>();
+        let rewards = Array1::from_iter(results.iter().map(|r| r.1));
+        let dones = Array1::from_iter(results.iter().map(|r| r.2));
+        let infos = results
+            .iter()
+            .map(|r| info_dict(py, &r.3))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok((
+            observation_dict(py, &observations, true)?,
+            rewards.into_pyarray(py).into_any(),
+            dones.into_pyarray(py).into_any(),
+            infos,
+        ))
+    }
+
+    fn legal_placements(&mut self) -> Vec<Vec<PyPlacement>> {
+        with_vec_env!(&mut self.inner, envs => {
+            envs.legal_placements().iter().map(|legal| placements(legal)).collect()
+        })
+    }
+
+    /// How many placements each environment has to choose from.
+    fn num_placements(&mut self) -> Vec<usize> {
+        with_vec_env!(&mut self.inner, envs => {
+            envs.legal_placements().iter().map(Vec::len).collect()
+        })
+    }
+}
+
+fn placements(candidates: &[tetris::env::Candidate]) -> Vec<PyPlacement> {
+    candidates
+        .iter()
+        .map(|c| PyPlacement::new(c.placement.clone(), c.hold))
+        .collect()
+}
+
+/// Arrays stacked along a first axis, or one observation as is.
+fn observation_dict<'py>(
+    py: Python<'py>,
+    observations: &[Observation],
+    stacked: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let n = observations.len();
+    let board = Array3::from_shape_fn((n, 40, 10), |(i, y, x)| observations[i].board[y][x]);
+    let current = Array2::from_shape_fn((n, 4), |(i, j)| match observations[i].current {
+        Some(piece) => [
+            piece.piece_state.get_kind() as i16,
+            piece.piece_state.get_rotation() as i16,
+            piece.piece_position.0 as i16,
+            piece.piece_position.1 as i16,
+        ][j],
+        None => -1,
+    });
+    let hold = Array1::from_shape_fn(n, |i| observations[i].hold.map_or(-1, |p| p as i8));
+    let queue = Array2::from_shape_fn((n, 3), |(i, j)| observations[i].queue[j] as i8);
+
+    let dict = PyDict::new(py);
+    if !stacked {
+        let board = board.index_axis_move(numpy::ndarray::Axis(0), 0);
+        dict.set_item("board", board.into_pyarray(py))?;
+        dict.set_item("current", current.row(0).to_owned().into_pyarray(py))?;
+        dict.set_item("hold", hold[0])?;
+        dict.set_item("queue", queue.row(0).to_owned().into_pyarray(py))?;
+    } else {
+        dict.set_item("board", board.into_pyarray(py))?;
+        dict.set_item("current", current.into_pyarray(py))?;
+        dict.set_item("hold", hold.into_pyarray(py))?;
+        dict.set_item("queue", queue.into_pyarray(py))?;
+    }
+    Ok(dict)
+}
+
+fn info_dict<'py>(py: Python<'py>, info: &Info) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("frames", info.frames)?;
+    dict.set_item("lines_cleared", info.lines_cleared)?;
+    dict.set_item("t_spin", info.t_spin.map(t_spin_name))?;
+    dict.set_item("pieces_locked", info.pieces_locked)?;
+    dict.set_item("total_frames", info.total_frames)?;
+    dict.set_item("total_lines", info.total_lines)?;
+    dict.set_item("total_pieces", info.total_pieces)?;
+    dict.set_item("truncated", info.truncated)?;
+    Ok(dict)
+}
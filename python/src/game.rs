@@ -0,0 +1,206 @@
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::{prelude::*, IntoPyObjectExt};
+use tetris::{Board, Game, GameState, Input, Randomizer, Sound, Status, TGM3Master, TetrisEvent};
+
+use crate::{board_array, t_spin_name, PyBoard, PyFallingPiece, PyPiece};
+
+/// Defines a Python class around a `GameState` with the methods every mode
+/// shares, plus the ones in braces.
+macro_rules! game_class {
+    ($name:ident, $py_name:literal, $inner:ty, $with_seed:expr, { $($extra:tt)* }) => {
+        #[pyclass(name = $py_name)]
+        pub struct $name {
+            inner: $inner,
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = (seed=None))]
+            fn new(seed: Option<u64>) -> Self {
+                let with_seed: fn(u64) -> $inner = $with_seed;
+                Self {
+                    inner: seed.map_or_else(<$inner>::new, with_seed),
+                }
+            }
+
+            #[pyo3(signature = (frames=1))]
+            fn update(&mut self, frames: usize) {
+                for _ in 0..frames {
+                    self.inner.update();
+                    self.after_update();
+                }
+            }
+
+            /// Buttons held from the next update on.
+            #[pyo3(signature = (
+                left=false, right=false, hard_drop=false, soft_drop=false, cw=false, ccw=false,
+                hold=false
+            ))]
+            #[allow(clippy::too_many_arguments)]
+            fn set_input(
+                &mut self,
+                left: bool,
+                right: bool,
+                hard_drop: bool,
+                soft_drop: bool,
+                cw: bool,
+                ccw: bool,
+                hold: bool,
+            ) {
+                self.inner.set_input(Input {
+                    left,
+                    right,
+                    hard_drop,
+                    soft_drop,
+                    cw,
+                    ccw,
+                    hold,
+                });
+            }
+
+            /// Same as `set_input` packed like `Input::to_bits`.
+            fn set_input_bits(&mut self, bits: u8) {
+                self.inner.set_input(Input::from_bits(bits));
+            }
+
+            #[getter]
+            fn board(&self) -> PyBoard {
+                PyBoard {
+                    inner: self.inner.get_board(),
+                }
+            }
+
+            /// The board as in `Board.to_numpy`, without the falling piece.
+            fn to_numpy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u8>> {
+                board_array(&self.inner.get_board()).into_pyarray(py)
+            }
+
+            #[getter]
+            fn current_piece(&self) -> Option<PyFallingPiece> {
+                self.inner.get_current_piece().map(PyFallingPiece::from)
+            }
+
+            #[getter]
+            fn hold(&self) -> Option<PyPiece> {
+                self.inner.get_hold().map(PyPiece::from)
+            }
+
+            #[getter]
+            fn next(&self) -> Vec<PyPiece> {
+                vec![
+                    self.inner.get_next().into(),
+                    self.inner.get_next_next().into(),
+                    self.inner.get_next_next_next().into(),
+                ]
+            }
+
+            /// Takes the events since the last call as `(name, value)` pairs.
+            fn events(&mut self, py: Python<'_>) -> PyResult<Vec<(&'static str, Py<PyAny>)>> {
+                self.inner
+                    .get_event_queue()
+                    .drain(..)
+                    .map(|event| event_to_py(py, event))
+                    .collect()
+            }
+
+            /// Takes the sounds since the last call.
+            fn sounds(&mut self) -> Vec<&'static str> {
+                self.inner.get_sound_queue().drain(..).map(sound_name).collect()
+            }
+
+            $($extra)*
+        }
+    };
+}
+
+game_class!(
+    PyGame,
+    "Game",
+    Game,
+    |seed| { Game::from_board(Board::with_seed(Randomizer::default(), seed)) },
+    {
+        fn is_game_over(&self) -> bool {
+            self.inner.is_game_over()
+        }
+    }
+);
+
+impl PyGame {
+    fn after_update(&mut self) {}
+}
+
+game_class!(
+    PyTGM3Master,
+    "TGM3Master",
+    TGM3Master,
+    TGM3Master::with_seed,
+    {
+        #[getter]
+        fn level(&self) -> usize {
+            self.inner.get_level()
+        }
+
+        /// The grade as shown, 0 being 9.
+        #[getter]
+        fn grade(&self) -> usize {
+            self.inner.get_display_grade()
+        }
+
+        /// `"game"`, `"clear"`, `"roll"` or `"end"`.
+        #[getter]
+        fn status(&self) -> &'static str {
+            match self.inner.get_status() {
+                Status::Game => "game",
+                Status::Clear => "clear",
+                Status::Roll(_) => "roll",
+                Status::End => "end",
+            }
+        }
+    }
+);
+
+impl PyTGM3Master {
+    fn after_update(&mut self) {
+        // only the renderer needs these, don't let them pile up
+        self.inner.get_tgm3events().clear();
+        self.inner.get_tgm3sounds().clear();
+    }
+}
+
+fn event_to_py(py: Python<'_>, event: TetrisEvent) -> PyResult<(&'static str, Py<PyAny>)> {
+    Ok(match event {
+        TetrisEvent::LineCleared(lines) => ("line_cleared", lines.into_py_any(py)?),
+        TetrisEvent::LineShrinked(rows) => ("line_shrinked", rows.into_py_any(py)?),
+        TetrisEvent::PieceSpawned(piece) => {
+            ("piece_spawned", PyPiece::from(piece).into_py_any(py)?)
+        }
+        TetrisEvent::PieceLocked(piece) => {
+            ("piece_locked", PyFallingPiece::from(piece).into_py_any(py)?)
+        }
+        TetrisEvent::TSpin(t_spin) => ("t_spin", t_spin_name(t_spin).into_py_any(py)?),
+        TetrisEvent::SoftDropped(rows) => ("soft_dropped", rows.into_py_any(py)?),
+        TetrisEvent::HardDropped(rows) => ("hard_dropped", rows.into_py_any(py)?),
+        TetrisEvent::GarbageInserted(rows) => ("garbage_inserted", rows.into_py_any(py)?),
+        TetrisEvent::GarbageReceived(rows) => ("garbage_received", rows.into_py_any(py)?),
+        TetrisEvent::ToppedOut => ("topped_out", py.None()),
+    })
+}
+
+fn sound_name(sound: Sound) -> &'static str {
+    match sound {
+        Sound::Bottom => "bottom",
+        Sound::Hold => "hold",
+        Sound::Lock => "lock",
+        Sound::Erase => "erase",
+        Sound::Fall => "fall",
+        Sound::PieceI => "piece_i",
+        Sound::PieceO => "piece_o",
+        Sound::PieceT => "piece_t",
+        Sound::PieceL => "piece_l",
+        Sound::PieceJ => "piece_j",
+        Sound::PieceS => "piece_s",
+        Sound::PieceZ => "piece_z",
+        Sound::RankUp => "rank_up",
+    }
+}
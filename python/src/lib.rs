@@ -0,0 +1,335 @@
+//! Python extension module exposing the engine, built with maturin:
+//!
+//! ```sh
+//! cd python && maturin develop --release
+//! ```
+//!
+//! Pieces are numbered like `tetris::Piece`, I O T L J S Z from 0, and board
+//! arrays are 40x10 with row 0 at the top like `Board::cells`.
+
+mod env;
+mod game;
+
+use numpy::{ndarray::Array2, IntoPyArray, PyArray2};
+use pyo3::{exceptions::PyIndexError, prelude::*};
+use tetris::{Board, Cell, FallingPiece, Move, Piece, Placement, Randomizer, Rotation, TSpin};
+
+#[pyclass(eq, eq_int, name = "Piece")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyPiece {
+    I,
+    O,
+    T,
+    L,
+    J,
+    S,
+    Z,
+}
+
+impl From<Piece> for PyPiece {
+    fn from(piece: Piece) -> Self {
+        match piece {
+            Piece::I => PyPiece::I,
+            Piece::O => PyPiece::O,
+            Piece::T => PyPiece::T,
+            Piece::L => PyPiece::L,
+            Piece::J => PyPiece::J,
+            Piece::S => PyPiece::S,
+            Piece::Z => PyPiece::Z,
+        }
+    }
+}
+
+impl From<PyPiece> for Piece {
+    fn from(piece: PyPiece) -> Self {
+        match piece {
+            PyPiece::I => Piece::I,
+            PyPiece::O => Piece::O,
+            PyPiece::T => Piece::T,
+            PyPiece::L => Piece::L,
+            PyPiece::J => Piece::J,
+            PyPiece::S => Piece::S,
+            PyPiece::Z => Piece::Z,
+        }
+    }
+}
+
+#[pyclass(eq, eq_int, name = "Rotation")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyRotation {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl From<Rotation> for PyRotation {
+    fn from(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::North => PyRotation::North,
+            Rotation::East => PyRotation::East,
+            Rotation::South => PyRotation::South,
+            Rotation::West => PyRotation::West,
+        }
+    }
+}
+
+/// A piece somewhere on the board, `x` and `y` being its rotation center.
+#[pyclass(frozen, name = "FallingPiece")]
+#[derive(Debug, Clone)]
+pub struct PyFallingPiece {
+    inner: FallingPiece,
+}
+
+#[pymethods]
+impl PyFallingPiece {
+    #[getter]
+    fn piece(&self) -> PyPiece {
+        self.inner.piece_state.get_kind().into()
+    }
+
+    #[getter]
+    fn rotation(&self) -> PyRotation {
+        self.inner.piece_state.get_rotation().into()
+    }
+
+    #[getter]
+    fn x(&self) -> usize {
+        self.inner.piece_position.0
+    }
+
+    #[getter]
+    fn y(&self) -> usize {
+        self.inner.piece_position.1
+    }
+
+    /// `(x, y)` of the four cells.
+    fn cells(&self) -> Vec<(usize, usize)> {
+        piece_cells(&self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        let (x, y) = self.inner.piece_position;
+        format!(
+            "FallingPiece({:?}, {:?}, x={x}, y={y})",
+            self.inner.piece_state.get_kind(),
+            self.inner.piece_state.get_rotation()
+        )
+    }
+}
+
+impl From<FallingPiece> for PyFallingPiece {
+    fn from(inner: FallingPiece) -> Self {
+        Self { inner }
+    }
+}
+
+fn piece_cells(piece: &FallingPiece) -> Vec<(usize, usize)> {
+    let (x, y) = piece.piece_position;
+    piece
+        .piece_state
+        .get_cells()
+        .into_iter()
+        .map(|(rel_x, rel_y)| ((rel_x + x as i16) as usize, (y as i16 - rel_y) as usize))
+        .collect()
+}
+
+/// Somewhere a piece can come to rest, with the moves that get it there.
+#[pyclass(frozen, name = "Placement")]
+#[derive(Debug, Clone)]
+pub struct PyPlacement {
+    inner: Placement,
+    hold: bool,
+}
+
+#[pymethods]
+impl PyPlacement {
+    #[getter]
+    fn piece(&self) -> PyFallingPiece {
+        self.inner.piece.into()
+    }
+
+    /// Whether the hold piece is played instead of the current one.
+    #[getter]
+    fn hold(&self) -> bool {
+        self.hold
+    }
+
+    /// `"left"`, `"right"`, `"cw"`, `"ccw"`, `"down"` and `"sonic_drop"`,
+    /// before the final hard drop.
+    #[getter]
+    fn moves(&self) -> Vec<&'static str> {
+        self.inner
+            .moves
+            .iter()
+            .map(|m| match m {
+                Move::Left => "left",
+                Move::Right => "right",
+                Move::Cw => "cw",
+                Move::Ccw => "ccw",
+                Move::Down => "down",
+                Move::SonicDrop => "sonic_drop",
+            })
+            .collect()
+    }
+
+    /// `"mini"`, `"full"` or `None`.
+    #[getter]
+    fn t_spin(&self) -> Option<&'static str> {
+        self.inner.t_spin.map(t_spin_name)
+    }
+
+    fn cells(&self) -> Vec<(usize, usize)> {
+        self.inner.get_cells()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Placement({}, hold={}, t_spin={:?})",
+            PyFallingPiece::from(self.inner.piece).__repr__(),
+            self.hold,
+            self.inner.t_spin
+        )
+    }
+}
+
+impl PyPlacement {
+    fn new(inner: Placement, hold: bool) -> Self {
+        Self { inner, hold }
+    }
+}
+
+fn t_spin_name(t_spin: TSpin) -> &'static str {
+    match t_spin {
+        TSpin::Mini => "mini",
+        TSpin::Full => "full",
+    }
+}
+
+#[pyclass(name = "Board")]
+#[derive(Debug, Clone)]
+pub struct PyBoard {
+    inner: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    #[new]
+    #[pyo3(signature = (seed=None))]
+    fn new(seed: Option<u64>) -> Self {
+        let inner = match seed {
+            Some(seed) => Board::with_seed(Randomizer::default(), seed),
+            None => Board::default(),
+        };
+        Self { inner }
+    }
+
+    /// 40x10 `uint8` array, 0 for empty, 1 to 7 for the piece colors in
+    /// `Piece` order and 8 for garbage.
+    fn to_numpy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u8>> {
+        board_array(&self.inner).into_pyarray(py)
+    }
+
+    /// Cell code as in `to_numpy`.
+    fn get_cell(&self, x: usize, y: usize) -> PyResult<u8> {
+        let cell = self
+            .inner
+            .cells
+            .get(y)
+            .and_then(|row| row.get(x))
+            .ok_or_else(|| PyIndexError::new_err((x, y)))?;
+        Ok(cell.map_or(0, cell_code))
+    }
+
+    /// Cell code as in `to_numpy`, 0 empties the cell.
+    fn set_cell(&mut self, x: usize, y: usize, code: u8) -> PyResult<()> {
+        let cell = self
+            .inner
+            .cells
+            .get_mut(y)
+            .and_then(|row| row.get_mut(x))
+            .ok_or_else(|| PyIndexError::new_err((x, y)))?;
+        *cell = code_cell(code);
+        Ok(())
+    }
+
+    #[getter]
+    fn hold(&self) -> Option<PyPiece> {
+        self.inner.hold_piece.map(PyPiece::from)
+    }
+
+    #[getter]
+    fn next_pieces(&self) -> Vec<PyPiece> {
+        self.inner.next_pieces.iter().map(|&p| p.into()).collect()
+    }
+
+    /// Locks `placement` and clears lines, returning how many.
+    fn place(&mut self, placement: &PyPlacement) -> PyResult<usize> {
+        self.inner
+            .set_piece(&placement.inner.piece)
+            .map_err(|e| PyIndexError::new_err(e.to_string()))?;
+        let lines = self.inner.line_clear().unwrap_or(0);
+        self.inner.line_shrink();
+        Ok(lines)
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// Every distinct resting placement for `piece` spawned on `board`.
+#[pyfunction]
+fn find_placements(board: &PyBoard, piece: PyPiece) -> Vec<PyPlacement> {
+    tetris::find_placements(&board.inner, piece.into())
+        .into_iter()
+        .map(|p| PyPlacement::new(p, false))
+        .collect()
+}
+
+fn cell_code(cell: Cell) -> u8 {
+    match cell {
+        Cell::Cyan => 1,
+        Cell::Yellow => 2,
+        Cell::Purple => 3,
+        Cell::Orange => 4,
+        Cell::Blue => 5,
+        Cell::Green => 6,
+        Cell::Red => 7,
+        _ => 8,
+    }
+}
+
+fn code_cell(code: u8) -> Option<Cell> {
+    let piece = match code {
+        0 => return None,
+        1 => Piece::I,
+        2 => Piece::O,
+        3 => Piece::T,
+        4 => Piece::L,
+        5 => Piece::J,
+        6 => Piece::S,
+        7 => Piece::Z,
+        _ => return Some(Cell::Glay),
+    };
+    Some(piece.into())
+}
+
+fn board_array(board: &Board) -> Array2<u8> {
+    Array2::from_shape_fn((40, 10), |(y, x)| board.cells[y][x].map_or(0, cell_code))
+}
+
+#[pymodule]
+fn naaris(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPiece>()?;
+    m.add_class::<PyRotation>()?;
+    m.add_class::<PyFallingPiece>()?;
+    m.add_class::<PyPlacement>()?;
+    m.add_class::<PyBoard>()?;
+    m.add_function(wrap_pyfunction!(find_placements, m)?)?;
+    m.add_class::<game::PyGame>()?;
+    m.add_class::<game::PyTGM3Master>()?;
+    m.add_class::<env::PyEnv>()?;
+    m.add_class::<env::PyVecEnv>()?;
+    Ok(())
+}
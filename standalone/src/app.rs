@@ -7,11 +7,53 @@ use graphics::{
 use piston_window::{
     Button, ButtonArgs, ButtonState, Context, G2d, GfxDevice, Glyphs, RenderArgs, Transformed,
 };
-use tetris::{GameState, Input};
+use tetris::{Bot, GameState, Input, TbpBot, TbpError};
 
 use crate::{renderers::Renderer, settings::KeyConfig, sound::StandaloneSound, CELL_SIZE};
 
-pub struct App<G: GameState + Renderer, R: FnMut() -> G> {
+pub trait Scene {
+    fn render(&mut self, args: &RenderArgs, c: Context, g2d: &mut G2d, d: &mut GfxDevice);
+    fn update(&mut self);
+    fn input(&mut self, args: &ButtonArgs);
+    /// Mouse moves, in window coordinates.
+    fn cursor(&mut self, _position: [f64; 2]) {}
+}
+
+/// Plays instead of the keyboard.
+pub enum Pilot {
+    Bot(Bot),
+    Tbp(TbpBot),
+}
+
+impl Pilot {
+    /// The TBP bot if `tbp` is set and starts, otherwise the built-in one if `ai`.
+    pub fn new(ai: bool, tbp: Option<&str>) -> Option<Self> {
+        if let Some(command) = tbp {
+            let mut words = command.split_whitespace();
+            match words.next().map(|program| TbpBot::spawn(program, words)) {
+                Some(Ok(bot)) => {
+                    println!("{} by {}", bot.get_name(), bot.get_author());
+                    return Some(Pilot::Tbp(bot));
+                }
+                Some(Err(e)) => println!("couldn't start {command}: {e}"),
+                None => {}
+            }
+        }
+        ai.then(|| Pilot::Bot(Bot::new()))
+    }
+
+    fn next_input<G: GameState>(&mut self, game: &G) -> Result<Input, TbpError> {
+        match self {
+            Pilot::Bot(bot) => Ok(bot.next_input(game)),
+            Pilot::Tbp(bot) => bot.next_input(game),
+        }
+    }
+}
+
+type OnUpdate<G> = Box<dyn FnMut(&mut G)>;
+
+/// `reset` makes the game that replaces the current one on restart.
+pub struct App<G: GameState + Renderer, R: FnMut(&G) -> G> {
     fps: FPSCounter,
     glyphs: Glyphs,
     game: G,      // Game
@@ -19,10 +61,18 @@ pub struct App<G: GameState + Renderer, R: FnMut() -> G> {
     key_config: KeyConfig,
     reset: R,
     pause: bool,
+    pilot: Option<Pilot>,
+    on_update: Option<OnUpdate<G>>,
 }
 
-impl<G: GameState + Renderer, R: FnMut() -> G> App<G, R> {
-    pub fn new(game: G, key_config: KeyConfig, glyphs: Glyphs, reset: R) -> Self {
+impl<G: GameState + Renderer, R: FnMut(&G) -> G> App<G, R> {
+    pub fn new(
+        game: G,
+        key_config: KeyConfig,
+        pilot: Option<Pilot>,
+        glyphs: Glyphs,
+        reset: R,
+    ) -> Self {
         Self {
             fps: FPSCounter::default(),
             glyphs,
@@ -31,10 +81,21 @@ impl<G: GameState + Renderer, R: FnMut() -> G> App<G, R> {
             key_config,
             reset,
             pause: false,
+            pilot,
+            on_update: None,
         }
     }
 
-    pub fn render(&mut self, args: &RenderArgs, c: Context, g2d: &mut G2d, d: &mut GfxDevice) {
+    /// Runs `on_update` after every update of the game, for things outside
+    /// the mode such as saving records when it ends.
+    pub fn with_on_update(mut self, on_update: impl FnMut(&mut G) + 'static) -> Self {
+        self.on_update = Some(Box::new(on_update));
+        self
+    }
+}
+
+impl<G: GameState + Renderer, R: FnMut(&G) -> G> Scene for App<G, R> {
+    fn render(&mut self, args: &RenderArgs, c: Context, g2d: &mut G2d, d: &mut GfxDevice) {
         clear(BLACK, g2d);
 
         self.game.render(args, c, g2d, d, &mut self.glyphs);
@@ -71,10 +132,22 @@ impl<G: GameState + Renderer, R: FnMut() -> G> App<G, R> {
         self.glyphs.factory.encoder.flush(d);
     }
 
-    pub fn update(&mut self) {
+    fn update(&mut self) {
+        if let (Some(pilot), false) = (self.pilot.as_mut(), self.pause) {
+            match pilot.next_input(&self.game) {
+                Ok(input) => self.input = input,
+                Err(e) => {
+                    println!("bot stopped: {e}");
+                    self.pilot = None;
+                }
+            }
+        }
         self.game.set_input(self.input);
         if !self.pause {
             self.game.update();
+            if let Some(on_update) = self.on_update.as_mut() {
+                on_update(&mut self.game);
+            }
         }
         {
             let sound_queue = self.game.get_sound_queue();
@@ -101,7 +174,7 @@ impl<G: GameState + Renderer, R: FnMut() -> G> App<G, R> {
         }
     }
 
-    pub fn input(&mut self, args: &ButtonArgs) {
+    fn input(&mut self, args: &ButtonArgs) {
         let state = match args.state {
             ButtonState::Press => true,
             ButtonState::Release => false,
@@ -109,31 +182,12 @@ impl<G: GameState + Renderer, R: FnMut() -> G> App<G, R> {
         match args.button {
             Button::Keyboard(key) => match key {
                 _ => {
-                    if self.key_config.left == key.code() as _ {
-                        self.input.left = state;
-                    }
-                    if self.key_config.right == key.code() as _ {
-                        self.input.right = state;
-                    }
-                    if self.key_config.hard_drop == key.code() as _ {
-                        self.input.hard_drop = state;
-                    }
-                    if self.key_config.soft_drop == key.code() as _ {
-                        self.input.soft_drop = state;
-                    }
-                    if self.key_config.cw == key.code() as _ {
-                        self.input.cw = state;
-                    }
-                    if self.key_config.ccw == key.code() as _ {
-                        self.input.ccw = state;
-                    }
-                    if self.key_config.hold == key.code() as _ {
-                        self.input.hold = state;
-                    }
-                    if self.key_config.restart == key.code() as _ && !state {
-                        self.game = (self.reset)();
+                    self.key_config
+                        .update_input(&mut self.input, key.code() as _, state);
+                    if self.key_config.restart == key.code() as usize && !state {
+                        self.game = (self.reset)(&self.game);
                     }
-                    if self.key_config.pause == key.code() as _ && !state {
+                    if self.key_config.pause == key.code() as usize && !state {
                         self.pause = !self.pause;
                     }
 
@@ -0,0 +1,230 @@
+use fps_counter::FPSCounter;
+use graphics::{
+    clear,
+    color::{BLACK, WHITE},
+    Text,
+};
+use piston_window::{
+    Button, ButtonArgs, ButtonState, Context, G2d, GfxDevice, Glyphs, Key, MouseButton, RenderArgs,
+    Transformed,
+};
+use tetris::{board_to_fumen, Board, Cell, Game, GameState, Input, Piece};
+
+use crate::{
+    app::Scene,
+    renderers::{
+        editor_renderer::{board_cell_at, palette_at, render_editor, PALETTE},
+        render_message, Renderer,
+    },
+    settings::{GameSetting, KeyConfig},
+    sound::play_sounds,
+    CELL_SIZE,
+};
+
+/// Sets up a position with the mouse and keyboard, then plays it in Free mode.
+/// Enter switches between editing and playing, the restart key starts the
+/// position over.
+pub struct EditorApp {
+    fps: FPSCounter,
+    glyphs: Glyphs,
+    board: Board,
+    queue: Vec<Piece>,
+    preview: Game,
+    selected: usize,
+    cursor: [f64; 2],
+    // what the held mouse button paints
+    brush: Option<Option<Cell>>,
+    game_setting: GameSetting,
+    key_config: KeyConfig,
+    playing: Option<Game>,
+    input: Input,
+    pause: bool,
+}
+
+impl EditorApp {
+    pub fn new(
+        board: Board,
+        game_setting: GameSetting,
+        key_config: KeyConfig,
+        glyphs: Glyphs,
+    ) -> Self {
+        Self {
+            fps: FPSCounter::default(),
+            glyphs,
+            preview: Game::from_board(board.clone()),
+            board,
+            queue: vec![],
+            selected: 0,
+            cursor: [0.0, 0.0],
+            brush: None,
+            game_setting,
+            key_config,
+            playing: None,
+            input: Default::default(),
+            pause: false,
+        }
+    }
+
+    /// The board with the queue as set, nothing random after it.
+    fn position(&self) -> Board {
+        let mut board = self.board.clone();
+        board.next_pieces = self.queue.clone();
+        board
+    }
+
+    fn play(&self) -> Game {
+        let mut board = self.board.clone();
+        board.set_queue(&self.queue);
+        self.game_setting.to_game(board)
+    }
+
+    fn paint(&mut self) {
+        if let (Some(cell), Some((x, y))) = (self.brush, board_cell_at(self.cursor)) {
+            self.board.cells[y][x] = cell;
+            self.preview = Game::from_board(self.board.clone());
+        }
+    }
+
+    fn edit(&mut self, key: Key) {
+        let piece = match key {
+            Key::I => Some(Piece::I),
+            Key::O => Some(Piece::O),
+            Key::T => Some(Piece::T),
+            Key::L => Some(Piece::L),
+            Key::J => Some(Piece::J),
+            Key::S => Some(Piece::S),
+            Key::Z => Some(Piece::Z),
+            _ => None,
+        };
+        match key {
+            _ if piece.is_some() => self.queue.extend(piece),
+            Key::Backspace => {
+                self.queue.pop();
+            }
+            Key::Tab => {
+                // none, then each piece in turn
+                let pieces = [
+                    Piece::I,
+                    Piece::O,
+                    Piece::T,
+                    Piece::L,
+                    Piece::J,
+                    Piece::S,
+                    Piece::Z,
+                ];
+                self.board.hold_piece = match self.board.hold_piece {
+                    None => Some(pieces[0]),
+                    Some(hold) => pieces.iter().skip_while(|&&p| p != hold).nth(1).copied(),
+                };
+            }
+            Key::Delete => {
+                for cells_x in self.board.cells.iter_mut() {
+                    cells_x.fill(None);
+                }
+            }
+            Key::F1 => print!("{}", self.position()),
+            Key::F2 => {
+                let board = self.position();
+                let queue = board.hold_piece.is_some() || !self.queue.is_empty();
+                println!("{}", board_to_fumen(&board, None, queue));
+            }
+            _ => {}
+        }
+        self.preview = Game::from_board(self.board.clone());
+    }
+}
+
+impl Scene for EditorApp {
+    fn render(&mut self, args: &RenderArgs, c: Context, g2d: &mut G2d, d: &mut GfxDevice) {
+        clear(BLACK, g2d);
+
+        match self.playing.as_mut() {
+            Some(game) => {
+                game.render(args, c, g2d, d, &mut self.glyphs);
+                if self.pause {
+                    render_message("pause", c, g2d, &mut self.glyphs);
+                }
+            }
+            None => render_editor(
+                &self.preview,
+                &self.queue,
+                self.selected,
+                c,
+                g2d,
+                &mut self.glyphs,
+            ),
+        }
+
+        let fps = self.fps.tick();
+        Text::new_color(WHITE, 8)
+            .draw(
+                &format!("{fps} fps"),
+                &mut self.glyphs,
+                &c.draw_state,
+                c.transform.trans(CELL_SIZE * 16.0 - 32.0, 11.0),
+                g2d,
+            )
+            .unwrap();
+        self.glyphs.factory.encoder.flush(d);
+    }
+
+    fn update(&mut self) {
+        if let Some(game) = self.playing.as_mut() {
+            game.set_input(self.input);
+            if !self.pause {
+                game.update();
+            }
+            play_sounds(game.get_sound_queue());
+            game.get_event_queue().clear();
+        }
+    }
+
+    fn input(&mut self, args: &ButtonArgs) {
+        let state = match args.state {
+            ButtonState::Press => true,
+            ButtonState::Release => false,
+        };
+        match args.button {
+            Button::Keyboard(Key::Return) if !state => {
+                self.playing = match self.playing {
+                    Some(_) => None,
+                    None => Some(self.play()),
+                };
+                self.input = Default::default();
+                self.pause = false;
+            }
+            Button::Keyboard(key) if self.playing.is_some() => {
+                let code = key.code() as usize;
+                self.key_config.update_input(&mut self.input, code, state);
+                if self.key_config.restart == code && !state {
+                    self.playing = Some(self.play());
+                }
+                if self.key_config.pause == code && !state {
+                    self.pause = !self.pause;
+                }
+            }
+            Button::Keyboard(key) if state => self.edit(key),
+            Button::Mouse(button) if self.playing.is_none() => {
+                self.brush = match (button, state) {
+                    (_, false) => None,
+                    (MouseButton::Left, true) => match palette_at(self.cursor) {
+                        Some(selected) => {
+                            self.selected = selected;
+                            None
+                        }
+                        None => Some(Some(PALETTE[self.selected])),
+                    },
+                    (MouseButton::Right, true) => Some(None),
+                    _ => None,
+                };
+                self.paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn cursor(&mut self, position: [f64; 2]) {
+        self.cursor = position;
+        self.paint();
+    }
+}
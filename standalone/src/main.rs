@@ -1,34 +1,69 @@
 extern crate piston_window;
 
 mod app;
+mod editor_app;
+mod net_app;
+mod records;
 mod renderers;
 mod settings;
 mod sound;
+mod versus_app;
 
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string, process};
 
-use app::App;
+use app::{App, Pilot, Scene};
+use editor_app::EditorApp;
 
 pub const CELL_SIZE: f64 = 16.0;
 
-use piston_window::{ButtonEvent, EventLoop, PistonWindow, RenderEvent, WindowSettings};
-use renderers::Renderer;
-use settings::{GameMode, Settings};
+use net_app::NetVersusApp;
+use piston_window::{
+    ButtonEvent, EventLoop, MouseCursorEvent, PistonWindow, RenderEvent, WindowSettings,
+};
+use records::Records;
+use renderers::royale_renderer::mini_grid_width;
+use settings::{GameMode, NetRole, Settings};
 use sound::StandaloneSound;
-use tetris::{Game, GameState, Music, TGM3Master};
+use tetris::{
+    pages_from_fumen, puzzles_from_str, Board, Death, Dig, Game, Lockstep, Marathon, Music,
+    PcPractice, Puzzle, Royale, Sprint, Status, TGM1Master, TGM2Master, TGM3Master, TGM3Shirase,
+    Ultra, Versus, PLAYERS,
+};
+use versus_app::VersusApp;
 
 fn main() {
-    let mut window: PistonWindow = WindowSettings::new(
-        "naaris",
-        [CELL_SIZE * (10.0 + 6.0), CELL_SIZE * (20.0 + 5.0)],
-    )
-    .exit_on_esc(true)
-    .build()
-    .unwrap();
-    window.set_max_fps(60);
-
     let settings_str = read_to_string("./settings.toml").unwrap();
-    let settings: Settings = toml::from_str(&settings_str).unwrap();
+    let mut settings: Settings = toml::from_str(&settings_str).unwrap();
+
+    // `naaris --fumen v115@...` practices the fumen's pages in Free mode, the
+    // restart key moving on to the next page
+    let args: Vec<String> = env::args().collect();
+    let fumen = args
+        .iter()
+        .position(|arg| arg == "--fumen")
+        .and_then(|i| args.get(i + 1))
+        .map(|data| {
+            pages_from_fumen(data).unwrap_or_else(|e| {
+                println!("couldn't read --fumen: {e}");
+                process::exit(1)
+            })
+        });
+    if fumen.is_some() {
+        settings.mode = GameMode::Free;
+    }
+
+    let width = match settings.mode {
+        GameMode::Versus | GameMode::NetVersus => CELL_SIZE * (10.0 + 6.0) * PLAYERS as f64,
+        GameMode::Royale => {
+            CELL_SIZE * (10.0 + 6.0) + mini_grid_width(settings.royale.players.max(2) - 1)
+        }
+        _ => CELL_SIZE * (10.0 + 6.0),
+    };
+    let mut window: PistonWindow = WindowSettings::new("naaris", [width, CELL_SIZE * (20.0 + 5.0)])
+        .exit_on_esc(true)
+        .build()
+        .unwrap();
+    window.set_max_fps(60);
 
     // Pixeloid Sans
     // This font family are licensed under the SIL Open Font License, Version 1.1.
@@ -36,49 +71,215 @@ fn main() {
     let font_path = "./assets/PixeloidSans.ttf";
     let glyphs = window.load_font(font_path).unwrap();
 
+    let pilot = || Pilot::new(settings.ai, settings.tbp.as_deref());
     match settings.mode {
-        GameMode::Free => {
-            let game = Game::from_settings(
-                settings.game.gravity,
-                settings.game.are,
-                settings.game.line_are,
-                settings.game.das,
-                settings.game.lock_delay,
-                settings.game.line_clear_delay,
-            );
+        GameMode::Free => match fumen {
+            Some(pages) => {
+                let game_setting = settings.game;
+                let game = game_setting.to_game(pages[0].board.clone());
+                let mut page = 0;
+                main_loop(
+                    window,
+                    App::new(game, settings.key, pilot(), glyphs, move |_| {
+                        page = (page + 1) % pages.len();
+                        game_setting.to_game(pages[page].board.clone())
+                    }),
+                )
+            }
+            None => {
+                let game = Game::from_settings(
+                    settings.game.gravity,
+                    settings.game.are,
+                    settings.game.line_are,
+                    settings.game.das,
+                    settings.game.lock_delay,
+                    settings.game.line_clear_delay,
+                );
+                main_loop(
+                    window,
+                    App::new(
+                        game,
+                        settings.key,
+                        pilot(),
+                        glyphs,
+                        Box::new(move |_: &Game| {
+                            Game::from_settings(
+                                settings.game.gravity,
+                                settings.game.are,
+                                settings.game.line_are,
+                                settings.game.das,
+                                settings.game.lock_delay,
+                                settings.game.line_clear_delay,
+                            )
+                        }),
+                    ),
+                )
+            }
+        },
+        GameMode::TGM3Master => {
+            let game = TGM3Master::new();
             main_loop(
                 window,
-                App::new(
-                    game,
-                    settings.key,
-                    glyphs,
-                    Box::new(move || {
-                        Game::from_settings(
-                            settings.game.gravity,
-                            settings.game.are,
-                            settings.game.line_are,
-                            settings.game.das,
-                            settings.game.lock_delay,
-                            settings.game.line_clear_delay,
-                        )
-                    }),
-                ),
+                App::new(game, settings.key, pilot(), glyphs, |_| TGM3Master::new()),
             )
         }
-        GameMode::TGM3Master => {
-            let game = TGM3Master::new();
+        GameMode::TGM3Shirase => {
+            let game = TGM3Shirase::new();
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, |_| TGM3Shirase::new()),
+            )
+        }
+        GameMode::TGM1Master => {
+            let game = TGM1Master::new();
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, |_| TGM1Master::new()),
+            )
+        }
+        GameMode::TGM2Master => {
+            let game = TGM2Master::new();
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, |_| TGM2Master::new()),
+            )
+        }
+        GameMode::Death => {
+            let game = Death::new();
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, |_| Death::new()),
+            )
+        }
+        GameMode::Sprint => {
+            let goal = settings.sprint.goal;
+            let new_sprint = move || {
+                let mut game = Sprint::new(goal);
+                game.set_best(Records::load().sprint_best(goal));
+                game
+            };
+            let game = new_sprint();
+            // save once as the sprint ends, restarting clears `ended`
+            let mut ended = false;
+            let save_record = move |game: &mut Sprint| {
+                let end = matches!(game.get_status(), Status::End);
+                if end && !ended && game.is_new_record() {
+                    let mut records = Records::load();
+                    if records.submit_sprint(goal, game.get_frames()) {
+                        records.save();
+                    }
+                }
+                ended = end;
+            };
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, move |_| new_sprint())
+                    .with_on_update(save_record),
+            )
+        }
+        GameMode::Ultra => {
+            let time_limit = settings.ultra.time_limit;
+            let game = Ultra::new(time_limit);
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, move |_| {
+                    Ultra::new(time_limit)
+                }),
+            )
+        }
+        GameMode::Marathon => {
+            let endless = settings.marathon.endless;
+            let game = Marathon::new(endless);
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, move |_| {
+                    Marathon::new(endless)
+                }),
+            )
+        }
+        GameMode::Dig => {
+            let dig = settings.dig;
+            let new_dig = move || {
+                let mut game = Dig::new(dig.rows);
+                game.set_rise_frames(dig.rise_frames);
+                game.set_rise_pieces(dig.rise_pieces);
+                game
+            };
+            let game = new_dig();
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, move |_| new_dig()),
+            )
+        }
+        GameMode::Versus => {
+            let versus = Versus::new(settings.versus.attack.into(), settings.versus.best_of);
+            main_loop(
+                window,
+                VersusApp::new(versus, [settings.key, settings.versus.key], glyphs),
+            )
+        }
+        GameMode::NetVersus => {
+            let net = &settings.net;
+            let (session, seed) = match net.role {
+                NetRole::Host => {
+                    println!("waiting for a peer on {}", net.address);
+                    Lockstep::host(&net.address, net.input_delay).unwrap()
+                }
+                NetRole::Join => {
+                    println!("connecting to {}", net.address);
+                    Lockstep::join(&net.address).unwrap()
+                }
+            };
+            let versus =
+                Versus::with_seed(settings.versus.attack.into(), settings.versus.best_of, seed);
+            main_loop(
+                window,
+                NetVersusApp::new(versus, session, settings.key, glyphs),
+            )
+        }
+        GameMode::Royale => {
+            let royale = settings.royale;
+            let attack = settings.versus.attack;
+            let new_royale = move || {
+                let mut game = Royale::new(royale.players, attack.clone().into());
+                game.set_targeting(royale.targeting.into());
+                game
+            };
+            let game = new_royale();
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, move |_| new_royale()),
+            )
+        }
+        GameMode::Editor => main_loop(
+            window,
+            EditorApp::new(Board::default(), settings.game, settings.key, glyphs),
+        ),
+        GameMode::Puzzle => {
+            let pack = puzzles_from_str(&read_to_string(&settings.puzzle.file).unwrap()).unwrap();
+            let game = Puzzle::new(pack);
+            main_loop(
+                window,
+                App::new(game, settings.key, pilot(), glyphs, Puzzle::retry),
+            )
+        }
+        GameMode::PcPractice => {
+            let show_solution = settings.pc_practice.show_solution;
+            let new_practice = move || {
+                let mut game = PcPractice::new();
+                game.set_show_solution(show_solution);
+                game
+            };
+            let game = new_practice();
             main_loop(
                 window,
-                App::new(game, settings.key, glyphs, || TGM3Master::new()),
+                App::new(game, settings.key, pilot(), glyphs, move |_| new_practice()),
             )
         }
     };
 }
 
-fn main_loop<G: GameState + Renderer, R: FnMut() -> G>(
-    mut window: PistonWindow,
-    mut app: App<G, R>,
-) {
+fn main_loop<S: Scene>(mut window: PistonWindow, mut app: S) {
     music::start::<Music, StandaloneSound, _>(256, || {
         sound::init();
         music::set_volume(0.5);
@@ -94,6 +295,9 @@ fn main_loop<G: GameState + Renderer, R: FnMut() -> G>(
             if let Some(args) = event.button_args() {
                 app.input(&args);
             }
+            if let Some(position) = event.mouse_cursor_args() {
+                app.cursor(position);
+            }
         }
     });
 }
@@ -0,0 +1,100 @@
+use fps_counter::FPSCounter;
+use graphics::{
+    clear,
+    color::{BLACK, WHITE},
+    Text,
+};
+use piston_window::{
+    Button, ButtonArgs, ButtonState, Context, G2d, GfxDevice, Glyphs, RenderArgs, Transformed,
+};
+use tetris::{GameState, Input, Lockstep, NetError, Versus, PLAYERS};
+
+use crate::{
+    app::Scene,
+    renderers::{render_message, versus_renderer::render_versus},
+    settings::KeyConfig,
+    sound::play_sounds,
+    CELL_SIZE,
+};
+
+pub struct NetVersusApp {
+    fps: FPSCounter,
+    glyphs: Glyphs,
+    versus: Versus,
+    session: Lockstep,
+    input: Input,
+    key_config: KeyConfig,
+    error: Option<NetError>,
+}
+
+impl NetVersusApp {
+    pub fn new(versus: Versus, session: Lockstep, key_config: KeyConfig, glyphs: Glyphs) -> Self {
+        Self {
+            fps: FPSCounter::default(),
+            glyphs,
+            versus,
+            session,
+            input: Default::default(),
+            key_config,
+            error: None,
+        }
+    }
+
+    fn step(&mut self) -> Result<(), NetError> {
+        if self.session.wants_local_input() {
+            self.session.add_local_input(self.input)?;
+        }
+        self.session.poll()?;
+        self.session.advance(&mut self.versus)?;
+        Ok(())
+    }
+}
+
+impl Scene for NetVersusApp {
+    fn render(&mut self, args: &RenderArgs, c: Context, g2d: &mut G2d, d: &mut GfxDevice) {
+        clear(BLACK, g2d);
+
+        render_versus(&mut self.versus, args, c, g2d, d, &mut self.glyphs);
+        if let Some(error) = &self.error {
+            render_message(&error.to_string(), c, g2d, &mut self.glyphs);
+        }
+
+        let fps = self.fps.tick();
+        Text::new_color(WHITE, 8)
+            .draw(
+                &format!("{fps} fps"),
+                &mut self.glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(CELL_SIZE * 16.0 * PLAYERS as f64 - 32.0, 11.0),
+                g2d,
+            )
+            .unwrap();
+        self.glyphs.factory.encoder.flush(d);
+    }
+
+    fn update(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.step() {
+            println!("netplay stopped: {e}");
+            self.error = Some(e);
+        }
+        for player in 0..PLAYERS {
+            play_sounds(self.versus.get_player_mut(player).inner.get_sound_queue());
+        }
+    }
+
+    // there is no pause or restart, rounds advance on their own on both ends
+    fn input(&mut self, args: &ButtonArgs) {
+        let state = match args.state {
+            ButtonState::Press => true,
+            ButtonState::Release => false,
+        };
+        if let Button::Keyboard(key) = args.button {
+            self.key_config
+                .update_input(&mut self.input, key.code() as _, state);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+const RECORDS_PATH: &str = "./records.toml";
+
+/// Personal bests, persisted next to `settings.toml`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Records {
+    /// Best sprint times in frames, keyed by line goal.
+    #[serde(default)]
+    pub sprint: BTreeMap<String, usize>,
+}
+
+impl Records {
+    pub fn load() -> Self {
+        read_to_string(RECORDS_PATH)
+            .ok()
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        match toml::to_string(self) {
+            Ok(s) => {
+                if let Err(e) = write(RECORDS_PATH, s) {
+                    println!("failed to save records: {e}");
+                }
+            }
+            Err(e) => println!("failed to serialize records: {e}"),
+        }
+    }
+
+    pub fn sprint_best(&self, goal: usize) -> Option<usize> {
+        self.sprint.get(&goal.to_string()).copied()
+    }
+
+    /// Returns true if `frames` is a new personal best for `goal`.
+    pub fn submit_sprint(&mut self, goal: usize, frames: usize) -> bool {
+        if self.sprint_best(goal).is_some_and(|best| best <= frames) {
+            return false;
+        }
+        self.sprint.insert(goal.to_string(), frames);
+        true
+    }
+}
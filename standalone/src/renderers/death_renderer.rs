@@ -0,0 +1,60 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Death, DeathGrade, Status};
+
+use crate::sound::play_sounds;
+
+use super::{render_message, render_text, standard_render, Renderer};
+
+impl Renderer for Death {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        if self.get_grade() != DeathGrade::None {
+            render_text("grade", 192.0, 80.0, c, g2d, glyphs);
+            render_text(grade_name(self.get_grade()), 208.0, 96.0, c, g2d, glyphs);
+        }
+
+        render_text(
+            &format!("{:0>3}", self.get_level()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        let rank = if self.get_level() > 899 {
+            999
+        } else {
+            self.get_level() / 100 * 100 + 100
+        };
+        render_text(&format!("{: >3}", rank), 208.0, 352.0, c, g2d, glyphs);
+
+        if let Status::End = self.get_status() {
+            let message = match self.get_grade() {
+                DeathGrade::None => "game over".to_string(),
+                grade => format!("congrats!: {}", grade_name(grade)),
+            };
+            render_message(&message, c, g2d, glyphs);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Death);
+
+fn grade_name(grade: DeathGrade) -> &'static str {
+    match grade {
+        DeathGrade::None => "",
+        DeathGrade::M => "M",
+        DeathGrade::GM => "GM",
+    }
+}
@@ -0,0 +1,55 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Dig, Status};
+
+use crate::sound::play_sounds;
+
+use super::{format_frames, render_message, render_text, standard_render, Renderer};
+
+impl Renderer for Dig {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        render_text("time", 192.0, 80.0, c, g2d, glyphs);
+        render_text(
+            &format_frames(self.get_frames()),
+            192.0,
+            96.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("pieces", 192.0, 128.0, c, g2d, glyphs);
+        render_text(&self.get_pieces().to_string(), 192.0, 144.0, c, g2d, glyphs);
+
+        render_text("garbage", 192.0, 320.0, c, g2d, glyphs);
+        render_text(
+            &format!("{: >3}", self.get_garbage_remaining()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        if let Status::End = self.get_status() {
+            let message = if self.is_cleared() {
+                format!("finish!: {}", format_frames(self.get_frames()))
+            } else {
+                "game over".to_string()
+            };
+            render_message(&message, c, g2d, glyphs);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Dig);
@@ -0,0 +1,110 @@
+use graphics::{Context, Transformed};
+use piston_window::{G2d, Glyphs};
+use tetris::{Cell, Game, GameState, Piece};
+
+use crate::CELL_SIZE;
+
+use super::{render_text, RenderInner};
+
+pub const PALETTE: [Cell; 10] = [
+    Cell::Cyan,
+    Cell::Yellow,
+    Cell::Purple,
+    Cell::Orange,
+    Cell::Blue,
+    Cell::Green,
+    Cell::Red,
+    Cell::Glay,
+    Cell::Black,
+    Cell::White,
+];
+
+const PALETTE_X: f64 = CELL_SIZE * 12.5;
+const PALETTE_Y: f64 = CELL_SIZE * 8.0;
+
+/// The visible cell under `position`, as a row of the whole board.
+pub fn board_cell_at(position: [f64; 2]) -> Option<(usize, usize)> {
+    let x = (position[0] / CELL_SIZE - 1.0).floor();
+    let y = (position[1] / CELL_SIZE - 4.0).floor();
+    ((0.0..10.0).contains(&x) && (0.0..20.0).contains(&y)).then(|| (x as usize, y as usize + 20))
+}
+
+/// The index of the `PALETTE` color under `position`.
+pub fn palette_at(position: [f64; 2]) -> Option<usize> {
+    let x = (position[0] - PALETTE_X) / CELL_SIZE;
+    let y = ((position[1] - PALETTE_Y) / CELL_SIZE).floor();
+    ((0.0..1.0).contains(&x) && (0.0..PALETTE.len() as f64).contains(&y)).then(|| y as usize)
+}
+
+/// `preview` is only there for its board, its falling piece isn't drawn.
+pub fn render_editor(
+    preview: &Game,
+    queue: &[Piece],
+    selected: usize,
+    c: Context,
+    g2d: &mut G2d,
+    glyphs: &mut Glyphs,
+) {
+    let board = preview.get_board();
+    preview.render_hold(
+        c.transform
+            .trans(CELL_SIZE * 1.5, CELL_SIZE * 2.5)
+            .scale(0.5, 0.5),
+        g2d,
+        board.hold_piece,
+    );
+    if let Some(&next) = queue.first() {
+        preview.render_next(
+            c.transform.trans(CELL_SIZE * 5.0, CELL_SIZE * 2.0),
+            g2d,
+            next,
+        );
+    }
+    if let [_, next_next, next_next_next, ..] = *queue {
+        preview.render_nexts(
+            c.transform
+                .trans(CELL_SIZE * 6.5, CELL_SIZE * 2.5)
+                .scale(0.5, 0.5),
+            g2d,
+            next_next,
+            next_next_next,
+        );
+    }
+    let transform = c.transform.trans(CELL_SIZE * 1.0, CELL_SIZE * 4.0);
+    preview.render_board(transform, g2d);
+    preview.render_board_pieces_outline(transform, g2d, &board);
+    preview.render_board_outline(transform, g2d, 1.0);
+    render_text("next", 16.0, 20.0, c, g2d, glyphs);
+
+    // the whole queue, the previews only show three
+    render_text("queue", 192.0, 48.0, c, g2d, glyphs);
+    let letters = queue
+        .iter()
+        .map(|piece| piece.to_char())
+        .collect::<Vec<_>>();
+    for (i, line) in letters.chunks(6).take(3).enumerate() {
+        let line = line.iter().collect::<String>();
+        render_text(&line, 192.0, 64.0 + 16.0 * i as f64, c, g2d, glyphs);
+    }
+
+    for (i, cell) in PALETTE.iter().enumerate() {
+        let y = PALETTE_Y + CELL_SIZE * i as f64;
+        preview.render_cell(c.transform.trans(PALETTE_X, y), g2d, 0, 0, cell);
+        if i == selected {
+            render_text(">", PALETTE_X - 10.0, y + 12.0, c, g2d, glyphs);
+        }
+    }
+
+    let help = [
+        "ioltjsz next",
+        "bksp undo",
+        "tab hold",
+        "del clear",
+        "enter play",
+        "f1 text",
+        "f2 fumen",
+    ];
+    for (i, line) in help.iter().enumerate() {
+        render_text(line, 184.0, 296.0 + 12.0 * i as f64, c, g2d, glyphs);
+    }
+}
@@ -0,0 +1,66 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Marathon, Status};
+
+use crate::sound::play_sounds;
+
+use super::{render_message, render_text, standard_render, Renderer};
+
+impl Renderer for Marathon {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        render_text("score", 192.0, 80.0, c, g2d, glyphs);
+        render_text(&self.get_score().to_string(), 192.0, 96.0, c, g2d, glyphs);
+        if let Some(combo) = self.get_scoring().get_combo().filter(|&c| c > 0) {
+            render_text(&format!("{combo} ren"), 192.0, 128.0, c, g2d, glyphs);
+        }
+        if self.get_scoring().is_back_to_back() {
+            render_text("b2b", 192.0, 144.0, c, g2d, glyphs);
+        }
+
+        render_text("lines", 192.0, 288.0, c, g2d, glyphs);
+        render_text(
+            &format!("{: >3}", self.get_lines()),
+            208.0,
+            304.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("level", 192.0, 320.0, c, g2d, glyphs);
+        render_text(
+            &format!("{: >3}", self.get_level()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        if let Status::End = self.get_status() {
+            let title = if self.inner.is_game_over() {
+                "game over"
+            } else {
+                "congrats!"
+            };
+            render_message(
+                &format!("{title}\nscore: {}", self.get_score()),
+                c,
+                g2d,
+                glyphs,
+            );
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Marathon);
@@ -1,11 +1,114 @@
-use graphics::{math::Matrix2d, types::Color, Context, Graphics, Text, Transformed};
+use graphics::{math::Matrix2d, rectangle, types::Color, Context, Graphics, Text, Transformed};
 use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
 use tetris::{Board, Cell, FallingPiece, GameState, Piece};
 
 use crate::CELL_SIZE;
 
+macro_rules! impl_render_inner_for_inner_game {
+    ($mode:ty) => {
+        impl crate::renderers::RenderInner for $mode {
+            fn render_board_outline<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                radius: f64,
+            ) {
+                self.inner.render_board_outline(transform, g, radius);
+            }
+
+            fn render_board<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+            ) {
+                self.inner.render_board(transform, g);
+            }
+
+            fn render_board_pieces_outline<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                board: &tetris::Board,
+            ) {
+                self.inner.render_board_pieces_outline(transform, g, board);
+            }
+
+            fn render_current_piece<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                current_piece: &Option<tetris::FallingPiece>,
+            ) {
+                self.inner.render_current_piece(transform, g, current_piece);
+            }
+
+            fn render_hold<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                hold: Option<tetris::Piece>,
+            ) {
+                self.inner.render_hold(transform, g, hold);
+            }
+
+            fn render_piece<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                piece: tetris::Piece,
+            ) {
+                self.inner.render_piece(transform, g, piece);
+            }
+
+            fn render_next<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                next: tetris::Piece,
+            ) {
+                self.inner.render_next(transform, g, next);
+            }
+
+            fn render_nexts<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                next_next: tetris::Piece,
+                next_next_next: tetris::Piece,
+            ) {
+                self.inner
+                    .render_nexts(transform, g, next_next, next_next_next);
+            }
+
+            fn render_cell<G: graphics::Graphics>(
+                &self,
+                transform: graphics::math::Matrix2d,
+                g: &mut G,
+                x: i32,
+                y: i32,
+                cell: &tetris::Cell,
+            ) {
+                self.inner.render_cell(transform, g, x, y, cell);
+            }
+        }
+    };
+}
+
+pub mod death_renderer;
+pub mod dig_renderer;
+pub mod editor_renderer;
 pub mod game_renderer;
+pub mod marathon_renderer;
+pub mod pc_practice_renderer;
+pub mod puzzle_renderer;
+pub mod royale_renderer;
+pub mod sprint_renderer;
+pub mod tgm1master_renderer;
+pub mod tgm2master_renderer;
 pub mod tgm3master_renderer;
+pub mod tgm3shirase_renderer;
+pub mod ultra_renderer;
+pub mod versus_renderer;
 
 pub trait Renderer {
     fn render(
@@ -191,13 +294,70 @@ const BLUE: [u8; 4] = [33, 65, 198, 255];
 const PURPLE: [u8; 4] = [175, 41, 138, 255];
 const GRAY: [u8; 4] = [107, 107, 107, 255];
 
+pub fn render_text(text: &str, x: f64, y: f64, c: Context, g2d: &mut G2d, glyphs: &mut Glyphs) {
+    Text::new_color(WHITE.to_color(), 8)
+        .draw(text, glyphs, &c.draw_state, c.transform.trans(x, y), g2d)
+        .unwrap();
+}
+
+pub fn render_message(text: &str, c: Context, g2d: &mut G2d, glyphs: &mut Glyphs) {
+    // grow upwards so long results still fit on the board
+    let height = text.lines().count().max(1) as f64 + 2.0;
+    let top = (CELL_SIZE * 12.0).min(CELL_SIZE * (24.0 - height));
+    rectangle(
+        BLACK.to_color(),
+        [0.0, 0.0, CELL_SIZE * 9.0, CELL_SIZE * height],
+        c.transform.trans(CELL_SIZE * 1.5, top),
+        g2d,
+    );
+    for (i, line) in text.lines().enumerate() {
+        render_text(
+            line,
+            CELL_SIZE * 2.0,
+            top + CELL_SIZE * (1.0 + i as f64),
+            c,
+            g2d,
+            glyphs,
+        );
+    }
+}
+
+/// Formats a 60 fps frame count as `m:ss.cc`.
+pub fn format_frames(frames: usize) -> String {
+    format!(
+        "{}:{:02}.{:02}",
+        frames / 3600,
+        frames / 60 % 60,
+        frames % 60 * 100 / 60
+    )
+}
+
 pub fn standard_render<G: GameState + RenderInner>(
+    game: &mut G,
+    args: &RenderArgs,
+    c: Context,
+    g2d: &mut G2d,
+    d: &mut GfxDevice,
+    glyphs: &mut Glyphs,
+) {
+    let queue = [
+        game.get_next(),
+        game.get_next_next(),
+        game.get_next_next_next(),
+    ];
+    render_with_queue(game, args, c, g2d, d, glyphs, &queue);
+}
+
+/// `standard_render` with only `queue` in the next slots, for a queue that
+/// runs out.
+pub fn render_with_queue<G: GameState + RenderInner>(
     game: &mut G,
     _args: &RenderArgs,
     c: Context,
     g2d: &mut G2d,
     _d: &mut GfxDevice,
     glyphs: &mut Glyphs,
+    queue: &[Piece],
 ) {
     game.render_hold(
         c.transform
@@ -206,20 +366,26 @@ pub fn standard_render<G: GameState + RenderInner>(
         g2d,
         game.get_hold(),
     );
-    game.render_next(
-        c.transform.trans(CELL_SIZE * 5.0, CELL_SIZE * 2.0),
-        g2d,
-        game.get_next(),
-    );
+    if let Some(&next) = queue.first() {
+        game.render_next(
+            c.transform.trans(CELL_SIZE * 5.0, CELL_SIZE * 2.0),
+            g2d,
+            next,
+        );
+    }
 
-    game.render_nexts(
-        c.transform
-            .trans(CELL_SIZE * 6.5, CELL_SIZE * 2.5)
-            .scale(0.5, 0.5),
-        g2d,
-        game.get_next_next(),
-        game.get_next_next_next(),
-    );
+    let nexts = c
+        .transform
+        .trans(CELL_SIZE * 6.5, CELL_SIZE * 2.5)
+        .scale(0.5, 0.5);
+    match *queue {
+        [_, next_next, next_next_next, ..] => {
+            game.render_nexts(nexts, g2d, next_next, next_next_next)
+        }
+        // the slot render_nexts fills first
+        [_, next_next] => game.render_piece(nexts.trans(CELL_SIZE * 4.0, 0.0), g2d, next_next),
+        _ => {}
+    }
 
     game.render_board(c.transform.trans(CELL_SIZE * 1.0, CELL_SIZE * 4.0), g2d);
     game.render_board_pieces_outline(
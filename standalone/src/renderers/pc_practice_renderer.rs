@@ -0,0 +1,85 @@
+use graphics::{rectangle, Context, Transformed};
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{GameState, PcPractice, Status};
+
+use crate::{sound::play_sounds, CELL_SIZE};
+
+use super::{render_message, render_text, standard_render, Renderer};
+
+impl Renderer for PcPractice {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        if self.get_show_solution() {
+            render_solution(self, c, g2d);
+        }
+
+        match self.is_pc_possible() {
+            Some(true) => render_text("pc possible", 192.0, 80.0, c, g2d, glyphs),
+            Some(false) => render_text("no pc", 192.0, 80.0, c, g2d, glyphs),
+            None => {}
+        }
+        if let Some(step) = self.get_solution().and_then(|steps| steps.first()) {
+            if step.hold {
+                render_text("hold", 192.0, 96.0, c, g2d, glyphs);
+            }
+        }
+        render_text("perfect clears", 192.0, 128.0, c, g2d, glyphs);
+        render_text(
+            &self.get_perfect_clears().to_string(),
+            192.0,
+            144.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("pieces", 192.0, 176.0, c, g2d, glyphs);
+        render_text(&self.get_pieces().to_string(), 192.0, 192.0, c, g2d, glyphs);
+
+        if let Status::End = self.get_status() {
+            render_message("game over\nrestart: retry", c, g2d, glyphs);
+        }
+    }
+}
+
+// the steps up to the first line clear, later ones are placed on a board
+// with the cleared rows gone
+fn render_solution(game: &PcPractice, c: Context, g2d: &mut G2d) {
+    // during line clears and ARE the steps are ahead of the board
+    if game.get_current_piece().is_none() {
+        return;
+    }
+    let Some(steps) = game.get_solution() else {
+        return;
+    };
+    let transform = c.transform.trans(CELL_SIZE * 1.0, CELL_SIZE * 4.0);
+    let square = rectangle::square(0.0, 0.0, CELL_SIZE - 1.0);
+    let mut board = game.get_board();
+    for (i, step) in steps.iter().enumerate() {
+        // the next piece stands out from the rest
+        let color = if i == 0 {
+            [1.0, 1.0, 1.0, 0.5]
+        } else {
+            [1.0, 1.0, 1.0, 0.2]
+        };
+        for (x, y) in step.placement.get_cells() {
+            let cell_transform =
+                transform.trans(x as f64 * CELL_SIZE, (y as f64 - 20.0) * CELL_SIZE);
+            rectangle(color, square, cell_transform, g2d);
+        }
+        if board.set_piece(&step.placement.piece).is_err() || board.line_clear().is_some() {
+            break;
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(PcPractice);
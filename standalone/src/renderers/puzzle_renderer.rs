@@ -0,0 +1,59 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Puzzle, Status};
+
+use crate::sound::play_sounds;
+
+use super::{render_message, render_text, render_with_queue, Renderer};
+
+impl Renderer for Puzzle {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        // no random pieces after the fixed sequence
+        let queue = self.get_queue();
+        render_with_queue(self, args, c, g2d, d, glyphs, &queue);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        let spec = self.get_spec();
+        render_text(&spec.name, 192.0, 80.0, c, g2d, glyphs);
+        render_text("goal", 192.0, 112.0, c, g2d, glyphs);
+        render_text(&spec.goal.to_string(), 192.0, 128.0, c, g2d, glyphs);
+        if !spec.hold_enabled {
+            render_text("no hold", 192.0, 144.0, c, g2d, glyphs);
+        }
+        render_text("lines", 192.0, 176.0, c, g2d, glyphs);
+        render_text(&self.get_lines().to_string(), 192.0, 192.0, c, g2d, glyphs);
+        if let Some(pieces) = self.get_pieces_remaining() {
+            render_text("pieces left", 192.0, 224.0, c, g2d, glyphs);
+            render_text(&pieces.to_string(), 192.0, 240.0, c, g2d, glyphs);
+        }
+
+        render_text("puzzle", 192.0, 320.0, c, g2d, glyphs);
+        render_text(
+            &format!("{}/{}", self.get_index() + 1, self.get_pack_len()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        if let Status::End = self.get_status() {
+            let message = if self.is_solved() {
+                "solved!\nrestart: next"
+            } else {
+                "failed\nrestart: retry"
+            };
+            render_message(message, c, g2d, glyphs);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Puzzle);
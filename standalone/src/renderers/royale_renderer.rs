@@ -0,0 +1,108 @@
+use graphics::{rectangle, Context, Rectangle, Transformed};
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{GameState, Royale, Status, Targeting};
+
+use crate::{sound::play_sounds, CELL_SIZE};
+
+use super::{
+    render_message, render_text, standard_render, RenderInner, Renderer, ToColor, RED, WHITE,
+};
+
+const MINI_SCALE: f64 = 0.125;
+const MINI_ROWS: usize = 9;
+const MINI_PITCH: [f64; 2] = [
+    CELL_SIZE * 10.0 * MINI_SCALE + 4.0,
+    CELL_SIZE * 20.0 * MINI_SCALE + 4.0,
+];
+
+/// Width of the opponent grid to the right of your board.
+pub fn mini_grid_width(opponents: usize) -> f64 {
+    opponents.div_ceil(MINI_ROWS) as f64 * MINI_PITCH[0] + 4.0
+}
+
+impl Renderer for Royale {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        let you = self.get_player(0);
+        let targeting = match you.get_targeting() {
+            Targeting::Random => "random",
+            Targeting::Attackers => "attackers",
+            Targeting::KOs => "KOs",
+            Targeting::Badges => "badges",
+        };
+        render_text("alive", 192.0, 48.0, c, g2d, glyphs);
+        render_text(&self.get_alive().to_string(), 192.0, 64.0, c, g2d, glyphs);
+        render_text("KOs", 192.0, 96.0, c, g2d, glyphs);
+        render_text(&you.get_kos().to_string(), 192.0, 112.0, c, g2d, glyphs);
+        render_text("badges", 192.0, 144.0, c, g2d, glyphs);
+        render_text(
+            &format!("{} ({})", you.get_badges(), you.get_badge_points()),
+            192.0,
+            160.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("target", 192.0, 192.0, c, g2d, glyphs);
+        render_text(targeting, 192.0, 208.0, c, g2d, glyphs);
+        render_text("attackers", 192.0, 240.0, c, g2d, glyphs);
+        render_text(
+            &self.get_attackers(0).to_string(),
+            192.0,
+            256.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        render_opponents(self, c, g2d);
+
+        if let Status::End = self.get_status() {
+            let message = match self.get_player(0).get_place() {
+                Some(1) => "winner!".to_string(),
+                Some(place) => format!("#{place}\ngame over"),
+                None => "game over".to_string(),
+            };
+            render_message(&message, c, g2d, glyphs);
+        }
+    }
+}
+
+/// Every bot's board at `MINI_SCALE`, your target outlined in red and the
+/// ones targeting you in white.
+fn render_opponents(royale: &Royale, c: Context, g2d: &mut G2d) {
+    let target = royale.get_player(0).get_target();
+    for player in 1..royale.get_players().len() {
+        let index = player - 1;
+        let x = CELL_SIZE * 16.0 + 4.0 + MINI_PITCH[0] * (index / MINI_ROWS) as f64;
+        let y = 4.0 + MINI_PITCH[1] * (index % MINI_ROWS) as f64;
+        let transform = c.transform.trans(x, y);
+        let size = [0.0, 0.0, MINI_PITCH[0] - 4.0, MINI_PITCH[1] - 4.0];
+
+        let game = royale.get_game(player);
+        let mini = transform.scale(MINI_SCALE, MINI_SCALE);
+        game.render_board(mini, g2d);
+        game.render_current_piece(mini, g2d, &game.get_current_piece());
+
+        let state = royale.get_player(player);
+        if !state.is_alive() {
+            rectangle([0.0, 0.0, 0.0, 0.75], size, transform, g2d);
+        } else if Some(player) == target {
+            Rectangle::new_border(RED.to_color(), 1.0).draw(size, &c.draw_state, transform, g2d);
+        } else if state.get_target() == Some(0) {
+            Rectangle::new_border(WHITE.to_color(), 0.5).draw(size, &c.draw_state, transform, g2d);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Royale);
@@ -0,0 +1,60 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Sprint, Status};
+
+use crate::sound::play_sounds;
+
+use super::{format_frames, render_message, render_text, standard_render, Renderer};
+
+impl Renderer for Sprint {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        render_text("time", 192.0, 80.0, c, g2d, glyphs);
+        render_text(
+            &format_frames(self.get_frames()),
+            192.0,
+            96.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        if let Some(best) = self.get_best() {
+            render_text("best", 192.0, 128.0, c, g2d, glyphs);
+            render_text(&format_frames(best), 192.0, 144.0, c, g2d, glyphs);
+        }
+
+        render_text("lines", 192.0, 320.0, c, g2d, glyphs);
+        render_text(
+            &format!("{: >3}", self.get_lines_remaining()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        if let Status::End = self.get_status() {
+            let time = format_frames(self.get_frames());
+            let message = if self.inner.is_game_over() {
+                format!("game over: {time}")
+            } else if self.is_new_record() {
+                format!("finish!: {time}\nnew record!")
+            } else {
+                format!("finish!: {time}")
+            };
+            render_message(&message, c, g2d, glyphs);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Sprint);
@@ -0,0 +1,67 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Status, TGM1Master};
+
+use crate::sound::play_sounds;
+
+use super::{render_message, render_text, standard_render, Renderer};
+
+impl Renderer for TGM1Master {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        render_text("grade", 192.0, 80.0, c, g2d, glyphs);
+        render_text(
+            grade_name(self.get_display_grade()),
+            208.0,
+            96.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("score", 192.0, 128.0, c, g2d, glyphs);
+        render_text(&self.get_score().to_string(), 192.0, 144.0, c, g2d, glyphs);
+        if let Some(next) = self.get_next_grade_score() {
+            render_text("next", 192.0, 176.0, c, g2d, glyphs);
+            render_text(&next.to_string(), 192.0, 192.0, c, g2d, glyphs);
+        }
+
+        render_text(
+            &format!("{:0>3}", self.get_level()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        let rank = if self.get_level() > 899 {
+            999
+        } else {
+            self.get_level() / 100 * 100 + 100
+        };
+        render_text(&format!("{: >3}", rank), 208.0, 352.0, c, g2d, glyphs);
+
+        if let Status::End = self.get_status() {
+            let grade = grade_name(self.get_grade());
+            render_message(&format!("congrats!: {grade}"), c, g2d, glyphs);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(TGM1Master);
+
+fn grade_name(grade: usize) -> &'static str {
+    [
+        "9", "8", "7", "6", "5", "4", "3", "2", "1", "S1", "S2", "S3", "S4", "S5", "S6", "S7",
+        "S8", "S9", "GM",
+    ][grade]
+}
@@ -0,0 +1,129 @@
+use graphics::{math::Matrix2d, rectangle, Context, Graphics};
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Board, Cell, FallingPiece, Piece, Roll, Status, TGM2Master};
+
+use crate::{sound::play_sounds, CELL_SIZE};
+
+use super::{render_message, render_text, standard_render, RenderInner, Renderer, ToColor, BLACK};
+
+impl Renderer for TGM2Master {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        render_text("grade", 192.0, 80.0, c, g2d, glyphs);
+        render_text(
+            grade_name(self.get_display_grade()),
+            208.0,
+            96.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        render_text(
+            &format!("{:0>3}", self.get_level()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        let rank = if self.get_level() > 899 {
+            999
+        } else {
+            self.get_level() / 100 * 100 + 100
+        };
+        render_text(&format!("{: >3}", rank), 208.0, 352.0, c, g2d, glyphs);
+
+        if let Status::End = self.get_status() {
+            let grade = grade_name(self.get_grade());
+            render_message(&format!("congrats!: {grade}"), c, g2d, glyphs);
+        }
+    }
+}
+
+impl RenderInner for TGM2Master {
+    fn render_board_outline<G: Graphics>(&self, transform: Matrix2d, g: &mut G, radius: f64) {
+        self.inner.render_board_outline(transform, g, radius);
+    }
+
+    fn render_board<G: Graphics>(&self, transform: Matrix2d, g: &mut G) {
+        if let Status::Roll(Roll::Invisible) = self.get_status() {
+            let square = [0.0, 0.0, 10.0 * CELL_SIZE, 20.0 * CELL_SIZE];
+            rectangle(BLACK.to_color(), square, transform, g);
+            return;
+        }
+        self.inner.render_board(transform, g);
+    }
+
+    fn render_board_pieces_outline<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        board: &Board,
+    ) {
+        if let Status::Roll(Roll::Invisible) = self.get_status() {
+            return;
+        }
+        self.inner.render_board_pieces_outline(transform, g, board);
+    }
+
+    fn render_current_piece<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        current_piece: &Option<FallingPiece>,
+    ) {
+        self.inner.render_current_piece(transform, g, current_piece);
+    }
+
+    fn render_hold<G: Graphics>(&self, transform: Matrix2d, g: &mut G, hold: Option<Piece>) {
+        self.inner.render_hold(transform, g, hold);
+    }
+
+    fn render_piece<G: Graphics>(&self, transform: Matrix2d, g: &mut G, piece: Piece) {
+        self.inner.render_piece(transform, g, piece);
+    }
+
+    fn render_next<G: Graphics>(&self, transform: Matrix2d, g: &mut G, next: Piece) {
+        self.inner.render_next(transform, g, next);
+    }
+
+    fn render_nexts<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        next_next: Piece,
+        next_next_next: Piece,
+    ) {
+        self.inner
+            .render_nexts(transform, g, next_next, next_next_next);
+    }
+
+    fn render_cell<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        x: i32,
+        y: i32,
+        cell: &Cell,
+    ) {
+        self.inner.render_cell(transform, g, x, y, cell);
+    }
+}
+
+fn grade_name(grade: usize) -> &'static str {
+    [
+        "9", "8", "7", "6", "5", "4", "3", "2", "1", "S1", "S2", "S3", "S4", "S5", "S6", "S7",
+        "S8", "S9", "M", "GM",
+    ][grade]
+}
@@ -72,6 +72,25 @@ impl Renderer for TGM3Master {
             )
             .unwrap();
 
+        Text::new_color(WHITE.to_color(), 8)
+            .draw(
+                "grade",
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(192.0, 80.0),
+                g2d,
+            )
+            .unwrap();
+        Text::new_color(WHITE.to_color(), 8)
+            .draw(
+                grade_name(self.get_display_grade()),
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(208.0, 96.0),
+                g2d,
+            )
+            .unwrap();
+
         if let Status::End = self.get_status() {
             rectangle(
                 BLACK.to_color(),
@@ -79,11 +98,7 @@ impl Renderer for TGM3Master {
                 c.transform.trans(CELL_SIZE * 1.5, CELL_SIZE * 12.0),
                 g2d,
             );
-            let grade = [
-                "9", "8", "7", "6", "5", "4", "3", "2", "1", "S1", "S2", "S3", "S4", "S5", "S6",
-                "S7", "S8", "S9", "m1", "m2", "m3", "m4", "m5", "m6", "m7", "m8", "m9", "M", "MK",
-                "MV", "MO", "MM", "GM",
-            ][self.get_aggregate_grade()];
+            let grade = grade_name(self.get_aggregate_grade());
             Text::new_color(WHITE.to_color(), 8)
                 .draw(
                     &format!("congrats!: {grade}"),
@@ -260,6 +275,14 @@ impl RenderInner for TGM3Master {
     }
 }
 
+fn grade_name(grade: usize) -> &'static str {
+    [
+        "9", "8", "7", "6", "5", "4", "3", "2", "1", "S1", "S2", "S3", "S4", "S5", "S6", "S7",
+        "S8", "S9", "m1", "m2", "m3", "m4", "m5", "m6", "m7", "m8", "m9", "M", "MK", "MV", "MO",
+        "MM", "GM",
+    ][grade]
+}
+
 fn tgm3_roll_render_cell<G: Graphics>(
     transform: Matrix2d,
     g: &mut G,
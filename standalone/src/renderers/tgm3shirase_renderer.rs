@@ -0,0 +1,201 @@
+use graphics::{math::Matrix2d, rectangle, Context, Graphics, Text, Transformed};
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Board, Cell, FallingPiece, GameState, Piece, Status, TGM3Shirase};
+
+use crate::{renderers::BLACK, sound::play_sounds, CELL_SIZE};
+
+use super::{standard_render, RenderInner, Renderer, ToColor, WHITE};
+
+impl Renderer for TGM3Shirase {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        Text::new_color(WHITE.to_color(), 8)
+            .draw(
+                &format!("{:0>4}", self.get_level()),
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(200.0, 336.0),
+                g2d,
+            )
+            .unwrap();
+
+        let rank = if self.get_level() > 1199 {
+            1300
+        } else {
+            self.get_level() / 100 * 100 + 100
+        };
+        Text::new_color(WHITE.to_color(), 8)
+            .draw(
+                &format!("{: >4}", rank),
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(200.0, 352.0),
+                g2d,
+            )
+            .unwrap();
+
+        Text::new_color(WHITE.to_color(), 8)
+            .draw(
+                "grade",
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(192.0, 80.0),
+                g2d,
+            )
+            .unwrap();
+        Text::new_color(WHITE.to_color(), 8)
+            .draw(
+                &grade_name(self.get_display_grade()),
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(208.0, 96.0),
+                g2d,
+            )
+            .unwrap();
+
+        if let Status::End = self.get_status() {
+            rectangle(
+                BLACK.to_color(),
+                [0.0, 0.0, CELL_SIZE * 9.0, CELL_SIZE * 3.0],
+                c.transform.trans(CELL_SIZE * 1.5, CELL_SIZE * 12.0),
+                g2d,
+            );
+            let grade = grade_name(self.get_grade());
+            Text::new_color(WHITE.to_color(), 8)
+                .draw(
+                    &format!("congrats!: {grade}"),
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(CELL_SIZE * 2.0, CELL_SIZE * 13.0),
+                    g2d,
+                )
+                .unwrap();
+        }
+    }
+}
+
+impl RenderInner for TGM3Shirase {
+    fn render_board_outline<G: Graphics>(&self, transform: Matrix2d, g: &mut G, radius: f64) {
+        self.inner.render_board_outline(transform, g, radius);
+    }
+
+    fn render_board<G: Graphics>(&self, transform: Matrix2d, g: &mut G) {
+        if !self.is_bone_block() {
+            self.inner.render_board(transform, g);
+            return;
+        }
+        let square = [0.0, 0.0, 10.0 * CELL_SIZE, 20.0 * CELL_SIZE];
+        rectangle(BLACK.to_color(), square, transform, g);
+        let cell_offset_y = 20;
+        let board = self.inner.get_board();
+        for (y, cells_x) in board.cells.iter().enumerate().skip(cell_offset_y) {
+            for (x, cell) in cells_x.iter().enumerate() {
+                if let Some(cell) = cell {
+                    self.render_cell(transform, g, x as _, (y - cell_offset_y) as _, cell);
+                }
+            }
+        }
+    }
+
+    fn render_board_pieces_outline<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        board: &Board,
+    ) {
+        self.inner.render_board_pieces_outline(transform, g, board);
+    }
+
+    fn render_current_piece<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        current_piece: &Option<FallingPiece>,
+    ) {
+        if !self.is_bone_block() {
+            self.inner.render_current_piece(transform, g, current_piece);
+            return;
+        }
+        if let Some(current_piece) = current_piece {
+            let cell_offset_y = 20;
+            let pos = current_piece.piece_position;
+            for (rel_x, rel_y) in current_piece.piece_state.get_cells().iter() {
+                self.render_cell(
+                    transform,
+                    g,
+                    (rel_x + pos.0 as i16) as _,
+                    ((-rel_y - cell_offset_y as i16) + pos.1 as i16) as _,
+                    &Cell::White,
+                );
+            }
+        }
+    }
+
+    fn render_hold<G: Graphics>(&self, transform: Matrix2d, g: &mut G, hold: Option<Piece>) {
+        self.inner.render_hold(transform, g, hold);
+    }
+
+    fn render_piece<G: Graphics>(&self, transform: Matrix2d, g: &mut G, piece: Piece) {
+        self.inner.render_piece(transform, g, piece);
+    }
+
+    fn render_next<G: Graphics>(&self, transform: Matrix2d, g: &mut G, next: Piece) {
+        self.inner.render_next(transform, g, next);
+    }
+
+    fn render_nexts<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        next_next: Piece,
+        next_next_next: Piece,
+    ) {
+        self.inner
+            .render_nexts(transform, g, next_next, next_next_next);
+    }
+
+    fn render_cell<G: Graphics>(
+        &self,
+        transform: Matrix2d,
+        g: &mut G,
+        x: i32,
+        y: i32,
+        cell: &Cell,
+    ) {
+        if !self.is_bone_block() {
+            self.inner.render_cell(transform, g, x, y, cell);
+            return;
+        }
+        let transform = transform.trans(x as f64 * CELL_SIZE, y as f64 * CELL_SIZE);
+        rectangle(
+            WHITE.to_color(),
+            rectangle::square(0.0, 0.0, CELL_SIZE - 1.0),
+            transform,
+            g,
+        );
+        rectangle(
+            BLACK.to_color(),
+            rectangle::square(3.0, 3.0, CELL_SIZE - 7.0),
+            transform,
+            g,
+        );
+    }
+}
+
+fn grade_name(grade: usize) -> String {
+    if grade == 0 {
+        "1".to_string()
+    } else {
+        format!("S{grade}")
+    }
+}
@@ -0,0 +1,84 @@
+use graphics::Context;
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{ClearType, Status, Ultra};
+
+use crate::sound::play_sounds;
+
+use super::{format_frames, render_message, render_text, standard_render, Renderer};
+
+impl Renderer for Ultra {
+    fn render(
+        &mut self,
+        args: &RenderArgs,
+        c: Context,
+        g2d: &mut G2d,
+        d: &mut GfxDevice,
+        glyphs: &mut Glyphs,
+    ) {
+        standard_render(self, args, c, g2d, d, glyphs);
+        self.get_tgm3events().clear();
+        play_sounds(self.get_tgm3sounds());
+
+        render_text("time", 192.0, 80.0, c, g2d, glyphs);
+        render_text(
+            &format_frames(self.get_frames_remaining()),
+            192.0,
+            96.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("score", 192.0, 128.0, c, g2d, glyphs);
+        render_text(&self.get_score().to_string(), 192.0, 144.0, c, g2d, glyphs);
+        if let Some(combo) = self.get_scoring().get_combo().filter(|&c| c > 0) {
+            render_text(&format!("{combo} ren"), 192.0, 176.0, c, g2d, glyphs);
+        }
+        if self.get_scoring().is_back_to_back() {
+            render_text("b2b", 192.0, 192.0, c, g2d, glyphs);
+        }
+
+        render_text("lines", 192.0, 320.0, c, g2d, glyphs);
+        render_text(
+            &format!("{: >3}", self.get_lines()),
+            208.0,
+            336.0,
+            c,
+            g2d,
+            glyphs,
+        );
+
+        if let Status::End = self.get_status() {
+            let mut result = vec![
+                format!("score: {}", self.get_score()),
+                format!("lines: {}", self.get_lines()),
+                format!("pps: {:.2}", self.get_pps()),
+            ];
+            for clear_type in ClearType::ALL {
+                let count = self.get_scoring().get_clear_count(clear_type);
+                if count > 0 {
+                    result.push(format!("{}: {count}", clear_type_name(clear_type)));
+                }
+            }
+            render_message(&result.join("\n"), c, g2d, glyphs);
+        }
+    }
+}
+
+impl_render_inner_for_inner_game!(Ultra);
+
+fn clear_type_name(clear_type: ClearType) -> &'static str {
+    match clear_type {
+        ClearType::Single => "single",
+        ClearType::Double => "double",
+        ClearType::Triple => "triple",
+        ClearType::Tetris => "tetris",
+        ClearType::TSpinMini => "t-spin mini",
+        ClearType::TSpinMiniSingle => "t-spin mini single",
+        ClearType::TSpinMiniDouble => "t-spin mini double",
+        ClearType::TSpin => "t-spin",
+        ClearType::TSpinSingle => "t-spin single",
+        ClearType::TSpinDouble => "t-spin double",
+        ClearType::TSpinTriple => "t-spin triple",
+        ClearType::PerfectClear => "perfect clear",
+    }
+}
@@ -0,0 +1,71 @@
+use graphics::{rectangle, Context, Transformed};
+use piston_window::{G2d, GfxDevice, Glyphs, RenderArgs};
+use tetris::{Versus, PLAYERS};
+
+use crate::CELL_SIZE;
+
+use super::{render_message, render_text, standard_render, ToColor, RED};
+
+pub fn render_versus(
+    versus: &mut Versus,
+    args: &RenderArgs,
+    c: Context,
+    g2d: &mut G2d,
+    d: &mut GfxDevice,
+    glyphs: &mut Glyphs,
+) {
+    for player in 0..PLAYERS {
+        let c = c.trans(CELL_SIZE * 16.0 * player as f64, 0.0);
+        let incoming = versus.get_incoming_garbage(player);
+        let wins = versus.get_wins()[player];
+        let sent = versus.get_player(player).get_sent();
+        standard_render(
+            &mut versus.get_player_mut(player).inner,
+            args,
+            c,
+            g2d,
+            d,
+            glyphs,
+        );
+        render_garbage_meter(incoming, c, g2d);
+
+        render_text(&format!("{}p", player + 1), 192.0, 48.0, c, g2d, glyphs);
+        render_text("wins", 192.0, 80.0, c, g2d, glyphs);
+        render_text(
+            &format!("{wins}/{}", versus.get_best_of() / 2 + 1),
+            192.0,
+            96.0,
+            c,
+            g2d,
+            glyphs,
+        );
+        render_text("sent", 192.0, 128.0, c, g2d, glyphs);
+        render_text(&sent.to_string(), 192.0, 144.0, c, g2d, glyphs);
+
+        if versus.is_round_over() {
+            let round = match versus.get_round_winner() {
+                Some(winner) if winner == player => "win",
+                Some(_) => "lose",
+                None => "draw",
+            };
+            let message = match versus.get_match_winner() {
+                Some(winner) if winner == player => format!("{round}\nmatch win!"),
+                Some(_) => format!("{round}\nmatch lose"),
+                None => round.to_string(),
+            };
+            render_message(&message, c, g2d, glyphs);
+        }
+    }
+}
+
+/// Pending garbage as a bar to the right of the board, growing from the bottom.
+fn render_garbage_meter(incoming: usize, c: Context, g2d: &mut G2d) {
+    let height = CELL_SIZE * incoming.min(20) as f64;
+    rectangle(
+        RED.to_color(),
+        [0.0, 0.0, CELL_SIZE / 4.0, height],
+        c.transform
+            .trans(CELL_SIZE * 11.0 + 2.0, CELL_SIZE * 24.0 - height),
+        g2d,
+    );
+}
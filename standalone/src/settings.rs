@@ -1,16 +1,59 @@
 use serde_derive::{Deserialize, Serialize};
+use tetris::{
+    AttackTable, Targeting, DEFAULT_DIG_ROWS, DEFAULT_ROYALE_PLAYERS, DEFAULT_SPRINT_GOAL,
+    DEFAULT_ULTRA_FRAMES,
+};
+use tetris::{Board, Game, Input};
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Settings {
     pub mode: GameMode,
     pub game: GameSetting,
     pub key: KeyConfig,
+    /// Let the built-in bot play single player modes.
+    #[serde(default)]
+    pub ai: bool,
+    /// Command line of a TBP bot to play single player modes instead.
+    #[serde(default)]
+    pub tbp: Option<String>,
+    #[serde(default)]
+    pub sprint: SprintSetting,
+    #[serde(default)]
+    pub ultra: UltraSetting,
+    #[serde(default)]
+    pub marathon: MarathonSetting,
+    #[serde(default)]
+    pub dig: DigSetting,
+    #[serde(default)]
+    pub versus: VersusSetting,
+    #[serde(default)]
+    pub net: NetSetting,
+    #[serde(default)]
+    pub royale: RoyaleSetting,
+    #[serde(default)]
+    pub puzzle: PuzzleSetting,
+    #[serde(default)]
+    pub pc_practice: PcPracticeSetting,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub enum GameMode {
     Free,
     TGM3Master,
+    TGM3Shirase,
+    TGM1Master,
+    TGM2Master,
+    Death,
+    Sprint,
+    Ultra,
+    Marathon,
+    Dig,
+    Versus,
+    NetVersus,
+    Royale,
+    Editor,
+    Puzzle,
+    PcPractice,
 }
 
 impl Default for GameMode {
@@ -29,6 +72,244 @@ pub struct GameSetting {
     pub line_clear_delay: usize,
 }
 
+impl GameSetting {
+    /// A Free mode game starting from `board`.
+    pub fn to_game(self, board: Board) -> Game {
+        let mut game = Game::from_board(board);
+        game.set_gravity(self.gravity);
+        game.set_are(self.are);
+        game.set_line_are(self.line_are);
+        game.set_das(self.das);
+        game.set_lock_delay(self.lock_delay);
+        game.set_line_clear_delay(self.line_clear_delay);
+        game
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct SprintSetting {
+    pub goal: usize,
+}
+
+impl Default for SprintSetting {
+    fn default() -> Self {
+        Self {
+            goal: DEFAULT_SPRINT_GOAL,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct UltraSetting {
+    /// Time limit in frames (60 fps).
+    pub time_limit: usize,
+}
+
+impl Default for UltraSetting {
+    fn default() -> Self {
+        Self {
+            time_limit: DEFAULT_ULTRA_FRAMES,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+pub struct MarathonSetting {
+    /// Keep going after the level 15 goal.
+    pub endless: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct DigSetting {
+    pub rows: usize,
+    /// Raise a garbage row every n frames.
+    #[serde(default)]
+    pub rise_frames: Option<usize>,
+    /// Raise a garbage row every n locked pieces.
+    #[serde(default)]
+    pub rise_pieces: Option<usize>,
+}
+
+impl Default for DigSetting {
+    fn default() -> Self {
+        Self {
+            rows: DEFAULT_DIG_ROWS,
+            rise_frames: None,
+            rise_pieces: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PuzzleSetting {
+    /// Puzzle pack, puzzles separated by `---` lines.
+    pub file: String,
+}
+
+impl Default for PuzzleSetting {
+    fn default() -> Self {
+        Self {
+            file: "./assets/puzzles.txt".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct PcPracticeSetting {
+    /// Draw the found perfect clear over the board.
+    pub show_solution: bool,
+}
+
+impl Default for PcPracticeSetting {
+    fn default() -> Self {
+        Self {
+            show_solution: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct VersusSetting {
+    pub best_of: usize,
+    /// Keys for the second player.
+    pub key: KeyConfig,
+    #[serde(default)]
+    pub attack: AttackSetting,
+}
+
+impl Default for VersusSetting {
+    fn default() -> Self {
+        Self {
+            best_of: 3,
+            key: Default::default(),
+            attack: Default::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetRole {
+    Host,
+    Join,
+}
+
+/// Both ends need the same `[versus]` settings, only the seed and input delay are exchanged.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NetSetting {
+    pub role: NetRole,
+    /// Address to listen on when hosting, or to connect to when joining.
+    pub address: String,
+    /// Frames between a key press and it taking effect. Decided by the host.
+    pub input_delay: u32,
+}
+
+impl Default for NetSetting {
+    fn default() -> Self {
+        Self {
+            role: NetRole::Host,
+            address: "127.0.0.1:7878".to_string(),
+            input_delay: 2,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetingSetting {
+    Random,
+    Attackers,
+    KOs,
+    Badges,
+}
+
+impl From<TargetingSetting> for Targeting {
+    fn from(targeting: TargetingSetting) -> Self {
+        match targeting {
+            TargetingSetting::Random => Targeting::Random,
+            TargetingSetting::Attackers => Targeting::Attackers,
+            TargetingSetting::KOs => Targeting::KOs,
+            TargetingSetting::Badges => Targeting::Badges,
+        }
+    }
+}
+
+/// Garbage uses the `[versus.attack]` table.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct RoyaleSetting {
+    /// Including you, everyone else is a bot.
+    pub players: usize,
+    pub targeting: TargetingSetting,
+}
+
+impl Default for RoyaleSetting {
+    fn default() -> Self {
+        Self {
+            players: DEFAULT_ROYALE_PLAYERS,
+            targeting: TargetingSetting::Random,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct AttackSetting {
+    pub single: usize,
+    pub double: usize,
+    pub triple: usize,
+    pub tetris: usize,
+    pub t_spin_mini_single: usize,
+    pub t_spin_mini_double: usize,
+    pub t_spin_single: usize,
+    pub t_spin_double: usize,
+    pub t_spin_triple: usize,
+    pub perfect_clear: usize,
+    pub back_to_back: usize,
+    pub combo: Vec<usize>,
+}
+
+impl Default for AttackSetting {
+    fn default() -> Self {
+        AttackTable::default().into()
+    }
+}
+
+impl From<AttackTable> for AttackSetting {
+    fn from(t: AttackTable) -> Self {
+        Self {
+            single: t.single,
+            double: t.double,
+            triple: t.triple,
+            tetris: t.tetris,
+            t_spin_mini_single: t.t_spin_mini_single,
+            t_spin_mini_double: t.t_spin_mini_double,
+            t_spin_single: t.t_spin_single,
+            t_spin_double: t.t_spin_double,
+            t_spin_triple: t.t_spin_triple,
+            perfect_clear: t.perfect_clear,
+            back_to_back: t.back_to_back,
+            combo: t.combo,
+        }
+    }
+}
+
+impl From<AttackSetting> for AttackTable {
+    fn from(s: AttackSetting) -> Self {
+        Self {
+            single: s.single,
+            double: s.double,
+            triple: s.triple,
+            tetris: s.tetris,
+            t_spin_mini_single: s.t_spin_mini_single,
+            t_spin_mini_double: s.t_spin_mini_double,
+            t_spin_single: s.t_spin_single,
+            t_spin_double: s.t_spin_double,
+            t_spin_triple: s.t_spin_triple,
+            perfect_clear: s.perfect_clear,
+            back_to_back: s.back_to_back,
+            combo: s.combo,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
 pub struct KeyConfig {
     pub left: usize,
@@ -41,3 +322,30 @@ pub struct KeyConfig {
     pub restart: usize,
     pub pause: usize,
 }
+
+impl KeyConfig {
+    /// Updates the game keys of `input` for a key press or release.
+    pub fn update_input(&self, input: &mut Input, code: usize, state: bool) {
+        if self.left == code {
+            input.left = state;
+        }
+        if self.right == code {
+            input.right = state;
+        }
+        if self.hard_drop == code {
+            input.hard_drop = state;
+        }
+        if self.soft_drop == code {
+            input.soft_drop = state;
+        }
+        if self.cw == code {
+            input.cw = state;
+        }
+        if self.ccw == code {
+            input.ccw = state;
+        }
+        if self.hold == code {
+            input.hold = state;
+        }
+    }
+}
@@ -17,6 +17,7 @@ pub enum StandaloneSound {
     RankUp,
     Cool,
     GameClear,
+    GradeUp,
 }
 
 impl From<Sound> for StandaloneSound {
@@ -46,10 +47,17 @@ impl From<TGM3Sound> for StandaloneSound {
         match s {
             TGM3Sound::Cool => Cool,
             TGM3Sound::GameClear => GameClear,
+            TGM3Sound::GradeUp => GradeUp,
         }
     }
 }
 
+pub fn play_sounds<S: Into<StandaloneSound>>(sound_queue: &mut Vec<S>) {
+    while let Some(sound) = sound_queue.pop() {
+        music::play_sound::<StandaloneSound>(&sound.into(), music::Repeat::Times(0), 0.25);
+    }
+}
+
 pub fn init() {
     use StandaloneSound::*;
     music::bind_sound_file(Bottom, "./assets/bottom.wav");
@@ -67,4 +75,5 @@ pub fn init() {
     music::bind_sound_file(RankUp, "./assets/rank_up.wav");
     music::bind_sound_file(Cool, "./assets/cool.wav");
     music::bind_sound_file(GameClear, "./assets/gameclear.wav");
+    music::bind_sound_file(GradeUp, "./assets/rank_up.wav");
 }
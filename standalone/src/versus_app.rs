@@ -0,0 +1,109 @@
+use fps_counter::FPSCounter;
+use graphics::{
+    clear,
+    color::{BLACK, WHITE},
+    rectangle, Text,
+};
+use piston_window::{
+    Button, ButtonArgs, ButtonState, Context, G2d, GfxDevice, Glyphs, RenderArgs, Transformed,
+};
+use tetris::{GameState, Input, Versus, PLAYERS};
+
+use crate::{
+    app::Scene, renderers::versus_renderer::render_versus, settings::KeyConfig, sound::play_sounds,
+    CELL_SIZE,
+};
+
+pub struct VersusApp {
+    fps: FPSCounter,
+    glyphs: Glyphs,
+    versus: Versus,
+    inputs: [Input; PLAYERS],
+    // restart and pause are read from the first player's keys
+    key_configs: [KeyConfig; PLAYERS],
+    pause: bool,
+}
+
+impl VersusApp {
+    pub fn new(versus: Versus, key_configs: [KeyConfig; PLAYERS], glyphs: Glyphs) -> Self {
+        Self {
+            fps: FPSCounter::default(),
+            glyphs,
+            versus,
+            inputs: Default::default(),
+            key_configs,
+            pause: false,
+        }
+    }
+}
+
+impl Scene for VersusApp {
+    fn render(&mut self, args: &RenderArgs, c: Context, g2d: &mut G2d, d: &mut GfxDevice) {
+        clear(BLACK, g2d);
+
+        render_versus(&mut self.versus, args, c, g2d, d, &mut self.glyphs);
+
+        let fps = self.fps.tick();
+        Text::new_color(WHITE, 8)
+            .draw(
+                &format!("{fps} fps"),
+                &mut self.glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(CELL_SIZE * 16.0 * PLAYERS as f64 - 32.0, 11.0),
+                g2d,
+            )
+            .unwrap();
+        if self.pause {
+            rectangle(
+                BLACK,
+                [0.0, 0.0, CELL_SIZE * 9.0, CELL_SIZE * 1.0],
+                c.transform.trans(CELL_SIZE * 1.5, CELL_SIZE * 12.5),
+                g2d,
+            );
+            Text::new_color(WHITE, 8)
+                .draw(
+                    "pause",
+                    &mut self.glyphs,
+                    &c.draw_state,
+                    c.transform.trans(CELL_SIZE * 2.0, CELL_SIZE * 13.0),
+                    g2d,
+                )
+                .unwrap();
+        }
+        self.glyphs.factory.encoder.flush(d);
+    }
+
+    fn update(&mut self) {
+        for (player, input) in self.inputs.iter().enumerate() {
+            self.versus.set_input(player, *input);
+        }
+        if !self.pause {
+            self.versus.update();
+        }
+        for player in 0..PLAYERS {
+            let game = &mut self.versus.get_player_mut(player).inner;
+            play_sounds(game.get_sound_queue());
+            game.get_event_queue().clear();
+        }
+    }
+
+    fn input(&mut self, args: &ButtonArgs) {
+        let state = match args.state {
+            ButtonState::Press => true,
+            ButtonState::Release => false,
+        };
+        if let Button::Keyboard(key) = args.button {
+            let code = key.code() as usize;
+            for (key_config, input) in self.key_configs.iter().zip(self.inputs.iter_mut()) {
+                key_config.update_input(input, code, state);
+            }
+            if self.key_configs[0].restart == code && !state {
+                self.versus.next_round();
+            }
+            if self.key_configs[0].pause == code && !state {
+                self.pause = !self.pause;
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Plays many environments in parallel with uniformly random placement
+//! actions and reports throughput, as a smoke test for `tetris::env`.
+
+use std::time::Instant;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tetris::env::{Action, Env, Rewards, VecEnv};
+
+const ENVS: usize = 64;
+const STEPS: usize = 500;
+
+fn main() {
+    let envs = (0..ENVS)
+        .map(|_| {
+            let mut env = Env::new();
+            env.set_rewards(Rewards {
+                holes: -0.1,
+                ..Default::default()
+            });
+            env.set_max_frames(Some(60 * 60 * 10));
+            env
+        })
+        .collect();
+    let mut envs = VecEnv::new(envs);
+    let seeds = (0..ENVS as u64).collect::<Vec<_>>();
+    envs.reset(&seeds);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut episodes = 0;
+    let mut total_reward = 0.0;
+    let mut pieces = 0;
+    let start = Instant::now();
+    for step in 0..STEPS {
+        let actions = envs
+            .legal_placements()
+            .iter()
+            .map(|legal| Action::Place(rng.gen_range(0..legal.len())))
+            .collect::<Vec<_>>();
+        let results = envs.step(&actions, |i| (step * ENVS + i) as u64);
+        for (_, reward, done, info) in results {
+            total_reward += reward;
+            pieces += info.pieces_locked;
+            episodes += done as usize;
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{} steps, {pieces} pieces, {episodes} episodes, mean reward {:.3}, {:.0} steps/s",
+        STEPS * ENVS,
+        total_reward / (STEPS * ENVS) as f64,
+        (STEPS * ENVS) as f64 / elapsed
+    );
+}
@@ -0,0 +1,55 @@
+//! Lets a TBP bot play endless Marathon headless and reports how it did.
+//! Without arguments it runs the in-repo stub, so build it first:
+//!
+//! ```sh
+//! cargo build --bin tbp_stub && cargo run --example tbp_play
+//! cargo run --example tbp_play -- path/to/bot [args...]
+//! ```
+
+use std::env;
+
+use tetris::*;
+
+const FRAMES: usize = 60 * 60 * 5;
+
+fn main() {
+    let mut args = env::args_os().skip(1);
+    let program = args.next().unwrap_or_else(|| {
+        // examples build into a directory under the bins
+        let exe = env::current_exe().unwrap();
+        let dir = exe.parent().and_then(|examples| examples.parent()).unwrap();
+        dir.join("tbp_stub").into_os_string()
+    });
+    let mut bot = TbpBot::spawn(&program, args).unwrap();
+    bot.set_blocking(true);
+    println!("{} by {}", bot.get_name(), bot.get_author());
+
+    let mut game = Marathon::new(true);
+    let mut pieces = 0;
+    'play: for frame in 0..FRAMES {
+        let input = bot.next_input(&game).unwrap();
+        game.set_input(input);
+        game.update();
+        for event in game.get_event_queue().drain(..) {
+            match event {
+                TetrisEvent::PieceLocked(_) => pieces += 1,
+                TetrisEvent::ToppedOut => {
+                    println!("topped out at frame {frame}");
+                    break 'play;
+                }
+                _ => {}
+            }
+        }
+        game.get_sound_queue().clear();
+        game.get_tgm3events().clear();
+        if let Status::End = game.get_status() {
+            break;
+        }
+    }
+    println!(
+        "{pieces} pieces, {} lines, level {}, score {}",
+        game.get_lines(),
+        game.get_level(),
+        game.get_score()
+    );
+}
@@ -0,0 +1,75 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{ClearType, LockResult};
+
+/// Garbage lines sent per clear, used by the versus modes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackTable {
+    pub single: usize,
+    pub double: usize,
+    pub triple: usize,
+    pub tetris: usize,
+    pub t_spin_mini_single: usize,
+    pub t_spin_mini_double: usize,
+    pub t_spin_single: usize,
+    pub t_spin_double: usize,
+    pub t_spin_triple: usize,
+    pub perfect_clear: usize,
+    pub back_to_back: usize,
+    /// Bonus by combo count, the last entry repeats.
+    pub combo: Vec<usize>,
+}
+
+impl Default for AttackTable {
+    fn default() -> Self {
+        Self {
+            single: 0,
+            double: 1,
+            triple: 2,
+            tetris: 4,
+            t_spin_mini_single: 0,
+            t_spin_mini_double: 1,
+            t_spin_single: 2,
+            t_spin_double: 4,
+            t_spin_triple: 6,
+            perfect_clear: 10,
+            back_to_back: 1,
+            combo: vec![0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5],
+        }
+    }
+}
+
+impl AttackTable {
+    pub fn attack(&self, result: &LockResult) -> usize {
+        if result.lines == 0 {
+            return 0;
+        }
+        let mut lines = match result.clear_type {
+            Some(ClearType::Single) => self.single,
+            Some(ClearType::Double) => self.double,
+            Some(ClearType::Triple) => self.triple,
+            Some(ClearType::Tetris) => self.tetris,
+            Some(ClearType::TSpinMiniSingle) => self.t_spin_mini_single,
+            Some(ClearType::TSpinMiniDouble) => self.t_spin_mini_double,
+            Some(ClearType::TSpinSingle) => self.t_spin_single,
+            Some(ClearType::TSpinDouble) => self.t_spin_double,
+            Some(ClearType::TSpinTriple) => self.t_spin_triple,
+            _ => 0,
+        };
+        if result.back_to_back {
+            lines += self.back_to_back;
+        }
+        if let Some(combo) = result.combo {
+            lines += self
+                .combo
+                .get(combo)
+                .or(self.combo.last())
+                .copied()
+                .unwrap_or(0);
+        }
+        if result.perfect_clear {
+            lines += self.perfect_clear;
+        }
+        lines
+    }
+}
@@ -0,0 +1,118 @@
+//! A minimal TBP bot for trying out the bridge: it suggests every placement of
+//! the current and hold piece, ones that leave a flatter stack with fewer
+//! holes first. Build it with `cargo build --bin tbp_stub` and point a frontend
+//! at the binary.
+
+use std::io::{self, BufRead, Write};
+
+use tetris::*;
+
+#[derive(Default)]
+struct State {
+    board: Board,
+    hold: Option<Piece>,
+    queue: Vec<Piece>,
+}
+
+impl State {
+    fn suggest(&self) -> Vec<TbpMove> {
+        let Some(&current) = self.queue.first() else {
+            return vec![];
+        };
+        let mut pieces = vec![current];
+        match self.hold.or(self.queue.get(1).copied()) {
+            Some(other) if other != current => pieces.push(other),
+            _ => {}
+        }
+        let mut scored = pieces
+            .into_iter()
+            .flat_map(|piece| find_placements(&self.board, piece))
+            .map(|placement| (score(&self.board, &placement), placement))
+            .collect::<Vec<_>>();
+        scored.sort_by_key(|(score, _)| *score);
+        scored
+            .iter()
+            .map(|(_, placement)| TbpMove::from_placement(placement))
+            .collect()
+    }
+
+    fn play(&mut self, mv: &TbpMove) {
+        if self.queue.is_empty() {
+            return;
+        }
+        let current = self.queue.remove(0);
+        if mv.location.kind != current {
+            if self.hold.replace(current).is_none() && !self.queue.is_empty() {
+                self.queue.remove(0);
+            }
+        }
+        if let Some(placement) = mv.to_placement(&self.board, false) {
+            let _ = self.board.set_piece(&placement.piece);
+            self.board.line_clear();
+            self.board.line_shrink();
+        }
+    }
+}
+
+/// Holes, stack height and bumpiness after locking, lower is better.
+fn score(board: &Board, placement: &Placement) -> usize {
+    let mut board = board.clone();
+    let _ = board.set_piece(&placement.piece);
+    board.line_clear();
+    board.line_shrink();
+    let mut holes = 0;
+    let mut heights = [0; 10];
+    for (x, height) in heights.iter_mut().enumerate() {
+        if let Some(top) = board.cells.iter().position(|row| row[x].is_some()) {
+            *height = board.cells.len() - top;
+            holes += board.cells[top..]
+                .iter()
+                .filter(|row| row[x].is_none())
+                .count();
+        }
+    }
+    let bumpiness = heights
+        .windows(2)
+        .map(|w| w[0].abs_diff(w[1]))
+        .sum::<usize>();
+    holes * 8 + heights.iter().sum::<usize>() + bumpiness * 2
+}
+
+fn send(message: &TbpBotMessage) {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, message).unwrap();
+    writeln!(stdout).unwrap();
+    stdout.flush().unwrap();
+}
+
+fn main() {
+    send(&TbpBotMessage::Info {
+        name: "naaris stub".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        author: "naaris".to_string(),
+        features: vec![],
+    });
+
+    let mut state = State::default();
+    for line in io::stdin().lock().lines() {
+        let Ok(message) = serde_json::from_str::<TbpFrontendMessage>(&line.unwrap()) else {
+            continue;
+        };
+        match message {
+            TbpFrontendMessage::Rules => send(&TbpBotMessage::Ready),
+            TbpFrontendMessage::Start(start) => {
+                state = State::default();
+                board_from_tbp(&mut state.board, &start.board);
+                state.hold = start.hold;
+                state.queue = start.queue;
+            }
+            TbpFrontendMessage::Stop => state = State::default(),
+            TbpFrontendMessage::Suggest => send(&TbpBotMessage::Suggestion {
+                moves: state.suggest(),
+            }),
+            TbpFrontendMessage::Play { mv } => state.play(&mv),
+            TbpFrontendMessage::NewPiece { piece } => state.queue.push(piece),
+            TbpFrontendMessage::Quit => break,
+        }
+    }
+}
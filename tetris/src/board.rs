@@ -1,70 +1,159 @@
-use std::fmt::Display;
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use core::str::FromStr;
 
 use arrayvec::ArrayVec;
-use rand::{prelude::SliceRandom, thread_rng};
+use rand::{rngs::StdRng, SeedableRng};
+use thiserror::Error;
 
-use crate::{Cell, FallingPiece, Piece, PieceState, TetrisError};
+use crate::{Cell, FallingPiece, Piece, PieceState, Randomizer, Rotation, TSpin, TetrisError};
 
 #[derive(Debug, Clone)]
 pub struct Board {
     pub cells: ArrayVec<ArrayVec<Option<Cell>, 10>, 40>,
     pub next_pieces: Vec<Piece>,
-    bag: Vec<Piece>,
+    randomizer: Randomizer,
+    rng: StdRng,
     pub hold_piece: Option<Piece>,
 }
 
+#[cfg(feature = "std")]
 impl Default for Board {
     fn default() -> Self {
-        let mut cells = ArrayVec::new();
-        for _ in 0..40 {
-            cells.push(ArrayVec::from([None; 10]));
-        }
-        let mut rng = thread_rng();
-        let mut bag = vec![
-            Piece::I,
-            Piece::O,
-            Piece::T,
-            Piece::L,
-            Piece::J,
-            Piece::S,
-            Piece::Z,
-        ];
-        bag.shuffle(&mut rng);
-        let mut next_pieces = bag.clone();
-        next_pieces.shuffle(&mut rng);
-        Self {
-            cells,
-            next_pieces,
-            bag,
-            hold_piece: None,
-        }
+        Self::new(Randomizer::default())
     }
 }
 
+#[derive(Error, Debug)]
+pub enum BoardParseError {
+    #[error("unknown cell {0:?}")]
+    Cell(char),
+    #[error("unknown piece {0:?}")]
+    Piece(char),
+    #[error("more than one hold piece")]
+    Hold,
+    #[error("row {0} isn't 10 cells wide")]
+    Width(usize),
+    #[error("more than 40 rows")]
+    Height,
+}
+
+/// `hold:` and `next:` lines, then the rows from the top of the visible
+/// field down, `.` being empty and `Cell::to_char` the rest:
+///
+/// ```text
+/// hold: T
+/// next: IOSZLJT
+/// ..........
+/// ...
+/// GGGG.GGGGG
+/// ```
 impl Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "    0 1 2 3 4 5 6 7 8 9")?;
-        writeln!(f, "    -------------------")?;
-
-        let offset_y = 20;
-        for (y, cells_x) in self.cells.iter().enumerate() {
-            if y < offset_y {
-                continue;
-            };
-            write!(f, "{y:02}| ")?;
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(hold) = self.hold_piece {
+            writeln!(f, "hold: {}", hold.to_char())?;
+        }
+        write!(f, "next: ")?;
+        for piece in &self.next_pieces {
+            write!(f, "{}", piece.to_char())?;
+        }
+        writeln!(f)?;
+
+        // rows above the visible field only when something is up there
+        let top = self
+            .cells
+            .iter()
+            .position(|cells_x| cells_x.iter().any(Option::is_some))
+            .map_or(20, |y| y.min(20));
+        for cells_x in &self.cells[top..] {
             for cell in cells_x {
-                match cell {
-                    Some(cell) => write!(f, "{cell} ")?,
-                    None => write!(f, "  ")?,
-                }
+                write!(f, "{}", cell.map_or('.', Cell::to_char))?;
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+/// Reads the `Display` form back. Both lines are optional, blank lines are
+/// skipped and the last row is the bottom one, so a puzzle only needs its
+/// lowest rows. `#` works for gray and letters can be lowercase.
+#[cfg(feature = "std")]
+impl FromStr for Board {
+    type Err = BoardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_pieces = |pieces: &str| {
+            pieces
+                .trim()
+                .chars()
+                .map(|c| Piece::from_char(c).ok_or(BoardParseError::Piece(c)))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut board = Board::default();
+        let mut rows = vec![];
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(hold) = line.strip_prefix("hold:") {
+                let mut hold = parse_pieces(hold)?;
+                if hold.len() > 1 {
+                    return Err(BoardParseError::Hold);
+                }
+                board.hold_piece = hold.pop();
+            } else if let Some(next) = line.strip_prefix("next:") {
+                board.set_queue(&parse_pieces(next)?);
+            } else {
+                let row = line
+                    .chars()
+                    .map(|c| match c {
+                        '.' => Ok(None),
+                        c => Cell::from_char(c).map(Some).ok_or(BoardParseError::Cell(c)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let row = ArrayVec::try_from(&row[..])
+                    .ok()
+                    .filter(ArrayVec::is_full)
+                    .ok_or(BoardParseError::Width(rows.len()))?;
+                rows.push(row);
+            }
+        }
+        if rows.len() > board.cells.len() {
+            return Err(BoardParseError::Height);
+        }
+        for (cells_x, row) in board.cells.iter_mut().rev().zip(rows.into_iter().rev()) {
+            *cells_x = row;
+        }
+        Ok(board)
+    }
+}
+
 impl Board {
+    #[cfg(feature = "std")]
+    pub fn new(randomizer: Randomizer) -> Self {
+        Self::from_rng(randomizer, StdRng::from_entropy())
+    }
+
+    /// Same piece sequence for the same seed and randomizer.
+    pub fn with_seed(randomizer: Randomizer, seed: u64) -> Self {
+        Self::from_rng(randomizer, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(mut randomizer: Randomizer, mut rng: StdRng) -> Self {
+        let mut cells = ArrayVec::new();
+        for _ in 0..40 {
+            cells.push(ArrayVec::from([None; 10]));
+        }
+        let next_pieces = (0..7).map(|_| randomizer.next(&mut rng)).collect();
+        Self {
+            cells,
+            next_pieces,
+            randomizer,
+            rng,
+            hold_piece: None,
+        }
+    }
+
     pub fn set_piece(&mut self, piece: &FallingPiece) -> Result<(), TetrisError> {
         let (x, y) = piece.piece_position;
         for (rel_x, rel_y) in piece.piece_state.get_cells().into_iter() {
@@ -83,9 +172,9 @@ impl Board {
 
     pub fn check_collision(&self, piece: PieceState, x: usize, y: usize) -> bool {
         for (rel_x, rel_y) in piece.get_cells().into_iter() {
-            if let Some(cells_x) = self.cells.clone().get_mut((-rel_y + y as i16) as usize) {
-                if let Some(cell) = cells_x.get_mut((rel_x + x as i16) as usize) {
-                    if let Some(_) = *cell {
+            if let Some(cells_x) = self.cells.get((-rel_y + y as i16) as usize) {
+                if let Some(cell) = cells_x.get((rel_x + x as i16) as usize) {
+                    if cell.is_some() {
                         return true;
                     }
                 } else {
@@ -100,25 +189,27 @@ impl Board {
 
     pub fn pop_next(&mut self) -> Piece {
         let next = self.next_pieces.remove(0);
-        self.next_pieces.push(self.bag.remove(0));
-        if self.bag.len() == 0 {
-            let mut rng = thread_rng();
-            let mut bag = vec![
-                Piece::I,
-                Piece::O,
-                Piece::T,
-                Piece::L,
-                Piece::J,
-                Piece::S,
-                Piece::Z,
-            ];
-            bag.shuffle(&mut rng);
-            self.bag = bag;
-        }
+        self.next_pieces.push(self.randomizer.next(&mut self.rng));
 
         next
     }
 
+    /// Replaces the queue with `pieces`, topped up from the randomizer.
+    pub fn set_queue(&mut self, pieces: &[Piece]) {
+        self.next_pieces = pieces.to_vec();
+        while self.next_pieces.len() < 7 {
+            self.next_pieces.push(self.randomizer.next(&mut self.rng));
+        }
+    }
+
+    /// Tops the queue up to `len` pieces from the randomizer. Taking pieces
+    /// keeps it that long.
+    pub fn extend_queue(&mut self, len: usize) {
+        while self.next_pieces.len() < len {
+            self.next_pieces.push(self.randomizer.next(&mut self.rng));
+        }
+    }
+
     pub fn line_clear(&mut self) -> Option<usize> {
         let mut cleared_lines = 0;
         for cells_x in self.cells.iter_mut() {
@@ -150,6 +241,68 @@ impl Board {
         lines
     }
 
+    pub fn push_row(&mut self, row: ArrayVec<Option<Cell>, 10>) -> bool {
+        let overflowed = self.cells[0].iter().any(|c| c.is_some());
+        self.cells.remove(0);
+        self.cells.push(row);
+        overflowed
+    }
+
+    /// A gray row with one empty cell. A `hole` past the right wall goes in
+    /// the last column.
+    pub fn garbage_row(hole: usize) -> ArrayVec<Option<Cell>, 10> {
+        let mut row = ArrayVec::from([Some(Cell::Glay); 10]);
+        row[hole.min(9)] = None;
+        row
+    }
+
+    /// Inserts `rows` garbage rows at the bottom, each with a hole at `hole`.
+    /// Returns true if blocks were pushed out of the top.
+    pub fn push_garbage(&mut self, rows: usize, hole: usize) -> bool {
+        let mut overflowed = false;
+        for _ in 0..rows {
+            overflowed |= self.push_row(Self::garbage_row(hole));
+        }
+        overflowed
+    }
+
+    /// 3-corner check. Mini unless both corners the T points at are filled.
+    pub fn get_t_spin(&self, piece: &FallingPiece) -> Option<TSpin> {
+        if piece.piece_state.get_kind() != Piece::T {
+            return None;
+        }
+        let (x, y) = piece.piece_position;
+        let occupied = |(dx, dy): (i16, i16)| {
+            let cx = x as i16 + dx;
+            let cy = y as i16 - dy;
+            cx < 0
+                || cy < 0
+                || self
+                    .cells
+                    .get(cy as usize)
+                    .and_then(|cells_x| cells_x.get(cx as usize))
+                    .is_none_or(|cell| cell.is_some())
+        };
+        let corners = [(-1, 1), (1, 1), (-1, -1), (1, -1)]
+            .into_iter()
+            .filter(|&c| occupied(c))
+            .count();
+        if corners < 3 {
+            return None;
+        }
+        let front = match piece.piece_state.get_rotation() {
+            Rotation::North => [(-1, 1), (1, 1)],
+            Rotation::East => [(1, 1), (1, -1)],
+            Rotation::South => [(-1, -1), (1, -1)],
+            Rotation::West => [(-1, 1), (-1, -1)],
+        };
+        if front.into_iter().all(occupied) {
+            Some(TSpin::Full)
+        } else {
+            Some(TSpin::Mini)
+        }
+    }
+
     pub fn swap_hold_piece(&mut self, piece: Piece) -> Option<Piece> {
         let original = self.hold_piece;
         self.hold_piece = Some(piece);
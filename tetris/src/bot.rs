@@ -0,0 +1,439 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use crate::{
+    find_placements_at_20g, find_placements_from, find_route, Board, FallingPiece, GameState,
+    Input, Move, PieceState, Placement, TSpin,
+};
+
+/// Placements kept after the first ply and looked ahead from with the next piece.
+const BEAM_WIDTH: usize = 6;
+
+/// Heuristic weights, positive is good.
+#[derive(Debug, Clone)]
+pub struct Weights {
+    /// Sum of column heights.
+    pub aggregate_height: f64,
+    /// Rows above half the visible board, on the tallest column.
+    pub danger_height: f64,
+    /// Empty cells with something above them.
+    pub holes: f64,
+    /// Sum of height differences between neighbouring columns.
+    pub bumpiness: f64,
+    /// Depth of every well but the deepest, which is kept for tetrises.
+    pub wells: f64,
+    /// Indexed by lines cleared.
+    pub clears: [f64; 5],
+    /// T-spins indexed by lines cleared, minis count as normal clears.
+    pub t_spins: [f64; 4],
+    /// Open T-spin double slots.
+    pub t_slots: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            aggregate_height: -0.5,
+            danger_height: -2.0,
+            holes: -3.0,
+            bumpiness: -0.2,
+            wells: -0.3,
+            clears: [0.0, 0.2, 0.8, 1.5, 8.0],
+            t_spins: [0.5, 3.0, 8.0, 10.0],
+            t_slots: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Plan {
+    hold: bool,
+    route: Route,
+    occupancy: Vec<u16>,
+}
+
+/// Plays any `GameState` by feeding it one `Input` per frame. Picks a
+/// placement with a one-piece lookahead when a piece spawns, then steers the
+/// piece there, re-routing from wherever it actually is whenever gravity up
+/// to 20G throws it off.
+#[derive(Debug, Clone, Default)]
+pub struct Bot {
+    weights: Weights,
+    plan: Option<Plan>,
+    previous: Input,
+    hold_disabled: bool,
+    lookahead_disabled: bool,
+}
+
+impl Bot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_weights(weights: Weights) -> Self {
+        Self {
+            weights,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_weights(&self) -> &Weights {
+        &self.weights
+    }
+
+    /// Without the lookahead it plays worse but plans several times faster,
+    /// for modes running lots of bots at once.
+    pub fn set_lookahead(&mut self, lookahead: bool) {
+        self.lookahead_disabled = !lookahead;
+    }
+
+    /// The placement it is heading for, if any.
+    pub fn get_target(&self) -> Option<&Placement> {
+        self.plan.as_ref().map(|plan| plan.route.get_target())
+    }
+
+    pub fn next_input<G: GameState>(&mut self, game: &G) -> Input {
+        let input = self.choose_input(game);
+        self.previous = input;
+        input
+    }
+
+    fn choose_input<G: GameState>(&mut self, game: &G) -> Input {
+        let Some(current) = game.get_current_piece() else {
+            self.plan = None;
+            return Input::default();
+        };
+        let board = game.get_board();
+        let occupancy = occupancy(&board);
+        if self.plan.as_ref().is_none_or(|p| p.occupancy != occupancy) {
+            self.plan = self.make_plan(game, &board, current, occupancy);
+        }
+        let Some(plan) = self.plan.as_mut() else {
+            return self.tap(None);
+        };
+
+        let kind = current.piece_state.get_kind();
+        if plan.hold && kind != plan.route.get_target().piece.piece_state.get_kind() {
+            if self.previous.hold {
+                // the mode has no hold, or it was used up already
+                self.hold_disabled = true;
+                self.plan = None;
+                return Input::default();
+            }
+            return Input {
+                hold: true,
+                ..Default::default()
+            };
+        }
+
+        if let Some(input) = plan.route.steer(&board, current, self.previous) {
+            return input;
+        }
+        // knocked off course, settle for the best spot reachable from here
+        let twenty_g = current.check_shift_collision(&board, 0, 1);
+        let placements = search(&board, current, twenty_g);
+        let Some(target) = self.best_placement(&board, placements) else {
+            return self.tap(None);
+        };
+        let mut route = Route::new(target);
+        let input = route.steer(&board, current, self.previous);
+        if let Some(plan) = self.plan.as_mut() {
+            plan.hold = false;
+            plan.route = route;
+        }
+        input.unwrap_or_else(|| self.tap(None))
+    }
+
+    fn tap(&self, m: Option<Move>) -> Input {
+        tap(self.previous, m)
+    }
+
+    fn make_plan<G: GameState>(
+        &self,
+        game: &G,
+        board: &Board,
+        current: FallingPiece,
+        occupancy: Vec<u16>,
+    ) -> Option<Plan> {
+        // a piece resting as soon as it spawns means 20G
+        let twenty_g = current.check_shift_collision(board, 0, 1);
+        let hold = game.get_hold();
+        // pieces rebuilt from the queue spawn as big as the current one
+        let big = current.piece_state.is_big();
+        let mut candidates = vec![(false, search(board, current, twenty_g), game.get_next())];
+        if !self.hold_disabled {
+            let (held, following) = match hold {
+                Some(hold) => (hold, game.get_next()),
+                None => (game.get_next(), game.get_next_next()),
+            };
+            if held != current.piece_state.get_kind() {
+                let start =
+                    FallingPiece::from_piece_state(PieceState::from_piece(held).with_big(big));
+                candidates.push((true, search(board, start, twenty_g), following));
+            }
+        }
+
+        let mut scored = vec![];
+        for (hold, placements, following) in candidates {
+            for placement in placements {
+                let (after, score) = self.evaluate(board, &placement);
+                scored.push((score, hold, placement, after, following));
+            }
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        if self.lookahead_disabled {
+            return scored
+                .into_iter()
+                .next()
+                .map(|(_, hold, target, _, _)| Plan {
+                    hold,
+                    route: Route::new(target),
+                    occupancy,
+                });
+        }
+        scored.truncate(BEAM_WIDTH);
+
+        scored
+            .into_iter()
+            .map(|(score, hold, placement, after, following)| {
+                let start =
+                    FallingPiece::from_piece_state(PieceState::from_piece(following).with_big(big));
+                let lookahead = search(&after, start, twenty_g)
+                    .iter()
+                    .map(|p| self.evaluate(&after, p).1)
+                    .fold(f64::MIN, f64::max);
+                (score + lookahead, hold, placement)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, hold, target)| Plan {
+                hold,
+                route: Route::new(target),
+                occupancy,
+            })
+    }
+
+    fn best_placement(&self, board: &Board, placements: Vec<Placement>) -> Option<Placement> {
+        placements
+            .into_iter()
+            .map(|p| (self.evaluate(board, &p).1, p))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, p)| p)
+    }
+
+    /// Locks `placement` on a copy of `board` and scores the result.
+    fn evaluate(&self, board: &Board, placement: &Placement) -> (Board, f64) {
+        let w = &self.weights;
+        let mut board = board.clone();
+        if board.set_piece(&placement.piece).is_err() {
+            return (board, f64::MIN);
+        }
+        let lines = board.line_clear().unwrap_or(0);
+        board.line_shrink();
+
+        let mut score = match placement.t_spin {
+            Some(TSpin::Full) => w.t_spins[lines.min(3)],
+            _ => w.clears[lines.min(4)],
+        };
+
+        let height = board.cells.len();
+        let mut heights = [0; 10];
+        let mut holes = 0;
+        for (x, column_height) in heights.iter_mut().enumerate() {
+            if let Some(top) = board.cells.iter().position(|row| row[x].is_some()) {
+                *column_height = height - top;
+                holes += board.cells[top..]
+                    .iter()
+                    .filter(|row| row[x].is_none())
+                    .count();
+            }
+        }
+        let max_height = heights.iter().copied().max().unwrap_or(0);
+        let bumpiness = heights
+            .windows(2)
+            .map(|w| w[0].abs_diff(w[1]))
+            .sum::<usize>();
+        let mut wells = (0..10)
+            .map(|x| {
+                let left = if x == 0 { height } else { heights[x - 1] };
+                let right = if x == 9 { height } else { heights[x + 1] };
+                left.min(right).saturating_sub(heights[x])
+            })
+            .collect::<Vec<_>>();
+        wells.sort_unstable();
+        wells.pop();
+
+        score += w.aggregate_height * heights.iter().sum::<usize>() as f64
+            + w.danger_height * max_height.saturating_sub(10) as f64
+            + w.holes * holes as f64
+            + w.bumpiness * bumpiness as f64
+            + w.wells * wells.iter().sum::<usize>() as f64
+            + w.t_slots * t_slots(&board) as f64;
+        (board, score)
+    }
+}
+
+/// The way to a placement, searched once and then played a tap at a time.
+/// It is only searched again when the piece isn't where the moves left
+/// expect and they no longer get it there, after gravity or a missed tap.
+#[derive(Debug, Clone)]
+pub(crate) struct Route {
+    target: Placement,
+    moves: VecDeque<Move>,
+    // where the piece should be before `moves[0]`, `None` until searched
+    piece: Option<FallingPiece>,
+    twenty_g: bool,
+    last_rotation: bool,
+}
+
+impl Route {
+    pub(crate) fn new(target: Placement) -> Self {
+        Self {
+            target,
+            moves: VecDeque::new(),
+            piece: None,
+            twenty_g: false,
+            last_rotation: false,
+        }
+    }
+
+    pub(crate) fn get_target(&self) -> &Placement {
+        &self.target
+    }
+
+    /// Next input bringing `current` to the target, or `None` if it can't
+    /// get there from where it is. `previous` is the input sent last frame.
+    pub(crate) fn steer(
+        &mut self,
+        board: &Board,
+        current: FallingPiece,
+        previous: Input,
+    ) -> Option<Input> {
+        if self.piece != Some(current) && !self.leads_to_target(board, current) {
+            // a piece resting where it is means 20G
+            self.twenty_g = current.check_shift_collision(board, 0, 1);
+            let moves = find_route(board, current, &self.target, self.twenty_g)?;
+            self.moves = moves.into();
+            self.last_rotation = false;
+        }
+        self.piece = Some(current);
+        // a sonic drop is done once the piece rests
+        while self.moves.front() == Some(&Move::SonicDrop)
+            && current.check_shift_collision(board, 0, 1)
+        {
+            self.moves.pop_front();
+        }
+
+        let m = match self.moves.make_contiguous() {
+            // a hard drop gets there just the same, without waiting on soft drop speed
+            [] | [Move::SonicDrop] => return Some(tap(previous, None)),
+            [m, ..] => *m,
+        };
+        let input = tap(previous, Some(m));
+        let pressed = match m {
+            Move::Left => input.left,
+            Move::Right => input.right,
+            Move::Cw => input.cw,
+            Move::Ccw => input.ccw,
+            Move::Down => true,
+            // held until the piece rests
+            Move::SonicDrop => false,
+        };
+        if pressed {
+            let mut piece = current;
+            self.play(board, &mut piece, m);
+            self.piece = Some(piece);
+            self.last_rotation = matches!(m, Move::Cw | Move::Ccw);
+            self.moves.pop_front();
+        }
+        Some(input)
+    }
+
+    fn play(&self, board: &Board, piece: &mut FallingPiece, m: Move) -> bool {
+        let moved = m.apply(board, piece);
+        if self.twenty_g {
+            Move::SonicDrop.apply(board, piece);
+        }
+        moved
+    }
+
+    // whether the moves left still get there from `current`
+    fn leads_to_target(&self, board: &Board, current: FallingPiece) -> bool {
+        if self.piece.is_none() {
+            return false;
+        }
+        let mut piece = current;
+        for &m in self.moves.iter() {
+            if !self.play(board, &mut piece, m) && m != Move::SonicDrop {
+                return false;
+            }
+        }
+        let last_rotation = match self.moves.back() {
+            Some(m) => matches!(m, Move::Cw | Move::Ccw),
+            None => self.last_rotation,
+        };
+        let resting = piece.check_shift_collision(board, 0, 1);
+        if self.target.t_spin.is_some() && !(resting && last_rotation) {
+            return false;
+        }
+        Move::SonicDrop.apply(board, &mut piece);
+        self.target.is_at(&piece)
+    }
+}
+
+/// Presses what `m` needs, hard drop for `None`. Buttons that were held
+/// last frame are released first so the press registers.
+pub(crate) fn tap(previous: Input, m: Option<Move>) -> Input {
+    let mut input = Input::default();
+    match m {
+        Some(Move::Left) => input.left = !previous.left,
+        Some(Move::Right) => input.right = !previous.right,
+        Some(Move::Cw) => input.cw = !previous.cw,
+        Some(Move::Ccw) => input.ccw = !previous.ccw,
+        Some(Move::Down | Move::SonicDrop) => input.soft_drop = true,
+        None => input.hard_drop = !previous.hard_drop,
+    }
+    input
+}
+
+/// Every resting placement reachable from `start`, as the piece falls at 20G
+/// or not.
+pub(crate) fn search(board: &Board, start: FallingPiece, twenty_g: bool) -> Vec<Placement> {
+    if twenty_g {
+        find_placements_at_20g(board, start)
+    } else {
+        find_placements_from(board, start)
+    }
+}
+
+/// One bit per filled cell, used to notice that a piece locked.
+pub(crate) fn occupancy(board: &Board) -> Vec<u16> {
+    board
+        .cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_some())
+                .fold(0, |bits, (x, _)| bits | 1 << x)
+        })
+        .collect()
+}
+
+/// Spots where a T pointing down would fit with both bottom corners filled
+/// and at least one top corner hanging over it.
+fn t_slots(board: &Board) -> usize {
+    let filled = |x: usize, y: usize| board.cells.get(y).is_none_or(|row| row[x].is_some());
+    let mut slots = 0;
+    for y in 1..board.cells.len() - 1 {
+        for x in 1..9 {
+            let fits = !filled(x - 1, y) && !filled(x, y) && !filled(x + 1, y) && !filled(x, y + 1);
+            if fits
+                && filled(x - 1, y + 1)
+                && filled(x + 1, y + 1)
+                && (filled(x - 1, y - 1) || filled(x + 1, y - 1))
+            {
+                slots += 1;
+            }
+        }
+    }
+    slots
+}
@@ -1,7 +1,7 @@
 use crate::Piece;
-use std::fmt::Display;
+use core::fmt::Display;
 
-#[cfg(not(windows))]
+#[cfg(all(feature = "std", not(windows)))]
 use termion::{color, style};
 
 #[derive(Debug, Clone, Copy)]
@@ -34,11 +34,41 @@ impl From<Piece> for Cell {
     }
 }
 
+impl Cell {
+    /// The letter of the piece with this color, `G` for gray, `B` for black
+    /// and `W` for white.
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::Cyan => 'I',
+            Cell::Yellow => 'O',
+            Cell::Purple => 'T',
+            Cell::Orange => 'L',
+            Cell::Blue => 'J',
+            Cell::Green => 'S',
+            Cell::Red => 'Z',
+            Cell::Glay => 'G',
+            Cell::Black => 'B',
+            Cell::White => 'W',
+        }
+    }
+
+    /// Also takes `#` for gray.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'G' | '#' => Some(Cell::Glay),
+            'B' => Some(Cell::Black),
+            'W' => Some(Cell::White),
+            c => Piece::from_char(c).map(Cell::from),
+        }
+    }
+}
+
 impl Display for Cell {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         use Cell::*;
 
-        #[cfg(not(windows))]
+        // plain letters where there's no terminal to color
+        #[cfg(all(feature = "std", not(windows)))]
         match self {
             Black => write!(f, "{}B{}", color::Fg(color::Black), style::Reset),
             White => write!(f, "{}W{}", color::Fg(color::White), style::Reset),
@@ -52,7 +82,7 @@ impl Display for Cell {
             Glay => write!(f, "{}G{}", color::Fg(color::LightBlack), style::Reset),
         }
 
-        #[cfg(windows)]
+        #[cfg(any(not(feature = "std"), windows))]
         match self {
             Black => write!(f, "B"),
             White => write!(f, "W"),
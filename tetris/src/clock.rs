@@ -0,0 +1,66 @@
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Play time behind the section and torikan timers of the TGM modes.
+#[derive(Debug, Clone, Copy)]
+pub enum Clock {
+    /// Wall time since the clock was made.
+    #[cfg(feature = "std")]
+    Wall(Instant),
+    /// Updates counted at 60 fps, so slowdown doesn't count.
+    Frames(u64),
+    /// Time read from a platform timer, relative to `start`.
+    Source {
+        now: fn() -> Duration,
+        start: Duration,
+    },
+}
+
+/// The wall clock with `std`.
+#[cfg(feature = "std")]
+impl Default for Clock {
+    fn default() -> Self {
+        Self::wall()
+    }
+}
+
+/// Counted frames without `std`.
+#[cfg(not(feature = "std"))]
+impl Default for Clock {
+    fn default() -> Self {
+        Self::frames()
+    }
+}
+
+impl Clock {
+    #[cfg(feature = "std")]
+    pub fn wall() -> Self {
+        Self::Wall(Instant::now())
+    }
+
+    pub fn frames() -> Self {
+        Self::Frames(0)
+    }
+
+    pub fn from_source(now: fn() -> Duration) -> Self {
+        Self::Source { now, start: now() }
+    }
+
+    /// Called by the modes once per update.
+    pub fn tick(&mut self) {
+        if let Self::Frames(frames) = self {
+            *frames += 1;
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match *self {
+            #[cfg(feature = "std")]
+            Self::Wall(start) => start.elapsed(),
+            Self::Frames(frames) => Duration::from_secs(frames) / 60,
+            Self::Source { now, start } => now().saturating_sub(start),
+        }
+    }
+}
@@ -0,0 +1,450 @@
+//! Reinforcement learning environment in the usual reset/step shape. Runs
+//! headless, and `VecEnv` steps many of them on all cores.
+
+use std::thread;
+
+use crate::{
+    bot::{search, tap, Route},
+    Board, FallingPiece, Game, GameState, Input, Piece, PieceState, Placement, Randomizer, Status,
+    TSpin, TetrisEvent,
+};
+
+/// Frames a placement action may take before the piece is hard dropped
+/// wherever it is.
+pub const MAX_PLACEMENT_FRAMES: usize = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Buttons held for one frame.
+    Input(Input),
+    /// Index into `Env::legal_placements`. Plays frames until the piece
+    /// locks and the next one spawns.
+    Place(usize),
+}
+
+/// A placement action, possibly swapping in the hold piece first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub hold: bool,
+    pub placement: Placement,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    /// Filled cells, row 0 at the top like `Board::cells`.
+    pub board: [[bool; 10]; 40],
+    pub current: Option<FallingPiece>,
+    pub hold: Option<Piece>,
+    pub queue: [Piece; 3],
+}
+
+/// Added up into the reward of each step.
+#[derive(Debug, Clone)]
+pub struct Rewards {
+    /// Indexed by lines cleared at once.
+    pub lines: [f64; 5],
+    /// T-spins indexed by lines cleared, on top of `lines`.
+    pub t_spins: [f64; 4],
+    /// For every piece locked.
+    pub survival: f64,
+    /// Times the change in the number of covered empty cells.
+    pub holes: f64,
+    pub game_over: f64,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self {
+            lines: [0.0, 1.0, 3.0, 5.0, 8.0],
+            t_spins: [0.0; 4],
+            survival: 0.01,
+            holes: 0.0,
+            game_over: -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Info {
+    /// Frames this step took.
+    pub frames: usize,
+    pub lines_cleared: usize,
+    pub t_spin: Option<TSpin>,
+    pub pieces_locked: usize,
+    pub total_frames: usize,
+    pub total_lines: usize,
+    pub total_pieces: usize,
+    /// Ended by `max_frames` rather than by the game.
+    pub truncated: bool,
+}
+
+type Factory<G> = Box<dyn FnMut(u64) -> G + Send>;
+
+/// One game driven by actions. `G` is any mode, made fresh by the factory
+/// from the seed passed to `reset`.
+pub struct Env<G: GameState = Game> {
+    factory: Factory<G>,
+    game: G,
+    rewards: Rewards,
+    hold: bool,
+    max_frames: Option<usize>,
+    previous: Input,
+    candidates: Option<Vec<Candidate>>,
+    /// Waiting for the line clear that decides what the T-spin is worth.
+    t_spin: Option<TSpin>,
+    done: bool,
+    frames: usize,
+    lines: usize,
+    pieces: usize,
+}
+
+impl Default for Env<Game> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Env<Game> {
+    /// Free play with `Game`'s default timings.
+    pub fn new() -> Self {
+        Self::with_factory(|seed| Game::from_board(Board::with_seed(Randomizer::default(), seed)))
+    }
+}
+
+impl<G: GameState> Env<G> {
+    pub fn with_factory(mut factory: impl FnMut(u64) -> G + Send + 'static) -> Self {
+        let game = factory(0);
+        Self {
+            factory: Box::new(factory),
+            game,
+            rewards: Rewards::default(),
+            hold: true,
+            max_frames: None,
+            previous: Input::default(),
+            candidates: None,
+            t_spin: None,
+            done: false,
+            frames: 0,
+            lines: 0,
+            pieces: 0,
+        }
+    }
+
+    pub fn set_rewards(&mut self, rewards: Rewards) {
+        self.rewards = rewards;
+    }
+
+    pub fn get_rewards(&self) -> &Rewards {
+        &self.rewards
+    }
+
+    /// Whether placements through hold are offered, off for modes without it.
+    pub fn set_hold(&mut self, hold: bool) {
+        self.hold = hold;
+        self.candidates = None;
+    }
+
+    /// Ends episodes after this many frames.
+    pub fn set_max_frames(&mut self, max_frames: Option<usize>) {
+        self.max_frames = max_frames;
+    }
+
+    pub fn get_game(&self) -> &G {
+        &self.game
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game = (self.factory)(seed);
+        self.previous = Input::default();
+        self.candidates = None;
+        self.t_spin = None;
+        self.done = false;
+        self.frames = 0;
+        self.lines = 0;
+        self.pieces = 0;
+        self.observe()
+    }
+
+    /// After the episode is done this returns the last observation again
+    /// with no reward.
+    ///
+    /// # Panics
+    ///
+    /// For `Action::Place` with an index not below `legal_placements().len()`.
+    pub fn step(&mut self, action: Action) -> (Observation, f64, bool, Info) {
+        let mut info = Info::default();
+        let mut reward = 0.0;
+        if !self.done {
+            let holes_before = holes(&self.game.get_board());
+            match action {
+                Action::Input(input) => reward += self.frame(input, &mut info),
+                Action::Place(index) => reward += self.place(index, &mut info),
+            }
+            let holes_after = holes(&self.game.get_board());
+            reward += self.rewards.holes * (holes_after as f64 - holes_before as f64);
+        }
+        info.total_frames = self.frames;
+        info.total_lines = self.lines;
+        info.total_pieces = self.pieces;
+        (self.observe(), reward, self.done, info)
+    }
+
+    /// Where the current piece, or the hold piece instead, can be placed.
+    /// Empty while no piece is falling.
+    pub fn legal_placements(&mut self) -> &[Candidate] {
+        if self.candidates.is_none() {
+            self.candidates = Some(self.find_candidates());
+        }
+        self.candidates.as_deref().unwrap()
+    }
+
+    fn find_candidates(&self) -> Vec<Candidate> {
+        let Some(current) = self.game.get_current_piece() else {
+            return vec![];
+        };
+        let board = self.game.get_board();
+        let twenty_g = current.check_shift_collision(&board, 0, 1);
+        let candidates = search(&board, current, twenty_g)
+            .into_iter()
+            .map(|placement| Candidate {
+                hold: false,
+                placement,
+            });
+        let held = self.game.get_hold().unwrap_or(self.game.get_next());
+        let holding = if self.hold && held != current.piece_state.get_kind() {
+            let start = FallingPiece::from_piece_state(
+                PieceState::from_piece(held).with_big(current.piece_state.is_big()),
+            );
+            search(&board, start, twenty_g)
+        } else {
+            vec![]
+        };
+        candidates
+            .chain(holding.into_iter().map(|placement| Candidate {
+                hold: true,
+                placement,
+            }))
+            .collect()
+    }
+
+    fn place(&mut self, index: usize, info: &mut Info) -> f64 {
+        let candidates = self.legal_placements();
+        assert!(
+            index < candidates.len(),
+            "placement {index} out of {}",
+            candidates.len()
+        );
+        let Candidate { hold, placement } = candidates[index].clone();
+        let target_kind = placement.piece.piece_state.get_kind();
+        let mut route = Route::new(placement);
+        let mut reward = 0.0;
+        for _ in 0..MAX_PLACEMENT_FRAMES {
+            let Some(current) = self.game.get_current_piece() else {
+                break;
+            };
+            let input = if hold && current.piece_state.get_kind() != target_kind {
+                if self.previous.hold {
+                    // hold wasn't available after all
+                    tap(self.previous, None)
+                } else {
+                    Input {
+                        hold: true,
+                        ..Default::default()
+                    }
+                }
+            } else {
+                route
+                    .steer(&self.game.get_board(), current, self.previous)
+                    .unwrap_or_else(|| tap(self.previous, None))
+            };
+            reward += self.frame(input, info);
+            if self.done || info.pieces_locked > 0 {
+                break;
+            }
+        }
+        // give the next observation a piece to place
+        let mut waited = 0;
+        while !self.done && self.game.get_current_piece().is_none() {
+            if waited == MAX_PLACEMENT_FRAMES {
+                // no more pieces coming, the mode has ended
+                self.done = true;
+                break;
+            }
+            reward += self.frame(Input::default(), info);
+            waited += 1;
+        }
+        reward
+    }
+
+    fn frame(&mut self, input: Input, info: &mut Info) -> f64 {
+        self.game.set_input(input);
+        self.game.update();
+        self.previous = input;
+        self.candidates = None;
+        self.frames += 1;
+        info.frames += 1;
+        self.game.get_sound_queue().clear();
+
+        let rewards = &self.rewards;
+        let mut reward = 0.0;
+        for event in self.game.get_event_queue().drain(..) {
+            match event {
+                TetrisEvent::LineCleared(lines) => {
+                    reward += rewards.lines[lines.min(4)];
+                    if let Some(TSpin::Full) = self.t_spin.take() {
+                        reward += rewards.t_spins[lines.min(3)];
+                    }
+                    info.lines_cleared += lines;
+                    self.lines += lines;
+                }
+                TetrisEvent::PieceSpawned(_) => {
+                    if let Some(TSpin::Full) = self.t_spin.take() {
+                        reward += rewards.t_spins[0];
+                    }
+                }
+                TetrisEvent::TSpin(t_spin) => {
+                    info.t_spin = Some(t_spin);
+                    self.t_spin = Some(t_spin);
+                }
+                TetrisEvent::PieceLocked(_) => {
+                    reward += rewards.survival;
+                    info.pieces_locked += 1;
+                    self.pieces += 1;
+                }
+                TetrisEvent::ToppedOut => {
+                    reward += rewards.game_over;
+                    self.done = true;
+                }
+                _ => {}
+            }
+        }
+        // modes also end on their own goals and time limits
+        if !self.done && matches!(self.game.get_status(), Status::End) {
+            self.done = true;
+        }
+        if !self.done && self.max_frames.is_some_and(|max| self.frames >= max) {
+            info.truncated = true;
+            self.done = true;
+        }
+        reward
+    }
+
+    fn observe(&self) -> Observation {
+        let board = self.game.get_board();
+        let mut cells = [[false; 10]; 40];
+        for (row, board_row) in cells.iter_mut().zip(&board.cells) {
+            for (cell, board_cell) in row.iter_mut().zip(board_row) {
+                *cell = board_cell.is_some();
+            }
+        }
+        Observation {
+            board: cells,
+            current: self.game.get_current_piece(),
+            hold: self.game.get_hold(),
+            queue: [
+                self.game.get_next(),
+                self.game.get_next_next(),
+                self.game.get_next_next_next(),
+            ],
+        }
+    }
+}
+
+/// Empty cells with something above them.
+fn holes(board: &Board) -> usize {
+    (0..10)
+        .map(|x| {
+            board
+                .cells
+                .iter()
+                .skip_while(|row| row[x].is_none())
+                .filter(|row| row[x].is_none())
+                .count()
+        })
+        .sum()
+}
+
+/// Many environments stepped together, split across threads.
+pub struct VecEnv<G: GameState = Game> {
+    envs: Vec<Env<G>>,
+}
+
+impl<G: GameState + Send> VecEnv<G> {
+    pub fn new(envs: Vec<Env<G>>) -> Self {
+        Self { envs }
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> &Env<G> {
+        &self.envs[i]
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> &mut Env<G> {
+        &mut self.envs[i]
+    }
+
+    /// One seed per environment.
+    pub fn reset(&mut self, seeds: &[u64]) -> Vec<Observation> {
+        assert_eq!(seeds.len(), self.envs.len());
+        self.par_map(|env, i| env.reset(seeds[i]))
+    }
+
+    /// `Env::legal_placements` of every environment, searched in parallel.
+    pub fn legal_placements(&mut self) -> Vec<Vec<Candidate>> {
+        self.par_map(|env, _| env.legal_placements().to_vec())
+    }
+
+    /// One action per environment. Ones that finish are reset right away
+    /// with the seed from `reseed`, so the observation that comes with
+    /// `done` is already the first of the next episode.
+    pub fn step(
+        &mut self,
+        actions: &[Action],
+        reseed: impl Fn(usize) -> u64 + Sync,
+    ) -> Vec<(Observation, f64, bool, Info)> {
+        assert_eq!(actions.len(), self.envs.len());
+        self.par_map(|env, i| {
+            let (observation, reward, done, info) = env.step(actions[i]);
+            if done {
+                return (env.reset(reseed(i)), reward, done, info);
+            }
+            (observation, reward, done, info)
+        })
+    }
+
+    fn par_map<T: Send>(&mut self, f: impl Fn(&mut Env<G>, usize) -> T + Sync) -> Vec<T> {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk = self.envs.len().div_ceil(threads).max(1);
+        let f = &f;
+        thread::scope(|scope| {
+            let handles = self
+                .envs
+                .chunks_mut(chunk)
+                .enumerate()
+                .map(|(c, envs)| {
+                    scope.spawn(move || {
+                        envs.iter_mut()
+                            .enumerate()
+                            .map(|(i, env)| f(env, c * chunk + i))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+}
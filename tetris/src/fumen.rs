@@ -0,0 +1,643 @@
+//! Fumen (v115) import and export, the board format of the fumen editor at
+//! harddrop.com and knewjade's fumen viewer.
+//!
+//! Fumen only knows the bottom 23 rows and a garbage row under them. Cells
+//! above that are dropped on export, and the garbage row is ignored on
+//! import. The hold piece and the queue travel in a `#Q=[H](C)NEXT` quiz
+//! comment, as the fumen editor writes them.
+
+use thiserror::Error;
+
+use crate::{Board, Cell, FallingPiece, Piece, PieceState, Rotation};
+
+const FIELD_WIDTH: usize = 10;
+const FIELD_TOP: i16 = 23;
+const FIELD_BLOCKS: usize = (FIELD_TOP as usize + 1) * FIELD_WIDTH;
+const BOARD_HEIGHT: i16 = 40;
+
+const ENCODE_TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const COMMENT_TABLE: &[u8] =
+    b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+const COMMENT_CHARS: u32 = 96;
+
+#[derive(Error, Debug)]
+pub enum FumenError {
+    #[error("not a v115 fumen")]
+    Version,
+    #[error("invalid character {0:?}")]
+    Character(char),
+    #[error("unexpected end of data")]
+    Truncated,
+    #[error("invalid data")]
+    Data,
+}
+
+/// One page of a fumen.
+#[derive(Debug, Clone)]
+pub struct FumenPage {
+    /// The field before the page's piece locks, with the hold piece and the
+    /// queue from a quiz comment. Without one the queue starts with the
+    /// page's piece.
+    pub board: Board,
+    pub piece: Option<FallingPiece>,
+    pub comment: String,
+}
+
+/// `board` as a one page fumen with `piece` drawn on it. With `queue` the
+/// hold piece and the queue go in a quiz comment, `piece` or else the first
+/// of `next_pieces` being the current piece.
+pub fn board_to_fumen(board: &Board, piece: Option<&FallingPiece>, queue: bool) -> String {
+    let comment = if queue {
+        quiz_comment(board, piece.map(|piece| piece.piece_state.get_kind()))
+    } else {
+        String::new()
+    };
+    pages_to_fumen(&[FumenPage {
+        board: board.clone(),
+        piece: piece.copied(),
+        comment,
+    }])
+}
+
+/// Pages are written with their piece locking, so a page that only drops a
+/// piece costs a few characters.
+pub fn pages_to_fumen(pages: &[FumenPage]) -> String {
+    let mut values = Values::default();
+    let mut prev_field = [0; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    let mut repeat_index = None;
+
+    for (i, page) in pages.iter().enumerate() {
+        let field = field_from_board(&page.board);
+        let (runs, changed) = encode_field(&prev_field, &field);
+        // a field that didn't change is written once, followed by how many
+        // pages after it keep it too
+        match repeat_index {
+            _ if changed => {
+                values.0.extend(runs.0);
+                repeat_index = None;
+            }
+            Some(index) if values.0[index] < ENCODE_TABLE.len() as u8 - 1 => {
+                values.0[index] += 1;
+            }
+            _ => {
+                values.0.extend(runs.0);
+                values.push(0, 1);
+                repeat_index = Some(values.0.len() - 1);
+            }
+        }
+
+        let action = page.piece.and_then(action_from_piece);
+        let comment = escape(&page.comment);
+        let comment_changed = page.comment != prev_comment;
+        let (kind, rotation, position) = action.unwrap_or((0, 0, 0));
+        let flags = [
+            false,           // !lock
+            comment_changed, // comment
+            i == 0,          // colorize
+            false,           // mirror
+            false,           // rise
+        ];
+        let flags = flags.iter().fold(0, |value, &flag| value * 2 + flag as u32);
+        let value = ((flags * FIELD_BLOCKS as u32 + position) * 4 + rotation) * 8 + kind;
+        values.push(value, 3);
+
+        if comment_changed {
+            let comment = comment.as_bytes();
+            let len = comment.len().min(4095);
+            values.push(len as u32, 2);
+            for chunk in comment[..len].chunks(4) {
+                let value = chunk.iter().rev().fold(0, |value, &c| {
+                    let index = COMMENT_TABLE.iter().position(|&t| t == c).unwrap_or(0);
+                    value * COMMENT_CHARS + index as u32
+                });
+                values.push(value, 5);
+            }
+            prev_comment = page.comment.clone();
+        }
+
+        prev_field = field;
+        if let Some((kind, rotation, position)) = action {
+            lock_piece(&mut prev_field, kind, rotation, position);
+            if let Some(quiz) = next_quiz(&prev_comment, piece_from_fumen(kind)) {
+                prev_comment = quiz;
+            }
+        }
+        clear_lines(&mut prev_field);
+    }
+
+    // the fumen editor breaks the data up like this
+    let data = values.to_string();
+    let mut fumen = String::from("v115@");
+    for (i, c) in data.chars().enumerate() {
+        if i >= 42 && (i - 42) % 47 == 0 && data.len() >= 41 {
+            fumen.push('?');
+        }
+        fumen.push(c);
+    }
+    fumen
+}
+
+/// The first page of `data`.
+pub fn board_from_fumen(data: &str) -> Result<FumenPage, FumenError> {
+    pages_from_fumen(data)?
+        .into_iter()
+        .next()
+        .ok_or(FumenError::Data)
+}
+
+pub fn pages_from_fumen(data: &str) -> Result<Vec<FumenPage>, FumenError> {
+    let data = data.trim();
+    let data = ["v115@", "m115@", "d115@", "D115@"]
+        .iter()
+        .find_map(|prefix| data.strip_prefix(prefix))
+        .ok_or(FumenError::Version)?;
+    let mut values = Values::parse(data)?;
+    if values.is_empty() {
+        return Err(FumenError::Truncated);
+    }
+
+    let mut pages = vec![];
+    let mut prev_field = [0; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    let mut repeat = 0;
+    while !values.is_empty() {
+        let mut field = prev_field;
+        if repeat > 0 {
+            repeat -= 1;
+        } else if !decode_field(&mut values, &mut field)? {
+            repeat = values.poll(1)?;
+        }
+
+        let action = values.poll(3)?;
+        let kind = action % 8;
+        let rotation = action / 8 % 4;
+        let position = action / 32 % FIELD_BLOCKS as u32;
+        let flags = action / 32 / FIELD_BLOCKS as u32;
+        let rise = flags & 1 != 0;
+        let mirror = flags & 2 != 0;
+        let comment_changed = flags & 8 != 0;
+        let lock = flags & 16 == 0;
+        if comment_changed {
+            let len = values.poll(2)? as usize;
+            let mut comment = String::new();
+            for _ in 0..len.div_ceil(4) {
+                let mut value = values.poll(5)?;
+                for _ in 0..4 {
+                    let c = COMMENT_TABLE.get((value % COMMENT_CHARS) as usize);
+                    comment.push(c.map_or(' ', |&c| c as char));
+                    value /= COMMENT_CHARS;
+                }
+            }
+            comment.truncate(len);
+            prev_comment = unescape(&comment);
+        }
+
+        let piece = (1..8)
+            .contains(&kind)
+            .then(|| piece_from_action(kind, rotation, position))
+            .flatten();
+        let board = board_from_field(&field, piece, &prev_comment);
+        pages.push(FumenPage {
+            board,
+            piece,
+            comment: prev_comment.clone(),
+        });
+
+        if lock {
+            if (1..8).contains(&kind) {
+                lock_piece(&mut field, kind, rotation, position);
+                // pages without a comment of their own carry on the quiz
+                if let Some(quiz) = next_quiz(&prev_comment, piece_from_fumen(kind)) {
+                    prev_comment = quiz;
+                }
+            }
+            clear_lines(&mut field);
+            if rise {
+                field.copy_within(FIELD_WIDTH.., 0);
+                field[FIELD_BLOCKS - FIELD_WIDTH..].fill(0);
+            }
+            if mirror {
+                for row in field[..FIELD_BLOCKS - FIELD_WIDTH].chunks_mut(FIELD_WIDTH) {
+                    row.reverse();
+                }
+            }
+        }
+        prev_field = field;
+    }
+    Ok(pages)
+}
+
+/// Base64 digits, least significant first.
+#[derive(Default)]
+struct Values(Vec<u8>);
+
+impl Values {
+    fn parse(data: &str) -> Result<Self, FumenError> {
+        let values = data
+            .chars()
+            .filter(|&c| c != '?')
+            .map(|c| {
+                ENCODE_TABLE
+                    .iter()
+                    .position(|&t| t as char == c)
+                    .map(|value| value as u8)
+                    .ok_or(FumenError::Character(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(values.into_iter().rev().collect()))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn poll(&mut self, digits: usize) -> Result<u32, FumenError> {
+        (0..digits)
+            .try_fold((0, 1), |(value, unit), _| {
+                let digit = self.0.pop().ok_or(FumenError::Truncated)?;
+                Ok((value + digit as u32 * unit, unit * 64))
+            })
+            .map(|(value, _)| value)
+    }
+
+    fn push(&mut self, mut value: u32, digits: usize) {
+        for _ in 0..digits {
+            self.0.push((value % 64) as u8);
+            value /= 64;
+        }
+    }
+}
+
+impl core::fmt::Display for Values {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for &value in &self.0 {
+            write!(f, "{}", ENCODE_TABLE[value as usize] as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs of the difference to `prev`, and false for a field that didn't change.
+fn encode_field(prev: &[u8], field: &[u8]) -> (Values, bool) {
+    let mut values = Values::default();
+    let diffs = field
+        .iter()
+        .zip(prev)
+        .map(|(&c, &p)| c as u32 + 8 - p as u32);
+    let mut runs: Vec<(u32, u32)> = vec![];
+    for diff in diffs {
+        match runs.last_mut() {
+            Some((last, count)) if *last == diff => *count += 1,
+            _ => runs.push((diff, 0)),
+        }
+    }
+    for &(diff, count) in &runs {
+        values.push(diff * FIELD_BLOCKS as u32 + count, 2);
+    }
+    (values, runs != [(8, FIELD_BLOCKS as u32 - 1)])
+}
+
+fn decode_field(values: &mut Values, field: &mut [u8]) -> Result<bool, FumenError> {
+    let mut index = 0;
+    let mut changed = true;
+    while index < FIELD_BLOCKS {
+        let run = values.poll(2)? as usize;
+        let diff = run / FIELD_BLOCKS;
+        let count = run % FIELD_BLOCKS + 1;
+        if diff == 8 && count == FIELD_BLOCKS {
+            changed = false;
+        }
+        for cell in field
+            .get_mut(index..index + count)
+            .ok_or(FumenError::Data)?
+        {
+            *cell = (*cell as usize + diff)
+                .checked_sub(8)
+                .filter(|&c| c <= 8)
+                .ok_or(FumenError::Data)? as u8;
+        }
+        index += count;
+    }
+    Ok(changed)
+}
+
+fn field_index(x: i16, y: i16) -> Option<usize> {
+    ((0..FIELD_WIDTH as i16).contains(&x) && (-1..FIELD_TOP).contains(&y))
+        .then(|| (FIELD_TOP - 1 - y) as usize * FIELD_WIDTH + x as usize)
+}
+
+fn field_from_board(board: &Board) -> [u8; FIELD_BLOCKS] {
+    let mut field = [0; FIELD_BLOCKS];
+    for y in 0..FIELD_TOP {
+        let row = &board.cells[(BOARD_HEIGHT - 1 - y) as usize];
+        for (x, cell) in row.iter().enumerate() {
+            if let Some(index) = field_index(x as i16, y) {
+                field[index] = cell.map_or(0, cell_to_fumen);
+            }
+        }
+    }
+    field
+}
+
+fn board_from_field(field: &[u8], piece: Option<FallingPiece>, comment: &str) -> Board {
+    let mut board = Board::default();
+    for y in 0..FIELD_TOP {
+        let row = &mut board.cells[(BOARD_HEIGHT - 1 - y) as usize];
+        for (x, cell) in row.iter_mut().enumerate() {
+            if let Some(index) = field_index(x as i16, y) {
+                *cell = cell_from_fumen(field[index]);
+            }
+        }
+    }
+    if let Some((hold, queue)) = parse_quiz(comment) {
+        board.hold_piece = hold;
+        board.set_queue(&queue);
+    } else if let Some(piece) = piece {
+        board.set_queue(&[piece.piece_state.get_kind()]);
+    }
+    board
+}
+
+/// Fills the cells of the action's piece.
+fn lock_piece(field: &mut [u8], kind: u32, rotation: u32, position: u32) {
+    let (piece, rotation) = (piece_from_fumen(kind), rotation_from_fumen(rotation));
+    let (x, y) = center_from_position(piece, rotation, position);
+    for (dx, dy) in fumen_cells(piece, rotation) {
+        if let Some(index) = field_index(x + dx, y + dy) {
+            field[index] = kind as u8;
+        }
+    }
+}
+
+fn clear_lines(field: &mut [u8; FIELD_BLOCKS]) {
+    let play = FIELD_BLOCKS - FIELD_WIDTH;
+    let mut rows = field[..play]
+        .chunks(FIELD_WIDTH)
+        .filter(|row| row.contains(&0))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    let cleared = play - rows.len();
+    rows.splice(0..0, core::iter::repeat_n(0, cleared));
+    field[..play].copy_from_slice(&rows);
+}
+
+/// Fumen's piece shapes, y up. They differ from ours in where some of the
+/// rotation centers sit.
+fn fumen_cells(piece: Piece, rotation: Rotation) -> [(i16, i16); 4] {
+    let cells = match piece {
+        Piece::I => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+        Piece::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+        Piece::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        Piece::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+        Piece::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+        Piece::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+        Piece::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+    };
+    cells.map(|(x, y)| match rotation {
+        Rotation::North => (x, y),
+        Rotation::East => (y, -x),
+        Rotation::South => (-x, -y),
+        Rotation::West => (-y, x),
+    })
+}
+
+/// Fumen stores some pieces one cell off their shape's center.
+fn position_offset(piece: Piece, rotation: Rotation) -> (i16, i16) {
+    match (piece, rotation) {
+        (Piece::O, Rotation::West) => (1, -1),
+        (Piece::O, Rotation::South) => (1, 0),
+        (Piece::O, Rotation::North) => (0, -1),
+        (Piece::I, Rotation::South) => (1, 0),
+        (Piece::I, Rotation::West) => (0, -1),
+        (Piece::S, Rotation::North) => (0, -1),
+        (Piece::S, Rotation::East) => (-1, 0),
+        (Piece::Z, Rotation::North) => (0, -1),
+        (Piece::Z, Rotation::West) => (1, 0),
+        _ => (0, 0),
+    }
+}
+
+fn center_from_position(piece: Piece, rotation: Rotation, position: u32) -> (i16, i16) {
+    let (dx, dy) = position_offset(piece, rotation);
+    let x = (position as usize % FIELD_WIDTH) as i16;
+    let y = FIELD_TOP - 1 - (position as usize / FIELD_WIDTH) as i16;
+    (x + dx, y + dy)
+}
+
+fn piece_from_action(kind: u32, rotation: u32, position: u32) -> Option<FallingPiece> {
+    let (piece, rotation) = (piece_from_fumen(kind), rotation_from_fumen(rotation));
+    let (x, y) = center_from_position(piece, rotation, position);
+    let cells = fumen_cells(piece, rotation).map(|(dx, dy)| (x + dx, BOARD_HEIGHT - 1 - (y + dy)));
+
+    // our centers don't always match fumen's, so line the shapes up instead
+    let rotations = [
+        rotation,
+        Rotation::North,
+        Rotation::East,
+        Rotation::South,
+        Rotation::West,
+    ];
+    rotations.into_iter().find_map(|rotation| {
+        let piece_state = PieceState::with_rotation(piece, rotation);
+        let shape = piece_state
+            .get_cells()
+            .into_iter()
+            .map(|(x, y)| (x, -y))
+            .collect::<Vec<_>>();
+        let (x, y) = align(&shape, &cells)?;
+        Some(FallingPiece {
+            piece_state,
+            piece_position: (usize::try_from(x).ok()?, usize::try_from(y).ok()?),
+            previous_lock_y: 0,
+        })
+    })
+}
+
+/// Kind, rotation and position of `piece` as a fumen action, if it fits in
+/// fumen's field.
+fn action_from_piece(piece: FallingPiece) -> Option<(u32, u32, u32)> {
+    let (px, py) = piece.piece_position;
+    let kind = piece.piece_state.get_kind();
+    let rotation = piece.piece_state.get_rotation();
+    let cells = piece
+        .piece_state
+        .get_cells()
+        .into_iter()
+        .map(|(x, y)| (px as i16 + x, BOARD_HEIGHT - 1 - (py as i16 - y)))
+        .collect::<Vec<_>>();
+
+    let (x, y) = align(&fumen_cells(kind, rotation), &cells)?;
+    let (dx, dy) = position_offset(kind, rotation);
+    let index = field_index(x - dx, y - dy)?;
+    Some((
+        piece_to_fumen(kind),
+        rotation_to_fumen(rotation),
+        index as u32,
+    ))
+}
+
+/// The offset that moves `shape` onto `cells`, if it's a translation.
+fn align(shape: &[(i16, i16)], cells: &[(i16, i16)]) -> Option<(i16, i16)> {
+    let mut shape = shape.to_vec();
+    let mut cells = cells.to_vec();
+    shape.sort_unstable_by_key(|&(x, y)| (y, x));
+    cells.sort_unstable_by_key(|&(x, y)| (y, x));
+    let (dx, dy) = (cells[0].0 - shape[0].0, cells[0].1 - shape[0].1);
+    shape
+        .iter()
+        .zip(&cells)
+        .all(|(&(sx, sy), &(cx, cy))| (sx + dx, sy + dy) == (cx, cy))
+        .then_some((dx, dy))
+}
+
+fn piece_to_fumen(piece: Piece) -> u32 {
+    match piece {
+        Piece::I => 1,
+        Piece::L => 2,
+        Piece::O => 3,
+        Piece::Z => 4,
+        Piece::T => 5,
+        Piece::J => 6,
+        Piece::S => 7,
+    }
+}
+
+fn piece_from_fumen(kind: u32) -> Piece {
+    match kind {
+        1 => Piece::I,
+        2 => Piece::L,
+        3 => Piece::O,
+        4 => Piece::Z,
+        5 => Piece::T,
+        6 => Piece::J,
+        _ => Piece::S,
+    }
+}
+
+fn rotation_to_fumen(rotation: Rotation) -> u32 {
+    match rotation {
+        Rotation::South => 0,
+        Rotation::East => 1,
+        Rotation::North => 2,
+        Rotation::West => 3,
+    }
+}
+
+fn rotation_from_fumen(rotation: u32) -> Rotation {
+    match rotation {
+        0 => Rotation::South,
+        1 => Rotation::East,
+        2 => Rotation::North,
+        _ => Rotation::West,
+    }
+}
+
+fn cell_to_fumen(cell: Cell) -> u8 {
+    match cell {
+        Cell::Cyan => 1,
+        Cell::Orange => 2,
+        Cell::Yellow => 3,
+        Cell::Red => 4,
+        Cell::Purple => 5,
+        Cell::Blue => 6,
+        Cell::Green => 7,
+        Cell::Black | Cell::White | Cell::Glay => 8,
+    }
+}
+
+fn cell_from_fumen(value: u8) -> Option<Cell> {
+    match value {
+        0 => None,
+        8 => Some(Cell::Glay),
+        kind => Some(piece_from_fumen(kind as u32).into()),
+    }
+}
+
+/// `#Q=[H](C)NEXT`, `current` or else the first of `next_pieces` as `C`.
+fn quiz_comment(board: &Board, current: Option<Piece>) -> String {
+    let queue = current.into_iter().chain(board.next_pieces.iter().copied());
+    format_quiz(board.hold_piece, queue)
+}
+
+fn format_quiz(hold: Option<Piece>, mut queue: impl Iterator<Item = Piece>) -> String {
+    let mut comment = String::from("#Q=[");
+    comment.extend(hold.map(Piece::to_char));
+    comment.push_str("](");
+    comment.extend(queue.next().map(Piece::to_char));
+    comment.push(')');
+    comment.extend(queue.map(Piece::to_char));
+    comment
+}
+
+/// The hold piece and the queue, current piece first.
+fn parse_quiz(comment: &str) -> Option<(Option<Piece>, Vec<Piece>)> {
+    let quiz = comment.strip_prefix("#Q=[")?;
+    let (hold, quiz) = quiz.split_once("](")?;
+    let (current, next) = quiz.split_once(')')?;
+    let hold = hold.chars().next().and_then(Piece::from_char);
+    let queue = current
+        .chars()
+        .chain(next.chars().take_while(|c| !c.is_whitespace()))
+        .map(Piece::from_char)
+        .collect::<Option<Vec<_>>>()?;
+    Some((hold, queue))
+}
+
+/// The quiz after `used` is played from it, straight from the current piece,
+/// from hold, or by holding the current piece and playing the next one.
+fn next_quiz(comment: &str, used: Piece) -> Option<String> {
+    let (hold, queue) = parse_quiz(comment)?;
+    let (&current, next) = queue.split_first()?;
+    let (hold, next) = if used == current {
+        (hold, next)
+    } else if hold == Some(used) {
+        (Some(current), next)
+    } else if hold.is_none() && next.first() == Some(&used) {
+        (Some(current), &next[1..])
+    } else {
+        return None;
+    };
+    Some(format_quiz(hold, next.iter().copied()))
+}
+
+/// JavaScript's `escape`, which fumen runs comments through.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for unit in text.encode_utf16() {
+        match char::from_u32(unit as u32) {
+            Some(c) if c.is_ascii_alphanumeric() || "@*_+-./".contains(c) => escaped.push(c),
+            _ if unit < 0x100 => escaped.push_str(&format!("%{unit:02X}")),
+            _ => escaped.push_str(&format!("%u{unit:04X}")),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut units = vec![];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let hex = |digits: &str| u16::from_str_radix(digits, 16).ok();
+        let (unit, len) = match (c, rest.get(1..2)) {
+            ('%', Some("u")) => match rest.get(2..6).and_then(hex) {
+                Some(unit) => (unit, 6),
+                None => (c as u16, 1),
+            },
+            ('%', _) => match rest.get(1..3).and_then(hex) {
+                Some(unit) => (unit, 3),
+                None => (c as u16, 1),
+            },
+            _ => {
+                let mut buf = [0; 2];
+                units.extend_from_slice(c.encode_utf16(&mut buf));
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        };
+        units.push(unit);
+        rest = &rest[len..];
+    }
+    String::from_utf16_lossy(&units)
+}
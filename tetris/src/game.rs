@@ -1,8 +1,11 @@
-use std::fmt::Display;
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
 
 use arrayvec::ArrayVec;
 
-use crate::{Board, FallingPiece, Input, Piece, PieceState, Sound, TetrisEvent};
+use crate::{
+    Board, Cell, FallingPiece, Input, Piece, PieceState, Randomizer, Sound, Status, TetrisEvent,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DasState {
@@ -29,6 +32,9 @@ pub trait GameState {
     fn get_sound_queue(&mut self) -> &mut Vec<Sound>;
     fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent>;
     fn set_input(&mut self, input: Input);
+    /// `Status::End` once the mode is over, whether by topping out or by its
+    /// own goal or time limit.
+    fn get_status(&self) -> Status;
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +49,7 @@ pub struct Game {
     input: Input,
     previous_input: Input,
     rotate_used: bool,
+    last_move_rotation: bool,
     sound_queue: Vec<Sound>,
     event_queue: Vec<TetrisEvent>,
     das: usize,
@@ -54,40 +61,33 @@ pub struct Game {
     line_clear_lock: usize,
     line_clear_lock_timer: Option<usize>,
     hold_used: bool,
+    hold_enabled: bool,
+    big: bool,
+    incoming_garbage: Vec<(usize, usize)>,
+    game_over: bool,
 }
 
 impl Display for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "    {:?}", self.board.hold_piece)?;
-        writeln!(f, "    {:?}", self.board.next_pieces)?;
-        writeln!(f, "    0 1 2 3 4 5 6 7 8 9")?;
-        writeln!(f, "    -------------------")?;
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut render_board = self.board.clone();
         if let Some(current_piece) = self.current_piece {
             render_board.set_piece(&current_piece).unwrap();
         }
-
-        let offset_y = 20;
-        for (y, cells_x) in render_board.cells.iter().enumerate() {
-            if y < offset_y {
-                continue;
-            };
-            write!(f, "{y:02}| ")?;
-            for cell in cells_x {
-                match cell {
-                    Some(cell) => write!(f, "{cell} ")?,
-                    None => write!(f, "  ")?,
-                }
-            }
-            writeln!(f, "")?;
-        }
-        Ok(())
+        write!(f, "{render_board}")
     }
 }
 
 impl Game {
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
-        let mut board = Board::default();
+        Self::from_board(Board::default())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_board(Board::with_seed(Randomizer::default(), seed))
+    }
+
+    pub fn from_board(mut board: Board) -> Self {
         let next = board.pop_next();
         let current_piece = FallingPiece::from_piece_state(PieceState::from_piece(next));
         // Self {
@@ -123,6 +123,7 @@ impl Game {
             input: Default::default(),
             previous_input: Default::default(),
             rotate_used: false,
+            last_move_rotation: false,
             sound_queue: vec![],
             event_queue: vec![],
             das: 8,
@@ -134,9 +135,14 @@ impl Game {
             line_clear_lock: 6,
             line_clear_lock_timer: None,
             hold_used: false,
+            hold_enabled: true,
+            big: false,
+            incoming_garbage: vec![],
+            game_over: false,
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn from_settings(
         gravity: f64,
         are: usize,
@@ -160,6 +166,7 @@ impl Game {
             input: Default::default(),
             previous_input: Default::default(),
             rotate_used: false,
+            last_move_rotation: false,
             sound_queue: vec![],
             event_queue: vec![],
             das,
@@ -171,6 +178,10 @@ impl Game {
             line_clear_lock: line_clear_delay,
             line_clear_lock_timer: None,
             hold_used: false,
+            hold_enabled: true,
+            big: false,
+            incoming_garbage: vec![],
+            game_over: false,
         }
     }
 
@@ -198,6 +209,23 @@ impl Game {
         self.line_clear_lock = line_clear_delay;
     }
 
+    pub fn set_hold_enabled(&mut self, hold_enabled: bool) {
+        self.hold_enabled = hold_enabled;
+    }
+
+    /// Pieces spawned from now on are big, see `PieceState::to_big`.
+    pub fn set_big(&mut self, big: bool) {
+        self.big = big;
+    }
+
+    pub fn is_big(&self) -> bool {
+        self.big
+    }
+
+    fn spawn_piece(&self, piece: Piece) -> FallingPiece {
+        FallingPiece::from_piece_state(PieceState::from_piece(piece).with_big(self.big))
+    }
+
     fn apply_gravity(&mut self) {
         if let Some(mut current_piece) = self.current_piece.clone() {
             if current_piece.check_shift_collision(&self.board, 0, 1) {
@@ -207,6 +235,7 @@ impl Game {
                 current_piece.previous_lock_y = current_piece.piece_position.1;
                 self.lock_counter += 1;
                 if self.lock_counter >= self.lock_delay {
+                    self.push_t_spin(&current_piece);
                     self.board.set_piece(&current_piece).unwrap();
                     self.event_queue
                         .push(TetrisEvent::PieceLocked(current_piece));
@@ -228,6 +257,7 @@ impl Game {
             if self.shift_down_counter >= 1.0 {
                 let fall_size = self.shift_down_counter as i32;
                 self.shift_down_counter = 0.0;
+                let from_y = current_piece.piece_position.1;
 
                 if current_piece.shift(&self.board, 0, fall_size) {
                     // falling down successful
@@ -238,6 +268,13 @@ impl Game {
                         };
                     }
                 }
+                let fallen = current_piece.piece_position.1 - from_y;
+                if fallen > 0 {
+                    self.last_move_rotation = false;
+                    if self.input.soft_drop {
+                        self.event_queue.push(TetrisEvent::SoftDropped(fallen));
+                    }
+                }
             }
             // let sound = self.get_next_sound();
             // self.sound_queue.push(sound);
@@ -245,13 +282,27 @@ impl Game {
         }
     }
 
-    fn apply_line_clear(&mut self) {
+    fn apply_line_clear(&mut self) -> bool {
         if let Some(lines) = self.board.line_clear() {
             self.event_queue.push(TetrisEvent::LineCleared(lines));
             self.sound_queue.push(Sound::Erase);
             self.line_clear_lock_timer = Some(self.line_clear_lock);
             self.are_counter = Some(self.line_are);
-        };
+            true
+        } else {
+            false
+        }
+    }
+
+    fn apply_incoming_garbage(&mut self) {
+        let incoming = core::mem::take(&mut self.incoming_garbage);
+        let rows = incoming.iter().map(|(rows, _)| rows).sum();
+        for (rows, hole) in incoming {
+            self.insert_garbage(rows, hole);
+        }
+        if rows > 0 {
+            self.event_queue.push(TetrisEvent::GarbageReceived(rows));
+        }
     }
 
     fn handle_rotate(&mut self) {
@@ -259,11 +310,13 @@ impl Game {
             if self.input.cw && !self.rotate_used {
                 if current_piece.cw(&self.board) {
                     self.lock_counter = 0;
+                    self.last_move_rotation = true;
                 };
                 self.rotate_used = true;
             } else if self.input.ccw && !self.rotate_used {
                 if current_piece.ccw(&self.board) {
                     self.lock_counter = 0;
+                    self.last_move_rotation = true;
                 };
                 self.rotate_used = true;
             }
@@ -274,21 +327,21 @@ impl Game {
     }
 
     fn handle_hold(&mut self) {
-        if !self.hold_used && self.input.hold {
+        if self.hold_enabled && !self.hold_used && self.input.hold {
             let sound = self.get_next_sound();
-            if let Some(current_piece) = self.current_piece.as_mut() {
+            if let Some(current_piece) = self.current_piece {
                 let swapped = self
                     .board
                     .swap_hold_piece(current_piece.piece_state.get_kind());
-                let new_piece = if let Some(swapped) = swapped {
-                    FallingPiece::from_piece_state(PieceState::from_piece(swapped))
-                } else {
-                    FallingPiece::from_piece_state(PieceState::from_piece(self.board.pop_next()))
+                let new_piece = match swapped {
+                    Some(swapped) => swapped,
+                    None => self.board.pop_next(),
                 };
                 self.sound_queue.push(sound);
-                *current_piece = new_piece;
+                self.current_piece = Some(self.spawn_piece(new_piece));
                 self.rotate_used = false;
                 self.hold_used = true;
+                self.last_move_rotation = false;
                 self.shift_down_counter = 0.0;
                 self.lock_counter = 0;
                 self.sound_queue.push(Sound::Hold);
@@ -298,16 +351,22 @@ impl Game {
 
     fn handle_hard_drop(&mut self) {
         if !self.previous_input.hard_drop && self.input.hard_drop {
-            if let Some(current_piece) = self.current_piece.as_mut() {
+            if let Some(mut current_piece) = self.current_piece {
                 self.sound_queue.push(Sound::Bottom);
 
                 for i in 0..20 {
                     if current_piece.check_shift_collision(&self.board, 0, i) {
                         current_piece.shift(&self.board, 0, i - 1);
+                        if i > 1 {
+                            self.last_move_rotation = false;
+                            self.event_queue
+                                .push(TetrisEvent::HardDropped(i as usize - 1));
+                        }
+                        self.push_t_spin(&current_piece);
                         self.board.set_piece(&current_piece).unwrap();
                         self.event_queue
-                            .push(TetrisEvent::PieceLocked(current_piece.clone()));
-                        self.locked_piece = Some(current_piece.clone());
+                            .push(TetrisEvent::PieceLocked(current_piece));
+                        self.locked_piece = Some(current_piece);
                         self.current_piece = None;
                         self.lock_counter = 0;
                         self.are_counter = Some(self.are);
@@ -327,8 +386,10 @@ impl Game {
             if self.das_counter == 0 {
                 self.das_counter += 1;
                 if let Some(current_piece) = self.current_piece.as_mut() {
-                    if current_piece.shift(&self.board, 1, 0) {
+                    let step = current_piece.piece_state.get_step();
+                    if current_piece.shift(&self.board, step, 0) {
                         self.lock_counter = 0;
+                        self.last_move_rotation = false;
                     };
                 }
             }
@@ -336,8 +397,10 @@ impl Game {
                 self.das_counter += 1;
                 if self.das_counter >= self.das {
                     if let Some(current_piece) = self.current_piece.as_mut() {
-                        if current_piece.shift(&self.board, 1, 0) {
+                        let step = current_piece.piece_state.get_step();
+                        if current_piece.shift(&self.board, step, 0) {
                             self.lock_counter = 0;
+                            self.last_move_rotation = false;
                         };
                     }
                 }
@@ -350,8 +413,10 @@ impl Game {
             if self.das_counter == 0 {
                 self.das_counter += 1;
                 if let Some(current_piece) = self.current_piece.as_mut() {
-                    if current_piece.shift(&self.board, -1, 0) {
+                    let step = current_piece.piece_state.get_step();
+                    if current_piece.shift(&self.board, -step, 0) {
                         self.lock_counter = 0;
+                        self.last_move_rotation = false;
                     };
                 }
             }
@@ -359,8 +424,10 @@ impl Game {
                 self.das_counter += 1;
                 if self.das_counter >= self.das {
                     if let Some(current_piece) = self.current_piece.as_mut() {
-                        if current_piece.shift(&self.board, -1, 0) {
+                        let step = current_piece.piece_state.get_step();
+                        if current_piece.shift(&self.board, -step, 0) {
                             self.lock_counter = 0;
+                            self.last_move_rotation = false;
                         };
                     }
                 }
@@ -372,6 +439,16 @@ impl Game {
         }
     }
 
+    fn push_t_spin(&mut self, piece: &FallingPiece) {
+        if !self.last_move_rotation {
+            return;
+        }
+        self.last_move_rotation = false;
+        if let Some(t_spin) = self.board.get_t_spin(piece) {
+            self.event_queue.push(TetrisEvent::TSpin(t_spin));
+        }
+    }
+
     fn get_next_sound(&self) -> Sound {
         match self.get_next() {
             Piece::I => Sound::PieceI,
@@ -384,6 +461,82 @@ impl Game {
         }
     }
 
+    /// Pushes `row` in from the bottom. Tops out if the stack leaves the buffer,
+    /// or pushes the falling piece out of it.
+    pub fn push_row(&mut self, row: ArrayVec<Option<Cell>, 10>) -> bool {
+        let mut overflowed = self.board.push_row(row);
+        if let Some(current_piece) = self.current_piece.as_mut() {
+            if current_piece.check_shift_collision(&self.board, 0, 0) {
+                match current_piece.piece_position.1.checked_sub(1) {
+                    Some(y) => {
+                        current_piece.piece_position.1 = y;
+                        current_piece.previous_lock_y = y;
+                    }
+                    None => overflowed = true,
+                }
+            }
+        }
+        if overflowed {
+            self.top_out();
+        }
+        overflowed
+    }
+
+    fn insert_garbage(&mut self, rows: usize, hole: usize) -> bool {
+        let mut overflowed = false;
+        for _ in 0..rows {
+            overflowed |= self.push_row(Board::garbage_row(hole));
+        }
+        overflowed
+    }
+
+    /// Inserts garbage right away.
+    pub fn push_garbage(&mut self, rows: usize, hole: usize) -> bool {
+        let overflowed = self.insert_garbage(rows, hole);
+        if rows > 0 {
+            self.event_queue.push(TetrisEvent::GarbageInserted(rows));
+        }
+        overflowed
+    }
+
+    /// Queues garbage to be inserted when the next piece locks without clearing lines.
+    pub fn receive_garbage(&mut self, rows: usize, hole: usize) {
+        if rows > 0 {
+            self.incoming_garbage.push((rows, hole));
+        }
+    }
+
+    pub fn get_incoming_garbage(&self) -> usize {
+        self.incoming_garbage.iter().map(|(rows, _)| rows).sum()
+    }
+
+    /// Offsets queued garbage, oldest first. Returns the rows that were not cancelled.
+    pub fn cancel_garbage(&mut self, mut rows: usize) -> usize {
+        while rows > 0 {
+            let Some((pending, _)) = self.incoming_garbage.first_mut() else {
+                break;
+            };
+            let cancelled = rows.min(*pending);
+            *pending -= cancelled;
+            rows -= cancelled;
+            if *pending == 0 {
+                self.incoming_garbage.remove(0);
+            }
+        }
+        rows
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    fn top_out(&mut self) {
+        if !self.game_over {
+            self.game_over = true;
+            self.event_queue.push(TetrisEvent::ToppedOut);
+        }
+    }
+
     pub fn clear_board(&mut self) {
         let mut cells = ArrayVec::new();
         for _ in 0..40 {
@@ -395,6 +548,9 @@ impl Game {
 
 impl GameState for Game {
     fn update(&mut self) {
+        if self.game_over {
+            return;
+        }
         if let Some(_locked_piece) = self.locked_piece {
             self.locked_piece = None;
         }
@@ -411,11 +567,14 @@ impl GameState for Game {
                 if let Some(are_counter) = self.are_counter.as_mut() {
                     *are_counter -= 1;
                     if *are_counter <= 0 {
-                        let next_piece = PieceState::from_piece(self.board.pop_next());
-                        self.current_piece = FallingPiece::from_piece_state(next_piece).into();
+                        let next = self.board.pop_next();
+                        let next_piece = self.spawn_piece(next);
+                        self.current_piece = Some(next_piece);
                         self.shift_down_counter = 0.0;
-                        self.event_queue
-                            .push(TetrisEvent::PieceSpawned(next_piece.get_kind()));
+                        if next_piece.check_shift_collision(&self.board, 0, 0) {
+                            self.top_out();
+                        }
+                        self.event_queue.push(TetrisEvent::PieceSpawned(next));
 
                         let sound = self.get_next_sound();
                         self.sound_queue.push(sound);
@@ -431,7 +590,10 @@ impl GameState for Game {
         self.handle_hard_drop();
         self.apply_gravity();
         self.handle_shift();
-        self.apply_line_clear();
+        let cleared = self.apply_line_clear();
+        if self.locked_piece.is_some() && !cleared {
+            self.apply_incoming_garbage();
+        }
 
         self.previous_input = self.input;
     }
@@ -475,4 +637,12 @@ impl GameState for Game {
     fn set_input(&mut self, input: Input) {
         self.input = input;
     }
+
+    fn get_status(&self) -> Status {
+        if self.game_over {
+            Status::End
+        } else {
+            Status::Game
+        }
+    }
 }
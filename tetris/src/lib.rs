@@ -1,15 +1,52 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod attack;
 mod board;
+mod bot;
 mod cell;
+mod clock;
+#[cfg(feature = "std")]
+pub mod env;
+#[cfg(feature = "std")]
+mod fumen;
 mod game;
 mod modes;
+#[cfg(feature = "std")]
+mod net;
+mod perfect_clear;
 mod piece;
+mod placement;
+mod randomizer;
+#[cfg(feature = "std")]
+mod rollback;
+mod scoring;
+#[cfg(feature = "std")]
+mod tbp;
 
+pub use attack::*;
 pub use board::*;
+pub use bot::*;
 pub use cell::*;
+pub use clock::*;
+#[cfg(feature = "std")]
+pub use fumen::*;
 pub use game::*;
 pub use modes::*;
+#[cfg(feature = "std")]
+pub use net::*;
+pub use perfect_clear::*;
 pub use piece::*;
+pub use placement::*;
+pub use randomizer::*;
+#[cfg(feature = "std")]
+pub use rollback::*;
+pub use scoring::*;
+#[cfg(feature = "std")]
+pub use tbp::*;
 
+use alloc::vec::Vec;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,6 +66,36 @@ pub struct Input {
     pub hold: bool,
 }
 
+impl Input {
+    pub fn to_bits(self) -> u8 {
+        [
+            self.left,
+            self.right,
+            self.hard_drop,
+            self.soft_drop,
+            self.cw,
+            self.ccw,
+            self.hold,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &pressed)| bits | (pressed as u8) << i)
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        let pressed = |i: u8| bits & (1 << i) != 0;
+        Self {
+            left: pressed(0),
+            right: pressed(1),
+            hard_drop: pressed(2),
+            soft_drop: pressed(3),
+            cw: pressed(4),
+            ccw: pressed(5),
+            hold: pressed(6),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum Music {}
 
@@ -55,4 +122,16 @@ pub enum TetrisEvent {
     LineShrinked(Vec<usize>),
     PieceSpawned(Piece),
     PieceLocked(FallingPiece),
+    TSpin(TSpin),
+    SoftDropped(usize),
+    HardDropped(usize),
+    GarbageInserted(usize),
+    GarbageReceived(usize),
+    ToppedOut,
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TSpin {
+    Mini,
+    Full,
 }
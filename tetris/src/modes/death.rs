@@ -0,0 +1,286 @@
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
+
+use crate::{
+    Board, Clock, FallingPiece, Game, GameState, Input, Piece, Randomizer, Roll, Sound, Status,
+    TGM3Event, TGM3Sound, TetrisEvent,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeathGrade {
+    None,
+    M,
+    GM,
+}
+
+#[derive(Debug, Clone)]
+pub struct Death {
+    pub inner: Game,
+    level: usize,
+    clock: Clock,
+    grade: DeathGrade,
+    status: Status,
+    start_roll_timer: Option<usize>,
+    roll_timer: Option<usize>,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Death {
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut inner = Game::from_board(Board::with_seed(Randomizer::tgm2(), seed));
+        inner.set_hold_enabled(false);
+        let mut me = Self {
+            inner,
+            level: 0,
+            clock: Clock::default(),
+            grade: DeathGrade::None,
+            status: Status::Game,
+            start_roll_timer: None,
+            roll_timer: None,
+            envets: vec![],
+            sounds: vec![],
+        };
+        me.sync_settings();
+        me
+    }
+
+    pub fn get_level(&self) -> usize {
+        self.level
+    }
+
+    fn get_gravity(&self) -> f64 {
+        20.0
+    }
+
+    fn get_are(&self) -> usize {
+        match self.level {
+            0..=99 => 18,
+            100..=299 => 14,
+            300..=399 => 8,
+            400..=499 => 7,
+            _ => 6,
+        }
+    }
+
+    fn get_line_are(&self) -> usize {
+        match self.level {
+            0..=99 => 14,
+            100..=399 => 8,
+            400..=499 => 7,
+            _ => 6,
+        }
+    }
+
+    fn get_das(&self) -> usize {
+        match self.level {
+            0..=199 => 12,
+            200..=299 => 11,
+            300..=399 => 10,
+            _ => 8,
+        }
+    }
+
+    fn get_lock_delay(&self) -> usize {
+        match self.level {
+            0..=99 => 30,
+            100..=199 => 26,
+            200..=299 => 22,
+            300..=399 => 18,
+            _ => 15,
+        }
+    }
+
+    fn get_line_clear_delay(&self) -> usize {
+        match self.level {
+            0..=99 => 12,
+            100..=399 => 6,
+            400..=499 => 5,
+            _ => 4,
+        }
+    }
+
+    fn torikan(&self) -> Duration {
+        Duration::from_secs(3 * 60 + 25)
+    }
+
+    fn sync_settings(&mut self) {
+        self.inner.set_gravity(self.get_gravity());
+        self.inner.set_are(self.get_are());
+        self.inner.set_line_are(self.get_line_are());
+        self.inner.set_das(self.get_das());
+        self.inner.set_lock_delay(self.get_lock_delay());
+        self.inner.set_line_clear_delay(self.get_line_clear_delay());
+    }
+
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.envets.push(TGM3Event::StatusChange(status));
+    }
+
+    fn set_grade(&mut self, grade: DeathGrade) {
+        self.grade = grade;
+        self.envets.push(TGM3Event::GradeUp(grade as usize));
+        self.sounds.push(TGM3Sound::GradeUp);
+    }
+
+    fn level_up(&mut self, up: usize, line_clear: bool) {
+        let prev = self.level;
+
+        if line_clear || (prev + up) % 100 > prev % 100 && prev + up < 998 {
+            self.level += up;
+        }
+        if self.level > 999 {
+            self.level = 999;
+        }
+
+        let section_up = line_clear && (prev % 100 > self.level % 100 || self.level == 999);
+        if !section_up {
+            return;
+        }
+        if self.level == 999 {
+            self.set_status(Status::Clear);
+            self.sounds.push(TGM3Sound::GameClear);
+            return;
+        }
+        if prev < 500 && self.level >= 500 {
+            if self.clock.elapsed() > self.torikan() {
+                self.level = 500;
+                self.set_status(Status::End);
+                return;
+            }
+            self.set_grade(DeathGrade::M);
+        }
+        self.inner.get_sound_queue().push(Sound::RankUp);
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        let events = self.inner.get_event_queue().clone();
+        for e in events.iter() {
+            match e {
+                TetrisEvent::LineCleared(n) => {
+                    self.level_up(*n, true);
+                }
+                TetrisEvent::PieceSpawned(_) => {
+                    self.level_up(1, false);
+                }
+                _ => {}
+            }
+        }
+        if self.inner.is_game_over() {
+            self.set_status(Status::End);
+        }
+        self.sync_settings();
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn get_grade(&self) -> DeathGrade {
+        self.grade
+    }
+}
+
+impl GameState for Death {
+    fn update(&mut self) {
+        self.clock.tick();
+        match self.status {
+            Status::Game => self.game_update(),
+            Status::Clear => {
+                if self.start_roll_timer.is_none() {
+                    self.start_roll_timer = Some(150)
+                }
+                if let Some(timer) = self.start_roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.inner.clear_board();
+                        self.set_status(Status::Roll(Roll::Normal));
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            Status::Roll(_) => {
+                self.inner.update();
+                if self.inner.is_game_over() {
+                    self.set_status(Status::End);
+                    return;
+                }
+                if self.roll_timer.is_none() {
+                    self.roll_timer = Some(3238);
+                }
+                if let Some(timer) = self.roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.set_status(Status::End);
+                        self.set_grade(DeathGrade::GM);
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
@@ -0,0 +1,202 @@
+use alloc::{vec, vec::Vec};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    Board, FallingPiece, Game, GameState, Input, Piece, Randomizer, Sound, Status, TGM3Event,
+    TGM3Sound, TetrisEvent,
+};
+
+pub const DEFAULT_DIG_ROWS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct Dig {
+    pub inner: Game,
+    rng: StdRng,
+    // marks rows that belong to the starting garbage, indexed like `Board::cells`
+    original_rows: Vec<bool>,
+    rise_frames: Option<usize>,
+    rise_pieces: Option<usize>,
+    frames: usize,
+    pieces: usize,
+    cleared: bool,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Dig {
+    #[cfg(feature = "std")]
+    pub fn new(rows: usize) -> Self {
+        Self::with_seed(rows, rand::random())
+    }
+
+    pub fn with_seed(rows: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut board = Board::with_seed(Randomizer::default(), rng.gen());
+        for _ in 0..rows {
+            board.push_garbage(1, rng.gen_range(0..10));
+        }
+        let mut original_rows = vec![false; board.cells.len()];
+        let len = original_rows.len();
+        for row in original_rows.iter_mut().skip(len - rows.min(len)) {
+            *row = true;
+        }
+        Self {
+            inner: Game::from_board(board),
+            rng,
+            original_rows,
+            rise_frames: None,
+            rise_pieces: None,
+            frames: 0,
+            pieces: 0,
+            cleared: false,
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        }
+    }
+
+    /// Raises one garbage row every `frames` frames.
+    pub fn set_rise_frames(&mut self, frames: Option<usize>) {
+        self.rise_frames = frames.filter(|&f| f > 0);
+    }
+
+    /// Raises one garbage row every `pieces` locked pieces.
+    pub fn set_rise_pieces(&mut self, pieces: Option<usize>) {
+        self.rise_pieces = pieces.filter(|&p| p > 0);
+    }
+
+    pub fn get_garbage_remaining(&self) -> usize {
+        self.original_rows.iter().filter(|&&o| o).count()
+    }
+
+    pub fn get_frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn get_pieces(&self) -> usize {
+        self.pieces
+    }
+
+    /// True once every starting garbage row has been cleared.
+    pub fn is_cleared(&self) -> bool {
+        self.cleared
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    fn rise(&mut self) {
+        let hole = self.rng.gen_range(0..10);
+        self.inner.push_garbage(1, hole);
+        self.original_rows.remove(0);
+        self.original_rows.push(false);
+    }
+
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.envets.push(TGM3Event::StatusChange(status));
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        self.frames += 1;
+        let events = self.inner.get_event_queue().clone();
+        let mut rise = self
+            .rise_frames
+            .is_some_and(|f| self.frames.is_multiple_of(f));
+        for e in events.iter() {
+            match e {
+                TetrisEvent::PieceLocked(_) => {
+                    self.pieces += 1;
+                    rise |= self
+                        .rise_pieces
+                        .is_some_and(|p| self.pieces.is_multiple_of(p));
+                }
+                TetrisEvent::LineShrinked(lines) => {
+                    for y in lines.iter().rev() {
+                        self.original_rows.remove(*y);
+                    }
+                    for _ in 0..lines.len() {
+                        self.original_rows.insert(0, false);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if self.get_garbage_remaining() == 0 {
+            self.cleared = true;
+            self.set_status(Status::End);
+            self.sounds.push(TGM3Sound::GameClear);
+            return;
+        }
+        if rise {
+            self.rise();
+        }
+        if self.inner.is_game_over() {
+            self.set_status(Status::End);
+        }
+    }
+}
+
+impl GameState for Dig {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Board, FallingPiece, Game, GameState, GuidelineScore, Input, Piece, Sound, Status, TGM3Event,
+    TGM3Sound, TetrisEvent,
+};
+
+const LINES_PER_LEVEL: usize = 10;
+const GOAL_LEVEL: usize = 15;
+
+#[derive(Debug, Clone)]
+pub struct Marathon {
+    pub inner: Game,
+    endless: bool,
+    scoring: GuidelineScore,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Marathon {
+    #[cfg(feature = "std")]
+    pub fn new(endless: bool) -> Self {
+        Self::with_seed(endless, rand::random())
+    }
+
+    pub fn with_seed(endless: bool, seed: u64) -> Self {
+        let mut me = Self {
+            inner: Game::with_seed(seed),
+            endless,
+            scoring: Default::default(),
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        };
+        me.sync_settings();
+        me
+    }
+
+    pub fn get_level(&self) -> usize {
+        self.scoring.get_level()
+    }
+
+    pub fn get_lines(&self) -> usize {
+        self.scoring.get_lines()
+    }
+
+    pub fn get_score(&self) -> usize {
+        self.scoring.get_score()
+    }
+
+    pub fn get_scoring(&self) -> &GuidelineScore {
+        &self.scoring
+    }
+
+    pub fn is_endless(&self) -> bool {
+        self.endless
+    }
+
+    fn sync_settings(&mut self) {
+        self.inner.set_gravity(guideline_gravity(self.get_level()));
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        let events = self.inner.get_event_queue().clone();
+        self.scoring.update(&events, &self.inner.get_board());
+
+        let level = self.get_lines() / LINES_PER_LEVEL + 1;
+        if !self.endless && level > GOAL_LEVEL {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+            self.sounds.push(TGM3Sound::GameClear);
+            return;
+        }
+        if level > self.get_level() {
+            self.scoring.set_level(level);
+            self.inner.get_sound_queue().push(Sound::RankUp);
+            self.sync_settings();
+        }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+}
+
+impl GameState for Marathon {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
+
+/// Guideline curve: `(0.8 - (level - 1) * 0.007) ^ (level - 1)` seconds per row.
+pub(crate) fn guideline_gravity(level: usize) -> f64 {
+    let base = 0.8 - (level - 1) as f64 * 0.007;
+    let mut seconds_per_row = 1.0;
+    for _ in 1..level {
+        seconds_per_row *= base;
+    }
+    if seconds_per_row <= 0.0 {
+        return 20.0;
+    }
+    (1.0 / (seconds_per_row * 60.0)).min(20.0)
+}
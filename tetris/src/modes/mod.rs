@@ -1,3 +1,27 @@
+mod death;
+mod dig;
+mod marathon;
+mod pc_practice;
+mod puzzle;
+mod royale;
+mod sprint;
+mod tgm1master;
+mod tgm2master;
 mod tgm3master;
+mod tgm3shirase;
+mod ultra;
+mod versus;
 
+pub use death::*;
+pub use dig::*;
+pub use marathon::*;
+pub use pc_practice::*;
+pub use puzzle::*;
+pub use royale::*;
+pub use sprint::*;
+pub use tgm1master::*;
+pub use tgm2master::*;
 pub use tgm3master::*;
+pub use tgm3shirase::*;
+pub use ultra::*;
+pub use versus::*;
@@ -0,0 +1,220 @@
+use alloc::{vec, vec::Vec};
+
+use super::marathon::guideline_gravity;
+use crate::{
+    find_perfect_clear, find_perfect_clear_after_hold, Board, FallingPiece, Game, GameState, Input,
+    PcStep, Piece, Randomizer, Sound, Status, TGM3Event, TGM3Sound, TetrisEvent,
+};
+
+/// Next pieces the solver gets to see, enough for a perfect clear four rows
+/// high with a piece to spare for hold.
+pub const PC_PRACTICE_NEXT: usize = 10;
+
+/// Free play that keeps a perfect clear solution for the position, searched
+/// again whenever a placement or hold goes off it.
+#[derive(Debug, Clone)]
+pub struct PcPractice {
+    pub inner: Game,
+    solution: Option<Vec<PcStep>>,
+    // went off the solution, the next piece searches again
+    pending: bool,
+    show_solution: bool,
+    perfect_clears: usize,
+    pieces: usize,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl PcPractice {
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_board(Board::with_seed(Randomizer::default(), seed))
+    }
+
+    /// Starts from `board`, which should have at most `PC_MAX_HEIGHT` filled
+    /// rows for a perfect clear to be found.
+    pub fn from_board(mut board: Board) -> Self {
+        board.extend_queue(PC_PRACTICE_NEXT + 1);
+        let mut inner = Game::from_board(board);
+        // solutions take tucks and spins 20G doesn't allow
+        inner.set_gravity(guideline_gravity(1));
+        let mut practice = Self {
+            inner,
+            solution: None,
+            pending: false,
+            show_solution: true,
+            perfect_clears: 0,
+            pieces: 0,
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        };
+        practice.solve(false);
+        practice
+    }
+
+    /// Whether a perfect clear can still be made with the pieces in view,
+    /// `None` until the next piece after going off the solution.
+    pub fn is_pc_possible(&self) -> Option<bool> {
+        (!self.pending).then(|| self.solution.is_some())
+    }
+
+    /// The remaining steps of the perfect clear, from the current piece on.
+    pub fn get_solution(&self) -> Option<&[PcStep]> {
+        self.solution.as_deref().filter(|_| !self.pending)
+    }
+
+    /// Whether frontends draw the solution over the board, the mode itself
+    /// doesn't care.
+    pub fn get_show_solution(&self) -> bool {
+        self.show_solution
+    }
+
+    pub fn set_show_solution(&mut self, show_solution: bool) {
+        self.show_solution = show_solution;
+    }
+
+    pub fn get_perfect_clears(&self) -> usize {
+        self.perfect_clears
+    }
+
+    pub fn get_pieces(&self) -> usize {
+        self.pieces
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    fn solve(&mut self, hold_used: bool) {
+        self.pending = false;
+        let Some(current) = self.inner.get_current_piece() else {
+            self.solution = None;
+            return;
+        };
+        let board = self.inner.get_board();
+        let mut queue = vec![current.piece_state.get_kind()];
+        queue.extend(board.next_pieces.iter().take(PC_PRACTICE_NEXT));
+        let hold = self.inner.get_hold();
+        self.solution = if hold_used {
+            find_perfect_clear_after_hold(&board, &queue, hold)
+        } else {
+            find_perfect_clear(&board, &queue, hold, true)
+        };
+    }
+
+    fn game_update(&mut self) {
+        let hold = self.inner.get_hold();
+        self.inner.update();
+        if self.inner.get_hold() != hold {
+            match self.solution.as_mut().and_then(|steps| steps.first_mut()) {
+                Some(step) if step.hold && !self.pending => step.hold = false,
+                _ => self.solve(true),
+            }
+        }
+
+        let events = self.inner.get_event_queue().clone();
+        for e in events.iter() {
+            match e {
+                TetrisEvent::PieceLocked(piece) => {
+                    self.pieces += 1;
+                    let followed = self.solution.as_ref().and_then(|steps| steps.first());
+                    if followed.is_some_and(|step| !step.hold && step.placement.is_at(piece)) {
+                        if let Some(steps) = self.solution.as_mut() {
+                            steps.remove(0);
+                        }
+                    } else {
+                        self.pending = true;
+                    }
+                }
+                TetrisEvent::LineCleared(_) => {
+                    let board = self.inner.get_board();
+                    if board
+                        .cells
+                        .iter()
+                        .all(|cells_x| cells_x.iter().all(Option::is_none))
+                    {
+                        self.perfect_clears += 1;
+                        self.sounds.push(TGM3Sound::GameClear);
+                    }
+                }
+                // a placement went off the solution or finished the last one
+                TetrisEvent::PieceSpawned(_)
+                    if self.pending || self.solution.as_ref().is_some_and(Vec::is_empty) =>
+                {
+                    self.solve(false)
+                }
+                _ => {}
+            }
+        }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
+    }
+}
+
+impl GameState for PcPractice {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
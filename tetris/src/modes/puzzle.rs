@@ -0,0 +1,378 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+
+use thiserror::Error;
+
+use crate::{
+    Board, BoardParseError, FallingPiece, Game, GameState, Input, Piece, Sound, Status, TGM3Event,
+    TGM3Sound, TSpin, TetrisEvent,
+};
+
+#[derive(Error, Debug)]
+pub enum PuzzleError {
+    #[error("unknown goal {0:?}")]
+    Goal(String),
+    #[error("puzzle without a goal")]
+    NoGoal,
+    #[error("allow hold is yes or no, not {0:?}")]
+    Hold(String),
+    #[error("no puzzles")]
+    Empty,
+    #[error("{0}")]
+    Board(#[from] BoardParseError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleGoal {
+    PerfectClear,
+    /// Lines in total.
+    Lines(usize),
+    /// A full T-spin clearing this many lines at once.
+    TSpin(usize),
+}
+
+impl Display for PuzzleGoal {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PuzzleGoal::PerfectClear => write!(f, "perfect clear"),
+            PuzzleGoal::Lines(1) => write!(f, "clear 1 line"),
+            PuzzleGoal::Lines(lines) => write!(f, "clear {lines} lines"),
+            PuzzleGoal::TSpin(1) => write!(f, "t-spin single"),
+            PuzzleGoal::TSpin(2) => write!(f, "t-spin double"),
+            PuzzleGoal::TSpin(3) => write!(f, "t-spin triple"),
+            PuzzleGoal::TSpin(lines) => write!(f, "t-spin {lines}"),
+        }
+    }
+}
+
+/// Takes what `Display` writes, plus `pc`, `lines N`, `tss`, `tsd` and `tst`.
+#[cfg(feature = "std")]
+impl FromStr for PuzzleGoal {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let goal = s.trim().to_ascii_lowercase();
+        let words = goal.split_whitespace().collect::<Vec<_>>();
+        let goal = match words[..] {
+            ["perfect", "clear"] | ["pc"] => PuzzleGoal::PerfectClear,
+            ["clear", lines, "line" | "lines"] | ["lines", lines] => match lines.parse() {
+                Ok(lines) => PuzzleGoal::Lines(lines),
+                Err(_) => return Err(PuzzleError::Goal(s.trim().to_string())),
+            },
+            ["t-spin", "single"] | ["tss"] => PuzzleGoal::TSpin(1),
+            ["t-spin", "double"] | ["tsd"] => PuzzleGoal::TSpin(2),
+            ["t-spin", "triple"] | ["tst"] => PuzzleGoal::TSpin(3),
+            _ => return Err(PuzzleError::Goal(s.trim().to_string())),
+        };
+        Ok(goal)
+    }
+}
+
+/// One puzzle of a pack.
+#[derive(Debug, Clone)]
+pub struct PuzzleSpec {
+    pub name: String,
+    pub goal: PuzzleGoal,
+    pub hold_enabled: bool,
+    /// Starting field, hold piece and queue.
+    pub board: Board,
+    /// Length of the fixed sequence, `None` for random pieces without end.
+    pub pieces: Option<usize>,
+}
+
+/// `name:`, `goal:` and `allow hold:` lines followed by a board in the form
+/// `Board` parses:
+///
+/// ```text
+/// name: first t-spin double
+/// goal: tsd
+/// allow hold: no
+/// next: TI
+/// ..........
+/// GG...GGGGG
+/// GGG.GGGGGG
+/// ```
+#[cfg(feature = "std")]
+impl FromStr for PuzzleSpec {
+    type Err = PuzzleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::new();
+        let mut goal = None;
+        let mut hold_enabled = true;
+        let mut pieces = None;
+        let mut board = String::new();
+        for line in s.lines().map(str::trim) {
+            if let Some(value) = line.strip_prefix("name:") {
+                name = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("goal:") {
+                goal = Some(value.parse()?);
+            } else if let Some(value) = line.strip_prefix("allow hold:") {
+                hold_enabled = match value.trim() {
+                    "yes" => true,
+                    "no" => false,
+                    value => return Err(PuzzleError::Hold(value.to_string())),
+                };
+            } else {
+                if let Some(next) = line.strip_prefix("next:") {
+                    pieces = Some(next.trim().chars().count());
+                }
+                board.push_str(line);
+                board.push('\n');
+            }
+        }
+        Ok(Self {
+            name,
+            goal: goal.ok_or(PuzzleError::NoGoal)?,
+            hold_enabled,
+            board: board.parse()?,
+            pieces,
+        })
+    }
+}
+
+/// Puzzles separated by `---` lines.
+#[cfg(feature = "std")]
+pub fn puzzles_from_str(s: &str) -> Result<Vec<PuzzleSpec>, PuzzleError> {
+    let mut pack = vec![];
+    let mut puzzle = String::new();
+    for line in s.lines().chain(["---"]) {
+        if line.trim() == "---" {
+            if !puzzle.trim().is_empty() {
+                pack.push(puzzle.parse()?);
+            }
+            puzzle.clear();
+        } else {
+            puzzle.push_str(line);
+            puzzle.push('\n');
+        }
+    }
+    if pack.is_empty() {
+        return Err(PuzzleError::Empty);
+    }
+    Ok(pack)
+}
+
+/// Plays through a pack of puzzles. A puzzle fails on topping out or when
+/// the next piece would come after its fixed sequence.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub inner: Game,
+    pack: Vec<PuzzleSpec>,
+    index: usize,
+    lines: usize,
+    /// Pieces of the fixed sequence still waiting in the queue, `None`
+    /// without one. The board tops its queue up past them, but those pieces
+    /// never come out.
+    queue: Option<usize>,
+    t_spin: Option<TSpin>,
+    solved: bool,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Puzzle {
+    /// Starts at the first puzzle, `pack` can't be empty.
+    pub fn new(pack: Vec<PuzzleSpec>) -> Self {
+        Self::with_index(pack, 0)
+    }
+
+    fn with_index(pack: Vec<PuzzleSpec>, index: usize) -> Self {
+        let spec = &pack[index];
+        let mut inner = Game::from_board(spec.board.clone());
+        inner.set_hold_enabled(spec.hold_enabled);
+        // the first piece is already falling
+        let queue = spec.pieces.map(|pieces| pieces.saturating_sub(1));
+        Self {
+            inner,
+            pack,
+            index,
+            lines: 0,
+            queue,
+            t_spin: None,
+            solved: false,
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        }
+    }
+
+    /// The next puzzle once this one is solved, otherwise this one again.
+    /// Wraps around after the last puzzle.
+    pub fn retry(&self) -> Self {
+        let index = if self.solved {
+            (self.index + 1) % self.pack.len()
+        } else {
+            self.index
+        };
+        Self::with_index(self.pack.clone(), index)
+    }
+
+    pub fn get_spec(&self) -> &PuzzleSpec {
+        &self.pack[self.index]
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_pack_len(&self) -> usize {
+        self.pack.len()
+    }
+
+    pub fn get_lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Pieces of the fixed sequence left to play, the falling one included,
+    /// `None` without a fixed sequence.
+    pub fn get_pieces_remaining(&self) -> Option<usize> {
+        let falling = self.inner.get_current_piece().is_some() as usize;
+        self.queue.map(|queue| queue + falling)
+    }
+
+    /// The pieces still to come, only those of the fixed sequence when there
+    /// is one.
+    pub fn get_queue(&self) -> Vec<Piece> {
+        let mut next_pieces = self.inner.get_board().next_pieces;
+        if let Some(queue) = self.queue {
+            next_pieces.truncate(queue);
+        }
+        next_pieces
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.envets.push(TGM3Event::StatusChange(status));
+    }
+
+    fn is_goal_met(&self, lines: usize) -> bool {
+        match self.get_spec().goal {
+            PuzzleGoal::PerfectClear => self
+                .inner
+                .get_board()
+                .cells
+                .iter()
+                .all(|cells_x| cells_x.iter().all(Option::is_none)),
+            PuzzleGoal::Lines(goal) => self.lines >= goal,
+            PuzzleGoal::TSpin(goal) => self.t_spin == Some(TSpin::Full) && lines == goal,
+        }
+    }
+
+    fn take_next(&mut self) {
+        if let Some(queue) = self.queue.as_mut() {
+            *queue = queue.saturating_sub(1);
+        }
+    }
+
+    fn game_update(&mut self) {
+        // holding into an empty slot takes the next piece, which has to be
+        // one of the sequence, and a piece spawning first takes one too
+        let held = self.inner.get_hold().is_some();
+        let needed = 1 + self.inner.get_current_piece().is_none() as usize;
+        let hold_enabled = self.get_spec().hold_enabled
+            && (held || self.queue.is_none_or(|queue| queue >= needed));
+        self.inner.set_hold_enabled(hold_enabled);
+
+        self.inner.update();
+        if !held && self.inner.get_hold().is_some() {
+            self.take_next();
+        }
+        let events = self.inner.get_event_queue().clone();
+        for e in events.iter() {
+            match e {
+                TetrisEvent::TSpin(t_spin) => self.t_spin = Some(*t_spin),
+                TetrisEvent::LineCleared(lines) => {
+                    self.lines += lines;
+                    if self.is_goal_met(*lines) {
+                        self.solved = true;
+                        self.set_status(Status::End);
+                        self.sounds.push(TGM3Sound::GameClear);
+                        return;
+                    }
+                }
+                TetrisEvent::PieceSpawned(_) => {
+                    self.t_spin = None;
+                    self.take_next();
+                }
+                _ => {}
+            }
+        }
+        // the sequence is used up once the last piece locks
+        let used_up = self.queue == Some(0) && self.inner.get_current_piece().is_none();
+        if self.inner.is_game_over() || used_up {
+            self.set_status(Status::End);
+        }
+    }
+}
+
+impl GameState for Puzzle {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
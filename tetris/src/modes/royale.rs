@@ -0,0 +1,432 @@
+use alloc::{vec, vec::Vec};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::marathon::guideline_gravity;
+use crate::{
+    AttackTable, Board, Bot, FallingPiece, Game, GameState, GuidelineScore, Input, Piece, Sound,
+    Status, TGM3Event, TGM3Sound, TetrisEvent,
+};
+
+pub const DEFAULT_ROYALE_PLAYERS: usize = 99;
+/// Everyone's gravity goes up one guideline level every this many frames.
+pub const ROYALE_LEVEL_FRAMES: usize = 1800;
+/// Badge point totals needed for each badge, every badge adds a quarter to outgoing attacks.
+pub const BADGE_THRESHOLDS: [usize; 4] = [2, 6, 14, 30];
+/// Frames a bot waits after each piece spawns, picked per bot so they don't all play alike.
+const THINK_FRAMES: core::ops::Range<usize> = 4..24;
+
+/// How a player picks who receives its garbage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Targeting {
+    Random,
+    /// Players currently targeting you.
+    Attackers,
+    /// The player closest to topping out.
+    KOs,
+    /// The player with the most badge points.
+    Badges,
+}
+
+impl Targeting {
+    pub const ALL: [Targeting; 4] = [
+        Targeting::Random,
+        Targeting::Attackers,
+        Targeting::KOs,
+        Targeting::Badges,
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub struct RoyalePlayer {
+    scoring: GuidelineScore,
+    targeting: Targeting,
+    target: Option<usize>,
+    last_attacker: Option<usize>,
+    kos: usize,
+    badge_points: usize,
+    sent: usize,
+    place: Option<usize>,
+    bot: Option<Bot>,
+    think_frames: usize,
+    // frames left before the bot starts on the current piece
+    thinking: usize,
+}
+
+impl RoyalePlayer {
+    fn new(targeting: Targeting, bot: Option<Bot>, think_frames: usize) -> Self {
+        Self {
+            scoring: Default::default(),
+            targeting,
+            target: None,
+            last_attacker: None,
+            kos: 0,
+            badge_points: 0,
+            sent: 0,
+            place: None,
+            bot,
+            think_frames,
+            thinking: think_frames,
+        }
+    }
+
+    pub fn get_scoring(&self) -> &GuidelineScore {
+        &self.scoring
+    }
+
+    pub fn get_targeting(&self) -> Targeting {
+        self.targeting
+    }
+
+    pub fn get_target(&self) -> Option<usize> {
+        self.target
+    }
+
+    pub fn get_kos(&self) -> usize {
+        self.kos
+    }
+
+    /// One for every KO plus the badge points the KO'd player had.
+    pub fn get_badge_points(&self) -> usize {
+        self.badge_points
+    }
+
+    pub fn get_badges(&self) -> usize {
+        BADGE_THRESHOLDS
+            .iter()
+            .filter(|&&t| self.badge_points >= t)
+            .count()
+    }
+
+    pub fn get_sent(&self) -> usize {
+        self.sent
+    }
+
+    /// Final place, `None` while still alive.
+    pub fn get_place(&self) -> Option<usize> {
+        self.place
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.place.is_none()
+    }
+
+    pub fn is_bot(&self) -> bool {
+        self.bot.is_some()
+    }
+}
+
+/// A last-one-standing match: you are player 0 in `inner`, everyone else is
+/// a bot simulated alongside. KOs go to whoever sent the last garbage.
+#[derive(Debug, Clone)]
+pub struct Royale {
+    pub inner: Game,
+    opponents: Vec<Game>,
+    players: Vec<RoyalePlayer>,
+    attack_table: AttackTable,
+    rng: StdRng,
+    alive: usize,
+    frames: usize,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Royale {
+    #[cfg(feature = "std")]
+    pub fn new(players: usize, attack_table: AttackTable) -> Self {
+        Self::with_seed(players, attack_table, rand::random())
+    }
+
+    pub fn with_seed(players: usize, attack_table: AttackTable, seed: u64) -> Self {
+        let players = players.max(2);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let opponents = (1..players)
+            .map(|_| Game::from_board(Board::with_seed(Default::default(), rng.gen())))
+            .collect();
+        let mut royale_players = vec![RoyalePlayer::new(Targeting::Random, None, 0)];
+        for _ in 1..players {
+            let targeting = *Targeting::ALL.choose(&mut rng).unwrap();
+            let think_frames = rng.gen_range(THINK_FRAMES);
+            // dozens of them plan every frame
+            let mut bot = Bot::new();
+            bot.set_lookahead(false);
+            royale_players.push(RoyalePlayer::new(targeting, Some(bot), think_frames));
+        }
+        let mut royale = Self {
+            inner: Game::from_board(Board::with_seed(Default::default(), rng.gen())),
+            opponents,
+            players: royale_players,
+            attack_table,
+            rng,
+            alive: players,
+            frames: 0,
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        };
+        for player in 0..players {
+            royale.retarget(player);
+        }
+        royale.sync_settings();
+        royale
+    }
+
+    pub fn set_targeting(&mut self, targeting: Targeting) {
+        self.players[0].targeting = targeting;
+        self.retarget(0);
+    }
+
+    pub fn get_players(&self) -> &[RoyalePlayer] {
+        &self.players
+    }
+
+    pub fn get_player(&self, player: usize) -> &RoyalePlayer {
+        &self.players[player]
+    }
+
+    /// Player 0 is `inner`.
+    pub fn get_game(&self, player: usize) -> &Game {
+        match player {
+            0 => &self.inner,
+            _ => &self.opponents[player - 1],
+        }
+    }
+
+    fn get_game_mut(&mut self, player: usize) -> &mut Game {
+        match player {
+            0 => &mut self.inner,
+            _ => &mut self.opponents[player - 1],
+        }
+    }
+
+    pub fn get_alive(&self) -> usize {
+        self.alive
+    }
+
+    /// Players whose current target is `player`.
+    pub fn get_attackers(&self, player: usize) -> usize {
+        self.players
+            .iter()
+            .filter(|p| p.is_alive() && p.target == Some(player))
+            .count()
+    }
+
+    pub fn get_frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn get_level(&self) -> usize {
+        1 + self.frames / ROYALE_LEVEL_FRAMES
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.envets.push(TGM3Event::StatusChange(status));
+    }
+
+    fn sync_settings(&mut self) {
+        let gravity = guideline_gravity(self.get_level());
+        self.inner.set_gravity(gravity);
+        for game in self.opponents.iter_mut() {
+            game.set_gravity(gravity);
+        }
+    }
+
+    fn game_update(&mut self) {
+        self.frames += 1;
+        if self.frames.is_multiple_of(ROYALE_LEVEL_FRAMES) {
+            self.sync_settings();
+        }
+        for player in 0..self.players.len() {
+            if self.players[player].is_alive() {
+                self.player_update(player);
+            }
+        }
+
+        for player in 0..self.players.len() {
+            if self.players[player].is_alive() && self.get_game(player).is_game_over() {
+                self.knock_out(player);
+            }
+        }
+        if !self.players[0].is_alive() {
+            self.set_status(Status::End);
+        } else if self.alive == 1 {
+            self.players[0].place = Some(1);
+            self.sounds.push(TGM3Sound::GameClear);
+            self.set_status(Status::End);
+        }
+    }
+
+    fn player_update(&mut self, player: usize) {
+        if let RoyalePlayer {
+            bot: Some(bot),
+            think_frames,
+            thinking,
+            ..
+        } = &mut self.players[player]
+        {
+            let game = &mut self.opponents[player - 1];
+            let input = if game.get_current_piece().is_none() {
+                *thinking = *think_frames;
+                Input::default()
+            } else if *thinking > 0 {
+                *thinking -= 1;
+                Input::default()
+            } else {
+                bot.next_input(game)
+            };
+            game.set_input(input);
+        }
+        let game = self.get_game_mut(player);
+        game.update();
+        let events = game.get_event_queue().clone();
+        let board = game.get_board();
+        if player != 0 {
+            // nobody drains the bots' queues
+            game.get_event_queue().clear();
+            game.get_sound_queue().clear();
+        }
+        let Some(result) = self.players[player].scoring.update(&events, &board) else {
+            return;
+        };
+        let attack = self.attack_table.attack(&result);
+        let attack = attack + attack * self.players[player].get_badges() / 4;
+        let sent = self.get_game_mut(player).cancel_garbage(attack);
+        if sent == 0 {
+            return;
+        }
+        self.players[player].sent += sent;
+        self.retarget(player);
+        if let Some(target) = self.players[player].target {
+            let hole = self.rng.gen_range(0..10);
+            self.get_game_mut(target).receive_garbage(sent, hole);
+            self.players[target].last_attacker = Some(player);
+        }
+    }
+
+    fn knock_out(&mut self, player: usize) {
+        self.players[player].place = Some(self.alive);
+        self.alive -= 1;
+        if let Some(attacker) = self.players[player].last_attacker {
+            if self.players[attacker].is_alive() {
+                let points = 1 + self.players[player].badge_points;
+                let attacker = &mut self.players[attacker];
+                attacker.kos += 1;
+                attacker.badge_points += points;
+            }
+        }
+        for other in 0..self.players.len() {
+            if self.players[other].target == Some(player) {
+                self.retarget(other);
+            }
+        }
+    }
+
+    fn retarget(&mut self, player: usize) {
+        let candidates = (0..self.players.len())
+            .filter(|&p| p != player && self.players[p].is_alive())
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            self.players[player].target = None;
+            return;
+        }
+        let current = self.players[player]
+            .target
+            .filter(|target| candidates.contains(target));
+        let target = match self.players[player].targeting {
+            Targeting::Random => current,
+            Targeting::Attackers => candidates
+                .iter()
+                .copied()
+                .filter(|&p| self.players[p].target == Some(player))
+                .collect::<Vec<_>>()
+                .choose(&mut self.rng)
+                .copied()
+                .or(current),
+            Targeting::KOs => candidates
+                .iter()
+                .copied()
+                .max_by_key(|&p| stack_height(self.get_game(p))),
+            Targeting::Badges => candidates
+                .iter()
+                .copied()
+                .max_by_key(|&p| self.players[p].badge_points),
+        };
+        self.players[player].target = target.or_else(|| candidates.choose(&mut self.rng).copied());
+    }
+}
+
+fn stack_height(game: &Game) -> usize {
+    let board = game.get_board();
+    let top = board
+        .cells
+        .iter()
+        .position(|row| row.iter().any(|cell| cell.is_some()))
+        .unwrap_or(board.cells.len());
+    board.cells.len() - top
+}
+
+impl GameState for Royale {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
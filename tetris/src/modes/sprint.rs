@@ -0,0 +1,162 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Board, FallingPiece, Game, GameState, Input, Piece, Sound, Status, TGM3Event, TGM3Sound,
+    TetrisEvent,
+};
+
+pub const DEFAULT_SPRINT_GOAL: usize = 40;
+
+#[derive(Debug, Clone)]
+pub struct Sprint {
+    pub inner: Game,
+    goal: usize,
+    lines: usize,
+    frames: usize,
+    best: Option<usize>,
+    new_record: bool,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Sprint {
+    #[cfg(feature = "std")]
+    pub fn new(goal: usize) -> Self {
+        Self::with_seed(goal, rand::random())
+    }
+
+    pub fn with_seed(goal: usize, seed: u64) -> Self {
+        Self {
+            inner: Game::with_seed(seed),
+            goal,
+            lines: 0,
+            frames: 0,
+            best: None,
+            new_record: false,
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        }
+    }
+
+    pub fn get_goal(&self) -> usize {
+        self.goal
+    }
+
+    pub fn get_lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn get_lines_remaining(&self) -> usize {
+        self.goal.saturating_sub(self.lines)
+    }
+
+    /// Elapsed frames at 60 fps. Stops counting once the goal is reached.
+    pub fn get_frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn get_best(&self) -> Option<usize> {
+        self.best
+    }
+
+    pub fn set_best(&mut self, best: Option<usize>) {
+        self.best = best;
+    }
+
+    /// The goal was reached faster than `best`, which is updated to match.
+    pub fn is_new_record(&self) -> bool {
+        self.new_record
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        self.frames += 1;
+        let events = self.inner.get_event_queue().clone();
+        for e in events.iter() {
+            if let TetrisEvent::LineCleared(n) = e {
+                self.lines += n;
+            }
+        }
+        if self.lines >= self.goal {
+            // a tie keeps the old record
+            self.new_record = self.best.is_none_or(|best| self.frames < best);
+            if self.new_record {
+                self.best = Some(self.frames);
+            }
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+            self.sounds.push(TGM3Sound::GameClear);
+            return;
+        }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
+    }
+}
+
+impl GameState for Sprint {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
@@ -0,0 +1,354 @@
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
+
+use crate::{
+    Board, Clock, FallingPiece, Game, GameState, Input, Piece, Randomizer, Roll, Sound, Status,
+    TGM3Event, TGM3Sound, TetrisEvent,
+};
+
+const GRADE_SCORES: [usize; 17] = [
+    400, 800, 1400, 2000, 3500, 5500, 8000, 12000, 16000, 22000, 30000, 40000, 52000, 66000, 82000,
+    100000, 120000,
+];
+
+const GM_GRADE: usize = 18;
+
+#[derive(Debug, Clone)]
+pub struct TGM1Master {
+    pub inner: Game,
+    level: usize,
+    score: usize,
+    combo: usize,
+    soft: usize,
+    input: Input,
+    clock: Clock,
+    gm_checks: [Option<bool>; 3],
+    status: Status,
+    start_roll_timer: Option<usize>,
+    roll_timer: Option<usize>,
+    display_grade: usize,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl TGM1Master {
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut inner = Game::from_board(Board::with_seed(Randomizer::tgm1(), seed));
+        inner.set_hold_enabled(false);
+        let mut me = Self {
+            inner,
+            level: 0,
+            score: 0,
+            combo: 1,
+            soft: 0,
+            input: Default::default(),
+            clock: Clock::default(),
+            gm_checks: [None; 3],
+            status: Status::Game,
+            start_roll_timer: None,
+            roll_timer: None,
+            display_grade: 0,
+            envets: vec![],
+            sounds: vec![],
+        };
+        me.sync_settings();
+        me
+    }
+
+    pub fn get_level(&self) -> usize {
+        self.level
+    }
+
+    pub fn get_score(&self) -> usize {
+        self.score
+    }
+
+    fn get_gravity(&self) -> f64 {
+        match self.level {
+            0..=29 => 4.0 / 256.0,
+            30..=34 => 6.0 / 256.0,
+            35..=39 => 8.0 / 256.0,
+            40..=49 => 10.0 / 256.0,
+            50..=59 => 12.0 / 256.0,
+            60..=69 => 16.0 / 256.0,
+            70..=79 => 32.0 / 256.0,
+            80..=89 => 48.0 / 256.0,
+            90..=99 => 64.0 / 256.0,
+            100..=119 => 80.0 / 256.0,
+            120..=139 => 96.0 / 256.0,
+            140..=159 => 112.0 / 256.0,
+            160..=169 => 128.0 / 256.0,
+            170..=199 => 144.0 / 256.0,
+            200..=219 => 4.0 / 256.0,
+            220..=229 => 32.0 / 256.0,
+            230..=232 => 64.0 / 256.0,
+            233..=235 => 96.0 / 256.0,
+            236..=238 => 128.0 / 256.0,
+            239..=242 => 160.0 / 256.0,
+            243..=246 => 192.0 / 256.0,
+            247..=250 => 224.0 / 256.0,
+            251..=299 => 1.0,
+            300..=329 => 2.0,
+            330..=359 => 3.0,
+            360..=399 => 4.0,
+            400..=419 => 5.0,
+            420..=449 => 4.0,
+            450..=499 => 3.0,
+            _ => 20.0,
+        }
+    }
+
+    fn get_are(&self) -> usize {
+        30
+    }
+
+    fn get_line_are(&self) -> usize {
+        30
+    }
+
+    fn get_das(&self) -> usize {
+        16
+    }
+
+    fn get_lock_delay(&self) -> usize {
+        30
+    }
+
+    fn get_line_clear_delay(&self) -> usize {
+        41
+    }
+
+    fn sync_settings(&mut self) {
+        self.inner.set_gravity(self.get_gravity());
+        self.inner.set_are(self.get_are());
+        self.inner.set_line_are(self.get_line_are());
+        self.inner.set_das(self.get_das());
+        self.inner.set_lock_delay(self.get_lock_delay());
+        self.inner.set_line_clear_delay(self.get_line_clear_delay());
+    }
+
+    fn gm_check(&self, check: usize) -> (usize, Duration) {
+        [
+            (12000, Duration::from_secs(4 * 60 + 15)),
+            (40000, Duration::from_secs(7 * 60 + 30)),
+            (126000, Duration::from_secs(13 * 60 + 30)),
+        ][check]
+    }
+
+    fn check_gm(&mut self, check: usize) {
+        if self.gm_checks[check].is_some() {
+            return;
+        }
+        let (score, time) = self.gm_check(check);
+        let passed = self.score >= score && self.clock.elapsed() <= time;
+        self.gm_checks[check] = Some(passed);
+    }
+
+    fn level_up(&mut self, up: usize, line_clear: bool) {
+        let prev = self.level;
+
+        if line_clear || (prev + up) % 100 > prev % 100 && prev + up < 998 {
+            self.level += up;
+        }
+        if self.level > 999 {
+            self.level = 999;
+        }
+
+        if prev < 300 && self.level >= 300 {
+            self.check_gm(0);
+        }
+        if prev < 500 && self.level >= 500 {
+            self.check_gm(1);
+        }
+
+        if line_clear && prev % 100 > self.level % 100 {
+            self.inner.get_sound_queue().push(Sound::RankUp);
+        }
+        if self.level == 999 {
+            self.check_gm(2);
+            self.status = Status::Clear;
+            self.envets.push(TGM3Event::StatusChange(Status::Clear));
+            self.sounds.push(TGM3Sound::GameClear);
+        }
+    }
+
+    fn game_line_clear(&mut self, n: usize) {
+        self.combo += 2 * n - 2;
+        let bravo = if self
+            .inner
+            .get_board()
+            .cells
+            .iter()
+            .all(|cells_x| cells_x.iter().all(|c| c.is_none()))
+        {
+            4
+        } else {
+            1
+        };
+        self.score += ((self.level + n).div_ceil(4) + self.soft) * n * self.combo * bravo;
+        self.level_up(n, true);
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        if self.input.soft_drop && self.inner.get_current_piece().is_some() {
+            self.soft += 1;
+        }
+        let events = self.inner.get_event_queue().clone();
+        let cleared = events
+            .iter()
+            .any(|e| matches!(e, TetrisEvent::LineCleared(_)));
+        for e in events.iter() {
+            match e {
+                TetrisEvent::LineCleared(n) => {
+                    self.game_line_clear(*n);
+                }
+                TetrisEvent::PieceLocked(_) if !cleared => {
+                    self.combo = 1;
+                }
+                TetrisEvent::PieceSpawned(_) => {
+                    self.soft = 0;
+                    self.level_up(1, false);
+                }
+                _ => {}
+            }
+        }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
+        self.sync_settings();
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn get_grade(&self) -> usize {
+        if self.gm_checks.into_iter().all(|c| c.unwrap_or(false)) {
+            return GM_GRADE;
+        }
+        GRADE_SCORES.iter().filter(|&&s| self.score >= s).count()
+    }
+
+    pub fn get_display_grade(&self) -> usize {
+        self.display_grade
+    }
+
+    pub fn get_next_grade_score(&self) -> Option<usize> {
+        GRADE_SCORES.iter().copied().find(|&s| self.score < s)
+    }
+
+    fn update_display_grade(&mut self) {
+        let grade = self.get_grade();
+        if grade > self.display_grade {
+            self.envets.push(TGM3Event::GradeUp(grade));
+            self.sounds.push(TGM3Sound::GradeUp);
+        }
+        self.display_grade = grade;
+    }
+}
+
+impl GameState for TGM1Master {
+    fn update(&mut self) {
+        self.clock.tick();
+        match self.status {
+            Status::Game => self.game_update(),
+            Status::Clear => {
+                if self.start_roll_timer.is_none() {
+                    self.start_roll_timer = Some(150)
+                }
+                if let Some(timer) = self.start_roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.inner.clear_board();
+                        self.status = Status::Roll(Roll::Normal);
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            Status::Roll(_) => {
+                self.inner.update();
+                if self.inner.is_game_over() {
+                    self.status = Status::End;
+                    self.envets.push(TGM3Event::StatusChange(Status::End));
+                    return;
+                }
+                if self.roll_timer.is_none() {
+                    self.roll_timer = Some(3238);
+                }
+                if let Some(timer) = self.roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.status = Status::End;
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.update_display_grade();
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.input = input;
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
@@ -0,0 +1,512 @@
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
+
+use crate::{
+    Board, Clock, FallingPiece, Game, GameState, Input, Piece, Randomizer, Roll, Sound, Status,
+    TGM3Event, TGM3Sound, TetrisEvent,
+};
+
+const SECTIONS: usize = 10;
+const M_GRADE: usize = 18;
+const GM_GRADE: usize = 19;
+// the grade shown for each internal grade
+const GRADES: [usize; 32] = [
+    0, 1, 2, 3, 4, 5, 5, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 11, 12, 12, 12, 13, 13, 14, 14, 15,
+    15, 16, 16, 17,
+];
+
+#[derive(Debug, Clone)]
+pub struct TGM2Master {
+    pub inner: Game,
+    level: usize,
+    grade_points: usize,
+    internal_grade: usize,
+    decay_counter: usize,
+    combo: usize,
+    clock: Clock,
+    section_times: [Option<Duration>; SECTIONS],
+    section_tetrises: [usize; SECTIONS],
+    status: Status,
+    start_roll_timer: Option<usize>,
+    roll_timer: Option<usize>,
+    // decided when the roll starts
+    m_roll: bool,
+    display_grade: usize,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl TGM2Master {
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut inner = Game::from_board(Board::with_seed(Randomizer::tgm2(), seed));
+        inner.set_hold_enabled(false);
+        let mut me = Self {
+            inner,
+            level: 0,
+            grade_points: 0,
+            internal_grade: 0,
+            decay_counter: 0,
+            combo: 1,
+            clock: Clock::default(),
+            section_times: [None; SECTIONS],
+            section_tetrises: [0; SECTIONS],
+            status: Status::Game,
+            start_roll_timer: None,
+            roll_timer: None,
+            m_roll: false,
+            display_grade: 0,
+            envets: vec![],
+            sounds: vec![],
+        };
+        me.sync_settings();
+        me
+    }
+
+    pub fn get_level(&self) -> usize {
+        self.level
+    }
+
+    fn get_gravity(&self) -> f64 {
+        match self.level {
+            0..=29 => 4.0 / 256.0,
+            30..=34 => 6.0 / 256.0,
+            35..=39 => 8.0 / 256.0,
+            40..=49 => 10.0 / 256.0,
+            50..=59 => 12.0 / 256.0,
+            60..=69 => 16.0 / 256.0,
+            70..=79 => 32.0 / 256.0,
+            80..=89 => 48.0 / 256.0,
+            90..=99 => 64.0 / 256.0,
+            100..=119 => 80.0 / 256.0,
+            120..=139 => 96.0 / 256.0,
+            140..=159 => 112.0 / 256.0,
+            160..=169 => 128.0 / 256.0,
+            170..=199 => 144.0 / 256.0,
+            200..=219 => 4.0 / 256.0,
+            220..=229 => 32.0 / 256.0,
+            230..=232 => 64.0 / 256.0,
+            233..=235 => 96.0 / 256.0,
+            236..=238 => 128.0 / 256.0,
+            239..=242 => 160.0 / 256.0,
+            243..=246 => 192.0 / 256.0,
+            247..=250 => 224.0 / 256.0,
+            251..=299 => 1.0,
+            300..=329 => 2.0,
+            330..=359 => 3.0,
+            360..=399 => 4.0,
+            400..=419 => 5.0,
+            420..=449 => 4.0,
+            450..=499 => 3.0,
+            _ => 20.0,
+        }
+    }
+
+    fn get_are(&self) -> usize {
+        match self.level {
+            0..=699 => 25,
+            700..=799 => 16,
+            _ => 12,
+        }
+    }
+
+    fn get_line_are(&self) -> usize {
+        match self.level {
+            0..=599 => 25,
+            600..=699 => 16,
+            700..=799 => 12,
+            _ => 6,
+        }
+    }
+
+    fn get_das(&self) -> usize {
+        match self.level {
+            0..=499 => 14,
+            500..=899 => 8,
+            _ => 6,
+        }
+    }
+
+    fn get_lock_delay(&self) -> usize {
+        match self.level {
+            0..=899 => 30,
+            _ => 17,
+        }
+    }
+
+    fn get_line_clear_delay(&self) -> usize {
+        match self.level {
+            0..=499 => 40,
+            500..=599 => 25,
+            600..=699 => 16,
+            700..=799 => 12,
+            _ => 6,
+        }
+    }
+
+    fn sync_settings(&mut self) {
+        self.inner.set_gravity(self.get_gravity());
+        self.inner.set_are(self.get_are());
+        self.inner.set_line_are(self.get_line_are());
+        self.inner.set_das(self.get_das());
+        self.inner.set_lock_delay(self.get_lock_delay());
+        self.inner.set_line_clear_delay(self.get_line_clear_delay());
+    }
+
+    fn get_grade_point_bonus(&self, n: usize) -> usize {
+        let bonus = match self.internal_grade {
+            0 => [10, 20, 40, 50],
+            1..=2 => [10, 20, 30, 40],
+            3 => [10, 15, 30, 40],
+            4 => [10, 15, 20, 40],
+            5 => [5, 15, 20, 30],
+            6 => [5, 10, 20, 30],
+            7..=9 => [5, 10, 15, 30],
+            _ => [2, 12, 13, 30],
+        };
+        bonus[n - 1]
+    }
+
+    fn get_decay_rate(&self) -> usize {
+        [
+            125, 80, 80, 50, 45, 45, 45, 40, 40, 40, 40, 40, 30, 30, 30, 20, 20, 20, 20, 20, 15,
+            15, 15, 15, 15, 15, 15, 15, 15, 15, 10, 10,
+        ][self.internal_grade]
+    }
+
+    // combo multipliers scaled by 10
+    fn get_combo_multiplier(&self, n: usize) -> usize {
+        [
+            [10, 10, 10, 10],
+            [10, 12, 14, 15],
+            [10, 12, 15, 18],
+            [10, 14, 16, 20],
+            [10, 14, 17, 22],
+            [10, 14, 18, 23],
+            [10, 14, 19, 24],
+            [10, 15, 20, 25],
+            [10, 15, 21, 26],
+            [10, 20, 25, 30],
+        ][self.combo.min(10) - 1][n - 1]
+    }
+
+    fn section_border(&self, rank: usize) -> Duration {
+        if rank < 5 {
+            return Duration::from_secs(65);
+        }
+        let first_half = self.section_times[..5]
+            .iter()
+            .flatten()
+            .fold(Duration::from_secs(0), |sum, &x| sum + x);
+        first_half / 5 + Duration::from_secs(2)
+    }
+
+    fn section_tetris_border(&self, rank: usize) -> usize {
+        match rank {
+            0..=3 => 2,
+            4..=8 => 1,
+            _ => 0,
+        }
+    }
+
+    fn is_m_roll_qualified(&self) -> bool {
+        let sections_ok = (0..SECTIONS).all(|rank| {
+            self.section_times[rank].is_some_and(|t| t <= self.section_border(rank))
+                && self.section_tetrises[rank] >= self.section_tetris_border(rank)
+        });
+        sections_ok
+            && GRADES[self.internal_grade] >= M_GRADE - 1
+            && self.section_time_total() <= Duration::from_secs(8 * 60 + 45)
+    }
+
+    fn section_time_total(&self) -> Duration {
+        self.section_times
+            .into_iter()
+            .flatten()
+            .fold(Duration::from_secs(0), |sum, x| sum + x)
+    }
+
+    fn level_up(&mut self, up: usize, line_clear: bool) {
+        let prev = self.level;
+
+        if line_clear || (prev + up) % 100 > prev % 100 && prev + up < 998 {
+            self.level += up;
+        }
+        if self.level > 999 {
+            self.level = 999;
+        }
+
+        if line_clear && (prev % 100 > self.level % 100 || self.level == 999) {
+            let rank = prev / 100;
+            self.section_times[rank] = Some(self.clock.elapsed() - self.section_time_total());
+            if self.level == 999 {
+                self.status = Status::Clear;
+                self.envets.push(TGM3Event::StatusChange(Status::Clear));
+                self.sounds.push(TGM3Sound::GameClear);
+            } else {
+                self.inner.get_sound_queue().push(Sound::RankUp);
+            }
+        }
+    }
+
+    fn game_line_clear(&mut self, n: usize) {
+        let bonus = self.get_grade_point_bonus(n) * self.get_combo_multiplier(n);
+        self.grade_points += bonus.div_ceil(10) * (self.level / 250 + 1);
+        if self.internal_grade < 31 && self.grade_points >= 100 {
+            self.grade_points = 0;
+            self.internal_grade += 1;
+        }
+        if n >= 2 {
+            self.combo += 1;
+        }
+        if n == 4 {
+            self.section_tetrises[self.level / 100] += 1;
+        }
+        self.level_up(n, true);
+    }
+
+    fn decay(&mut self) {
+        if self.inner.get_current_piece().is_none() || self.combo > 1 || self.grade_points == 0 {
+            return;
+        }
+        self.decay_counter += 1;
+        if self.decay_counter >= self.get_decay_rate() {
+            self.decay_counter = 0;
+            self.grade_points -= 1;
+        }
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        self.decay();
+        let events = self.inner.get_event_queue().clone();
+        let cleared = events
+            .iter()
+            .any(|e| matches!(e, TetrisEvent::LineCleared(_)));
+        for e in events.iter() {
+            match e {
+                TetrisEvent::LineCleared(n) => {
+                    self.game_line_clear(*n);
+                }
+                TetrisEvent::PieceLocked(_) if !cleared => {
+                    self.combo = 1;
+                }
+                TetrisEvent::PieceSpawned(_) => {
+                    self.level_up(1, false);
+                }
+                _ => {}
+            }
+        }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
+        self.sync_settings();
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn get_internal_grade(&self) -> usize {
+        self.internal_grade
+    }
+
+    pub fn get_grade(&self) -> usize {
+        match self.status {
+            Status::Roll(Roll::Invisible) => M_GRADE,
+            // topping out in the invisible roll keeps M, surviving it is GM
+            Status::End if self.m_roll && !self.inner.is_game_over() => GM_GRADE,
+            Status::End if self.m_roll => M_GRADE,
+            _ => GRADES[self.internal_grade],
+        }
+    }
+
+    pub fn get_display_grade(&self) -> usize {
+        self.display_grade
+    }
+
+    fn update_display_grade(&mut self) {
+        let grade = self.get_grade();
+        if grade > self.display_grade {
+            self.envets.push(TGM3Event::GradeUp(grade));
+            self.sounds.push(TGM3Sound::GradeUp);
+        }
+        self.display_grade = grade;
+    }
+}
+
+impl GameState for TGM2Master {
+    fn update(&mut self) {
+        self.clock.tick();
+        match self.status {
+            Status::Game => self.game_update(),
+            Status::Clear => {
+                if self.start_roll_timer.is_none() {
+                    self.start_roll_timer = Some(150)
+                }
+                if let Some(timer) = self.start_roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.inner.clear_board();
+                        self.m_roll = self.is_m_roll_qualified();
+                        self.status = Status::Roll(if self.m_roll {
+                            Roll::Invisible
+                        } else {
+                            Roll::Normal
+                        });
+                        self.envets.push(TGM3Event::StatusChange(self.status));
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            Status::Roll(_) => {
+                self.inner.update();
+                if self.inner.is_game_over() {
+                    self.status = Status::End;
+                    self.envets.push(TGM3Event::StatusChange(Status::End));
+                    return;
+                }
+                if self.roll_timer.is_none() {
+                    self.roll_timer = Some(3238);
+                }
+                if let Some(timer) = self.roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.status = Status::End;
+                        self.envets.push(TGM3Event::StatusChange(Status::End));
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.update_display_grade();
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
+
+// the roll can't be reached through play in a test, so these set the run up
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A run that cleared level 999 fast enough for the invisible roll.
+    fn qualified() -> TGM2Master {
+        let mut game = TGM2Master::with_seed(0);
+        game.set_clock(Clock::frames());
+        game.level = 999;
+        game.internal_grade = GRADES.len() - 1;
+        game.section_times = [Some(Duration::from_secs(45)); SECTIONS];
+        game.section_tetrises = [2; SECTIONS];
+        game.status = Status::Clear;
+        game.sync_settings();
+        game
+    }
+
+    /// Updates until the roll ends, keeping the field clear unless the
+    /// pieces are left to stack up and top out.
+    fn play_roll(game: &mut TGM2Master, top_out: bool) {
+        for _ in 0..4000 {
+            if let Status::End = game.get_status() {
+                return;
+            }
+            if !top_out {
+                game.inner.clear_board();
+            }
+            game.update();
+        }
+        panic!("the roll didn't end");
+    }
+
+    #[test]
+    fn surviving_m_roll_is_gm() {
+        let mut game = qualified();
+        play_roll(&mut game, false);
+        assert_eq!(game.roll_timer, Some(0));
+        assert!(game.m_roll);
+        assert_eq!(game.get_grade(), GM_GRADE);
+        assert_eq!(game.get_display_grade(), GM_GRADE);
+        let events = game.get_tgm3events();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, TGM3Event::StatusChange(Status::End))));
+    }
+
+    #[test]
+    fn topping_out_in_m_roll_keeps_m() {
+        let mut game = qualified();
+        play_roll(&mut game, true);
+        assert!(game.inner.is_game_over());
+        assert_eq!(game.get_grade(), M_GRADE);
+        assert_eq!(game.get_display_grade(), M_GRADE);
+    }
+
+    #[test]
+    fn slow_run_gets_normal_roll() {
+        let mut game = qualified();
+        game.section_times[9] = Some(Duration::from_secs(90));
+        play_roll(&mut game, false);
+        assert!(!game.m_roll);
+        assert_eq!(game.get_grade(), GRADES[GRADES.len() - 1]);
+    }
+}
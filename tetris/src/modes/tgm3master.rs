@@ -1,8 +1,11 @@
-use std::time::{Duration, Instant};
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
 
 use arrayvec::ArrayVec;
 
-use crate::{Board, FallingPiece, Game, GameState, Input, Piece, Sound, TetrisEvent};
+use crate::{Board, Clock, FallingPiece, Game, GameState, Input, Piece, Sound, TetrisEvent};
+
+const MAX_GRADE: usize = 32;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Status {
@@ -23,12 +26,14 @@ pub enum TGM3Event {
     StatusChange(Status),
     GotCool,
     GotRegret,
+    GradeUp(usize),
 }
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum TGM3Sound {
     Cool,
     GameClear,
+    GradeUp,
 }
 
 #[derive(Debug, Clone)]
@@ -38,8 +43,9 @@ pub struct TGM3Master {
     speed_level: usize,
     grade_points: usize,
     grade: usize,
+    display_grade: usize,
     roll_points: usize,
-    start_time: Instant,
+    clock: Clock,
     section_times: [Option<Duration>; 9],
     cool_line_section_times: [Option<Duration>; 9],
     cools: [Option<bool>; 9],
@@ -53,19 +59,26 @@ pub struct TGM3Master {
 }
 
 impl TGM3Master {
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Same piece sequence for the same seed.
+    pub fn with_seed(seed: u64) -> Self {
         let mut opacity_timers = ArrayVec::new();
         for _ in 0..40 {
             opacity_timers.push(ArrayVec::from([None; 10]));
         }
         let mut me = Self {
-            inner: Game::new(),
+            inner: Game::with_seed(seed),
             level: 0,
             speed_level: 0,
             grade_points: 0,
             grade: 0,
+            display_grade: 0,
             roll_points: 0,
-            start_time: Instant::now(),
+            clock: Clock::default(),
             section_times: [None; 9],
             cool_line_section_times: [None; 9],
             cools: [None; 9],
@@ -292,6 +305,7 @@ impl TGM3Master {
             if let Some(current_cool_section_time) = self.cool_line_section_times[rank] {
                 let cool = self.cool_border(rank) > current_cool_section_time;
                 self.cools[rank] = Some(cool);
+                #[cfg(feature = "std")]
                 println!("cool: {cool}");
                 if cool {
                     self.envets.push(TGM3Event::GotCool);
@@ -318,7 +332,7 @@ impl TGM3Master {
     }
 
     fn current_section_time(&self) -> Duration {
-        Instant::now() - self.start_time - self.section_time_total()
+        self.clock.elapsed() - self.section_time_total()
     }
 
     fn rank_up(&mut self) {
@@ -385,6 +399,10 @@ impl TGM3Master {
                 _ => {}
             }
         }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
         self.sync_settings();
     }
 
@@ -465,26 +483,38 @@ impl TGM3Master {
         self.status
     }
 
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
     pub fn get_aggregate_grade(&self) -> usize {
-        self.cools
-            .into_iter()
-            .filter_map(|t| t)
-            .fold(0, |sum, c| sum + if c { 1 } else { 0 })
+        let grade = self.cools.into_iter().flatten().filter(|&c| c).count()
             + self.roll_points / 100
             + [
                 0, 1, 2, 3, 4, 5, 5, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 11, 12, 12, 12, 13, 13,
                 14, 14, 15, 15, 16, 16, 17,
-            ][self.grade]
-            - self
-                .regrets
-                .into_iter()
-                .filter_map(|t| t)
-                .fold(0, |sum, c| sum + if c { 1 } else { 0 })
+            ][self.grade];
+        let regrets = self.regrets.into_iter().flatten().filter(|&r| r).count();
+        grade.saturating_sub(regrets).min(MAX_GRADE)
+    }
+
+    pub fn get_display_grade(&self) -> usize {
+        self.display_grade
+    }
+
+    fn update_display_grade(&mut self) {
+        let grade = self.get_aggregate_grade();
+        if grade > self.display_grade {
+            self.envets.push(TGM3Event::GradeUp(grade));
+            self.sounds.push(TGM3Sound::GradeUp);
+        }
+        self.display_grade = grade;
     }
 }
 
 impl GameState for TGM3Master {
     fn update(&mut self) {
+        self.clock.tick();
         match self.status {
             Status::Game => self.game_update(),
             Status::Clear => {
@@ -507,19 +537,25 @@ impl GameState for TGM3Master {
             }
             Status::Roll(roll) => {
                 self.roll_update(roll);
+                if self.inner.is_game_over() {
+                    self.status = Status::End;
+                    self.envets.push(TGM3Event::StatusChange(Status::End));
+                    return;
+                }
                 if let None = self.roll_timer {
                     self.roll_timer = Some(3238);
                 }
                 if let Some(timer) = self.roll_timer.as_mut() {
                     if *timer == 0 {
                         self.status = Status::End;
-                        return;
+                    } else {
+                        *timer -= 1;
                     }
-                    *timer -= 1;
                 }
             }
             _ => {}
         }
+        self.update_display_grade();
     }
 
     fn get_board(&self) -> Board {
@@ -561,4 +597,8 @@ impl GameState for TGM3Master {
     fn set_input(&mut self, input: Input) {
         self.inner.set_input(input)
     }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
 }
@@ -0,0 +1,372 @@
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
+
+use crate::{
+    Board, Clock, FallingPiece, Game, GameState, Input, Piece, Roll, Sound, Status, TGM3Event,
+    TGM3Sound, TetrisEvent,
+};
+
+const MAX_LEVEL: usize = 1300;
+const SECTIONS: usize = 13;
+
+#[derive(Debug, Clone)]
+pub struct TGM3Shirase {
+    pub inner: Game,
+    level: usize,
+    clock: Clock,
+    section_times: [Option<Duration>; SECTIONS],
+    regrets: [Option<bool>; SECTIONS],
+    garbage_counter: usize,
+    status: Status,
+    start_roll_timer: Option<usize>,
+    roll_timer: Option<usize>,
+    display_grade: usize,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl TGM3Shirase {
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut me = Self {
+            inner: Game::with_seed(seed),
+            level: 0,
+            clock: Clock::default(),
+            section_times: [None; SECTIONS],
+            regrets: [None; SECTIONS],
+            garbage_counter: 0,
+            status: Status::Game,
+            start_roll_timer: None,
+            roll_timer: None,
+            display_grade: 0,
+            envets: vec![],
+            sounds: vec![],
+        };
+        me.sync_settings();
+        me
+    }
+
+    pub fn get_level(&self) -> usize {
+        self.level
+    }
+
+    fn get_are(&self) -> usize {
+        match self.level {
+            0..=299 => 12,
+            300..=399 => 6,
+            400..=499 => 5,
+            _ => 4,
+        }
+    }
+
+    fn get_line_are(&self) -> usize {
+        match self.level {
+            0..=99 => 8,
+            100..=199 => 7,
+            200..=399 => 6,
+            400..=499 => 5,
+            _ => 4,
+        }
+    }
+
+    fn get_das(&self) -> usize {
+        match self.level {
+            0..=199 => 10,
+            200..=299 => 9,
+            300..=399 => 8,
+            _ => 6,
+        }
+    }
+
+    fn get_line_clear_delay(&self) -> usize {
+        match self.level {
+            0..=99 => 6,
+            100..=199 => 5,
+            200..=399 => 4,
+            _ => 3,
+        }
+    }
+
+    fn get_lock_delay(&self) -> usize {
+        match self.level {
+            0..=199 => 18,
+            200..=299 => 17,
+            300..=399 => 15,
+            400..=499 => 13,
+            500..=599 => 12,
+            600..=1099 => 10,
+            _ => 8,
+        }
+    }
+
+    fn get_gravity(&self) -> f64 {
+        20.0
+    }
+
+    fn get_garbage_quota(&self) -> Option<usize> {
+        match self.level {
+            500..=599 => Some(20),
+            600..=699 => Some(18),
+            700..=799 => Some(10),
+            800..=899 => Some(9),
+            900..=999 => Some(8),
+            _ => None,
+        }
+    }
+
+    fn torikan(&self, level: usize) -> Option<Duration> {
+        match level {
+            500 => Some(Duration::from_secs(148)),
+            1000 => Some(Duration::from_secs(296)),
+            _ => None,
+        }
+    }
+
+    fn regret_border(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn sync_settings(&mut self) {
+        self.inner.set_gravity(self.get_gravity());
+        self.inner.set_are(self.get_are());
+        self.inner.set_line_are(self.get_line_are());
+        self.inner.set_das(self.get_das());
+        self.inner.set_lock_delay(self.get_lock_delay());
+        self.inner.set_line_clear_delay(self.get_line_clear_delay());
+        // the bone block segment is played with big pieces
+        self.inner.set_big(self.is_bone_block());
+    }
+
+    fn level_up(&mut self, up: usize, line_clear: bool) {
+        let prev = self.level;
+
+        if line_clear || (prev + up) % 100 > prev % 100 && prev + up < MAX_LEVEL {
+            self.level += up;
+        }
+
+        if line_clear && (prev % 100 > self.level % 100 || self.level >= MAX_LEVEL) {
+            if self.level > MAX_LEVEL {
+                self.level = MAX_LEVEL;
+            }
+            self.rank_up(prev / 100);
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    fn section_time_total(&self) -> Duration {
+        self.section_times
+            .into_iter()
+            .flatten()
+            .fold(Duration::from_secs(0), |sum, x| sum + x)
+    }
+
+    fn rank_up(&mut self, rank: usize) {
+        let section_time = self.elapsed() - self.section_time_total();
+        self.section_times[rank] = Some(section_time);
+        let regret = self.regret_border() < section_time;
+        self.regrets[rank] = Some(regret);
+        if regret {
+            self.envets.push(TGM3Event::GotRegret);
+        }
+
+        if self.level == MAX_LEVEL {
+            self.set_status(Status::Clear);
+            self.sounds.push(TGM3Sound::GameClear);
+            return;
+        }
+
+        let section_start = self.level / 100 * 100;
+        if let Some(border) = self.torikan(section_start) {
+            if self.elapsed() > border {
+                self.level = section_start;
+                self.set_status(Status::End);
+                return;
+            }
+        }
+        self.inner.get_sound_queue().push(Sound::RankUp);
+    }
+
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.envets.push(TGM3Event::StatusChange(status));
+    }
+
+    fn raise_garbage(&mut self) {
+        let bottom = self.inner.get_board().cells[39].clone();
+        self.inner.push_row(bottom);
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        let events = self.inner.get_event_queue().clone();
+        let cleared = events
+            .iter()
+            .any(|e| matches!(e, TetrisEvent::LineCleared(_)));
+        for e in events.iter() {
+            match e {
+                TetrisEvent::LineCleared(n) => {
+                    // a big piece fills two rows for every line
+                    let n = if self.inner.is_big() {
+                        n.div_ceil(2)
+                    } else {
+                        *n
+                    };
+                    let up = match n {
+                        3 => 4,
+                        4 => 6,
+                        _ => n,
+                    };
+                    self.level_up(up, true);
+                }
+                TetrisEvent::PieceSpawned(_) => {
+                    self.level_up(1, false);
+                }
+                TetrisEvent::PieceLocked(_) if !cleared => {
+                    if let Some(quota) = self.get_garbage_quota() {
+                        self.garbage_counter += 1;
+                        if self.garbage_counter >= quota {
+                            self.garbage_counter = 0;
+                            self.raise_garbage();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if self.inner.is_game_over() {
+            self.set_status(Status::End);
+        }
+        self.sync_settings();
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn is_bone_block(&self) -> bool {
+        self.level >= 1000
+    }
+
+    pub fn get_grade(&self) -> usize {
+        let sections = self.section_times.into_iter().flatten().count();
+        let regrets = self.regrets.into_iter().flatten().filter(|&r| r).count();
+        sections.saturating_sub(regrets)
+    }
+
+    pub fn get_display_grade(&self) -> usize {
+        self.display_grade
+    }
+
+    fn update_display_grade(&mut self) {
+        let grade = self.get_grade();
+        if grade > self.display_grade {
+            self.envets.push(TGM3Event::GradeUp(grade));
+            self.sounds.push(TGM3Sound::GradeUp);
+        }
+        self.display_grade = grade;
+    }
+}
+
+impl GameState for TGM3Shirase {
+    fn update(&mut self) {
+        self.clock.tick();
+        match self.status {
+            Status::Game => self.game_update(),
+            Status::Clear => {
+                if self.start_roll_timer.is_none() {
+                    self.start_roll_timer = Some(150)
+                }
+                if let Some(timer) = self.start_roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.inner.clear_board();
+                        self.set_status(Status::Roll(Roll::Normal));
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            Status::Roll(_) => {
+                self.inner.update();
+                if self.inner.is_game_over() {
+                    self.set_status(Status::End);
+                    return;
+                }
+                if self.roll_timer.is_none() {
+                    self.roll_timer = Some(3238);
+                }
+                if let Some(timer) = self.roll_timer.as_mut() {
+                    if *timer == 0 {
+                        self.set_status(Status::End);
+                    } else {
+                        *timer -= 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.update_display_grade();
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Board, FallingPiece, Game, GameState, GuidelineScore, Input, Piece, Sound, Status, TGM3Event,
+    TGM3Sound, TetrisEvent,
+};
+
+pub const DEFAULT_ULTRA_FRAMES: usize = 2 * 60 * 60;
+
+#[derive(Debug, Clone)]
+pub struct Ultra {
+    pub inner: Game,
+    time_limit: usize,
+    frames: usize,
+    pieces: usize,
+    scoring: GuidelineScore,
+    status: Status,
+    envets: Vec<TGM3Event>,
+    sounds: Vec<TGM3Sound>,
+}
+
+impl Ultra {
+    #[cfg(feature = "std")]
+    pub fn new(time_limit: usize) -> Self {
+        Self::with_seed(time_limit, rand::random())
+    }
+
+    pub fn with_seed(time_limit: usize, seed: u64) -> Self {
+        Self {
+            inner: Game::with_seed(seed),
+            time_limit,
+            frames: 0,
+            pieces: 0,
+            scoring: Default::default(),
+            status: Status::Game,
+            envets: vec![],
+            sounds: vec![],
+        }
+    }
+
+    pub fn get_frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn get_frames_remaining(&self) -> usize {
+        self.time_limit.saturating_sub(self.frames)
+    }
+
+    pub fn get_pieces(&self) -> usize {
+        self.pieces
+    }
+
+    /// Pieces per second over the elapsed frames.
+    pub fn get_pps(&self) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.pieces as f64 * 60.0 / self.frames as f64
+    }
+
+    pub fn get_scoring(&self) -> &GuidelineScore {
+        &self.scoring
+    }
+
+    pub fn get_score(&self) -> usize {
+        self.scoring.get_score()
+    }
+
+    pub fn get_lines(&self) -> usize {
+        self.scoring.get_lines()
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status
+    }
+
+    pub fn get_tgm3events(&mut self) -> &mut Vec<TGM3Event> {
+        self.envets.as_mut()
+    }
+
+    pub fn get_tgm3sounds(&mut self) -> &mut Vec<TGM3Sound> {
+        self.sounds.as_mut()
+    }
+
+    fn game_update(&mut self) {
+        self.inner.update();
+        self.frames += 1;
+        let events = self.inner.get_event_queue().clone();
+        self.pieces += events
+            .iter()
+            .filter(|e| matches!(e, TetrisEvent::PieceLocked(_)))
+            .count();
+        self.scoring.update(&events, &self.inner.get_board());
+        if self.frames >= self.time_limit {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+            self.sounds.push(TGM3Sound::GameClear);
+            return;
+        }
+        if self.inner.is_game_over() {
+            self.status = Status::End;
+            self.envets.push(TGM3Event::StatusChange(Status::End));
+        }
+    }
+}
+
+impl GameState for Ultra {
+    fn update(&mut self) {
+        if let Status::Game = self.status {
+            self.game_update();
+        }
+    }
+
+    fn get_board(&self) -> Board {
+        self.inner.get_board()
+    }
+
+    fn get_current_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_current_piece()
+    }
+
+    fn get_locked_piece(&self) -> Option<FallingPiece> {
+        self.inner.get_locked_piece()
+    }
+
+    fn get_hold(&self) -> Option<Piece> {
+        self.inner.get_hold()
+    }
+
+    fn get_next(&self) -> Piece {
+        self.inner.get_next()
+    }
+
+    fn get_next_next(&self) -> Piece {
+        self.inner.get_next_next()
+    }
+
+    fn get_next_next_next(&self) -> Piece {
+        self.inner.get_next_next_next()
+    }
+
+    fn get_sound_queue(&mut self) -> &mut Vec<Sound> {
+        self.inner.get_sound_queue()
+    }
+
+    fn get_event_queue(&mut self) -> &mut Vec<TetrisEvent> {
+        self.inner.get_event_queue()
+    }
+
+    fn set_input(&mut self, input: Input) {
+        self.inner.set_input(input)
+    }
+
+    fn get_status(&self) -> Status {
+        self.status
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::{vec, vec::Vec};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{AttackTable, Board, Game, GameState, GuidelineScore, Input, Randomizer};
+
+pub const PLAYERS: usize = 2;
+/// Frames a finished round stays on screen before `Versus::step` starts the next one.
+pub const NEXT_ROUND_DELAY: u32 = 180;
+
+#[derive(Debug, Clone)]
+pub struct VersusPlayer {
+    pub inner: Game,
+    scoring: GuidelineScore,
+    sent: usize,
+}
+
+impl VersusPlayer {
+    fn new(seed: u64) -> Self {
+        Self {
+            inner: Game::from_board(Board::with_seed(Randomizer::default(), seed)),
+            scoring: Default::default(),
+            sent: 0,
+        }
+    }
+
+    pub fn get_scoring(&self) -> &GuidelineScore {
+        &self.scoring
+    }
+
+    /// Garbage lines sent this round, after cancelling.
+    pub fn get_sent(&self) -> usize {
+        self.sent
+    }
+}
+
+/// Garbage lines `from` sent the other player, after cancelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Garbage {
+    pub from: usize,
+    pub rows: usize,
+    pub hole: usize,
+}
+
+/// Two games sending garbage to each other, played as a best-of-N match.
+#[derive(Debug, Clone)]
+pub struct Versus {
+    players: [VersusPlayer; PLAYERS],
+    attack_table: AttackTable,
+    rng: StdRng,
+    best_of: usize,
+    wins: [usize; PLAYERS],
+    round_over: bool,
+    round_winner: Option<usize>,
+    round_over_frames: u32,
+    deliver_garbage: bool,
+    sent_garbage: Vec<Garbage>,
+}
+
+impl Versus {
+    #[cfg(feature = "std")]
+    pub fn new(attack_table: AttackTable, best_of: usize) -> Self {
+        Self::with_seed(attack_table, best_of, rand::random())
+    }
+
+    /// Fully deterministic given the same seed and inputs, both players get the same pieces.
+    pub fn with_seed(attack_table: AttackTable, best_of: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let piece_seed = rng.gen();
+        Self {
+            players: [VersusPlayer::new(piece_seed), VersusPlayer::new(piece_seed)],
+            attack_table,
+            rng,
+            best_of: best_of.max(1),
+            wins: [0; PLAYERS],
+            round_over: false,
+            round_winner: None,
+            round_over_frames: 0,
+            deliver_garbage: true,
+            sent_garbage: vec![],
+        }
+    }
+
+    pub fn update(&mut self) {
+        if self.round_over {
+            return;
+        }
+        for i in 0..PLAYERS {
+            let player = &mut self.players[i];
+            player.inner.update();
+            let events = player.inner.get_event_queue().clone();
+            let board = player.inner.get_board();
+            let Some(result) = player.scoring.update(&events, &board) else {
+                continue;
+            };
+            let attack = self.attack_table.attack(&result);
+            let sent = player.inner.cancel_garbage(attack);
+            player.sent += sent;
+            if sent > 0 {
+                let garbage = Garbage {
+                    from: i,
+                    rows: sent,
+                    hole: self.rng.gen_range(0..10),
+                };
+                if self.deliver_garbage {
+                    self.receive_garbage(garbage);
+                } else {
+                    self.sent_garbage.push(garbage);
+                }
+            }
+        }
+
+        let winner = match self.players.each_ref().map(|p| p.inner.is_game_over()) {
+            [false, false] => return,
+            [true, true] => None,
+            [true, false] => Some(1),
+            [false, true] => Some(0),
+        };
+        self.round_over = true;
+        self.round_winner = winner;
+        if let Some(winner) = winner {
+            self.wins[winner] += 1;
+        }
+    }
+
+    /// One frame of a networked match. Clears the event queues so the result only
+    /// depends on the inputs, and starts the next round after `NEXT_ROUND_DELAY`.
+    pub fn step(&mut self, inputs: [Input; PLAYERS]) {
+        for (player, input) in inputs.into_iter().enumerate() {
+            self.set_input(player, input);
+        }
+        self.update();
+        for player in self.players.iter_mut() {
+            player.inner.get_event_queue().clear();
+        }
+        if self.round_over {
+            self.round_over_frames += 1;
+            if self.round_over_frames >= NEXT_ROUND_DELAY {
+                self.next_round();
+            }
+        }
+    }
+
+    /// Starts the next round, or a new match if the current one is decided.
+    pub fn next_round(&mut self) {
+        if self.get_match_winner().is_some() {
+            self.wins = [0; PLAYERS];
+        }
+        let piece_seed = self.rng.gen();
+        self.players = [VersusPlayer::new(piece_seed), VersusPlayer::new(piece_seed)];
+        self.round_over = false;
+        self.round_winner = None;
+        self.round_over_frames = 0;
+    }
+
+    pub fn set_input(&mut self, player: usize, input: Input) {
+        self.players[player].inner.set_input(input);
+    }
+
+    pub fn get_player(&self, player: usize) -> &VersusPlayer {
+        &self.players[player]
+    }
+
+    pub fn get_player_mut(&mut self, player: usize) -> &mut VersusPlayer {
+        &mut self.players[player]
+    }
+
+    /// When false, garbage is kept for `take_sent_garbage` instead of going
+    /// straight to the other player, so the caller can deliver it later with
+    /// `receive_garbage`.
+    pub fn set_deliver_garbage(&mut self, deliver_garbage: bool) {
+        self.deliver_garbage = deliver_garbage;
+    }
+
+    /// Garbage sent since the last call while delivery is off.
+    pub fn take_sent_garbage(&mut self) -> Vec<Garbage> {
+        core::mem::take(&mut self.sent_garbage)
+    }
+
+    /// Queues `garbage` on the player it was sent to.
+    pub fn receive_garbage(&mut self, garbage: Garbage) {
+        self.players[1 - garbage.from]
+            .inner
+            .receive_garbage(garbage.rows, garbage.hole);
+    }
+
+    pub fn get_incoming_garbage(&self, player: usize) -> usize {
+        self.players[player].inner.get_incoming_garbage()
+    }
+
+    pub fn is_round_over(&self) -> bool {
+        self.round_over
+    }
+
+    /// `None` after a draw or while the round is still going.
+    pub fn get_round_winner(&self) -> Option<usize> {
+        self.round_winner
+    }
+
+    pub fn get_wins(&self) -> [usize; PLAYERS] {
+        self.wins
+    }
+
+    pub fn get_best_of(&self) -> usize {
+        self.best_of
+    }
+
+    pub fn get_match_winner(&self) -> Option<usize> {
+        let needed = self.best_of / 2 + 1;
+        self.wins.iter().position(|&w| w >= needed)
+    }
+}
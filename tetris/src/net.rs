@@ -0,0 +1,379 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use thiserror::Error;
+
+use crate::{Game, GameState, Garbage, Input, Versus, PLAYERS};
+
+/// Frames between board checksum exchanges.
+pub const CHECKSUM_INTERVAL: u32 = 60;
+
+#[derive(Error, Debug)]
+pub enum NetError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("connection closed")]
+    Disconnected,
+    #[error("unexpected message")]
+    Protocol,
+    #[error("desync at frame {0}")]
+    Desync(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    Hello {
+        seed: u64,
+        input_delay: u32,
+    },
+    Input {
+        frame: u32,
+        input: Input,
+    },
+    Checksum {
+        frame: u32,
+        checksum: u64,
+    },
+    /// Garbage the sender's player sent, to be received before `frame` is simulated.
+    Garbage {
+        frame: u32,
+        rows: u32,
+        hole: u8,
+    },
+}
+
+impl Message {
+    const HELLO_LEN: usize = 13;
+    const INPUT_LEN: usize = 6;
+    const CHECKSUM_LEN: usize = 13;
+    const GARBAGE_LEN: usize = 10;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        match *self {
+            Message::Hello { seed, input_delay } => {
+                buf.push(0);
+                buf.extend(seed.to_le_bytes());
+                buf.extend(input_delay.to_le_bytes());
+            }
+            Message::Input { frame, input } => {
+                buf.push(1);
+                buf.extend(frame.to_le_bytes());
+                buf.push(input.to_bits());
+            }
+            Message::Checksum { frame, checksum } => {
+                buf.push(2);
+                buf.extend(frame.to_le_bytes());
+                buf.extend(checksum.to_le_bytes());
+            }
+            Message::Garbage { frame, rows, hole } => {
+                buf.push(3);
+                buf.extend(frame.to_le_bytes());
+                buf.extend(rows.to_le_bytes());
+                buf.push(hole);
+            }
+        }
+        buf
+    }
+
+    /// Returns the message and its length, or `None` if `buf` holds only part of one.
+    pub fn decode(buf: &[u8]) -> Result<Option<(Self, usize)>, NetError> {
+        let Some(&tag) = buf.first() else {
+            return Ok(None);
+        };
+        let len = match tag {
+            0 => Self::HELLO_LEN,
+            1 => Self::INPUT_LEN,
+            2 => Self::CHECKSUM_LEN,
+            3 => Self::GARBAGE_LEN,
+            _ => return Err(NetError::Protocol),
+        };
+        if buf.len() < len {
+            return Ok(None);
+        }
+        let u32_at = |i: usize| u32::from_le_bytes(buf[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_le_bytes(buf[i..i + 8].try_into().unwrap());
+        let message = match tag {
+            0 => Message::Hello {
+                seed: u64_at(1),
+                input_delay: u32_at(9),
+            },
+            1 => Message::Input {
+                frame: u32_at(1),
+                input: Input::from_bits(buf[5]),
+            },
+            2 => Message::Checksum {
+                frame: u32_at(1),
+                checksum: u64_at(5),
+            },
+            _ => Message::Garbage {
+                frame: u32_at(1),
+                rows: u32_at(5),
+                hole: buf[9],
+            },
+        };
+        Ok(Some((message, len)))
+    }
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// FNV-1a over everything that affects future frames of `game`.
+pub fn checksum(game: &Game) -> u64 {
+    let mut hash = 0xcbf29ce484222325;
+    let board = game.get_board();
+    for cells_x in board.cells.iter() {
+        for cell in cells_x.iter() {
+            hash = fnv1a(hash, &[cell.map_or(0, |c| c as u8 + 1)]);
+        }
+    }
+    for piece in board.next_pieces.iter() {
+        hash = fnv1a(hash, &[(*piece).into()]);
+    }
+    hash = fnv1a(hash, &[board.hold_piece.map_or(0, |p| u8::from(p) + 1)]);
+    if let Some(piece) = game.get_current_piece() {
+        hash = fnv1a(hash, &[piece.piece_state.get_kind().into()]);
+        hash = fnv1a(hash, &[piece.piece_state.get_rotation() as u8]);
+        hash = fnv1a(hash, &(piece.piece_position.0 as u32).to_le_bytes());
+        hash = fnv1a(hash, &(piece.piece_position.1 as u32).to_le_bytes());
+    }
+    hash = fnv1a(hash, &(game.get_incoming_garbage() as u32).to_le_bytes());
+    fnv1a(hash, &[game.is_game_over() as u8])
+}
+
+pub fn versus_checksum(versus: &Versus) -> u64 {
+    (0..PLAYERS).fold(0, |hash, player| {
+        let hash = fnv1a(
+            hash,
+            &checksum(&versus.get_player(player).inner).to_le_bytes(),
+        );
+        fnv1a(hash, &(versus.get_wins()[player] as u32).to_le_bytes())
+    })
+}
+
+/// Deterministic lockstep over TCP. Both peers simulate the whole `Versus`
+/// from the shared seed and inputs, but garbage goes over the wire as events:
+/// each peer sends what its own player attacks with, scheduled for its next
+/// unsent input frame. Every input the peer sends after that comes later on
+/// the stream, so the garbage is always in before its frame can be simulated.
+pub struct Lockstep {
+    stream: TcpStream,
+    recv_buffer: Vec<u8>,
+    local_player: usize,
+    input_delay: u32,
+    frame: u32,
+    next_local_frame: u32,
+    disconnected: bool,
+    inputs: [BTreeMap<u32, Input>; PLAYERS],
+    garbage: BTreeMap<u32, Vec<Garbage>>,
+    local_checksums: BTreeMap<u32, u64>,
+    remote_checksums: BTreeMap<u32, u64>,
+}
+
+impl Lockstep {
+    /// Waits for a peer on `addr` and plays as player 1. Returns the seed for `Versus::with_seed`.
+    pub fn host<A: ToSocketAddrs>(addr: A, input_delay: u32) -> Result<(Self, u64), NetError> {
+        Self::host_on(TcpListener::bind(addr)?, input_delay)
+    }
+
+    /// `host` on a listener that is already bound, say to port 0.
+    pub fn host_on(listener: TcpListener, input_delay: u32) -> Result<(Self, u64), NetError> {
+        let (stream, _) = listener.accept()?;
+        let seed = rand::random();
+        stream.set_nodelay(true)?;
+        let mut me = Self::new(stream, 0, input_delay)?;
+        me.send(Message::Hello { seed, input_delay })?;
+        Ok((me, seed))
+    }
+
+    /// Connects to a host and plays as player 2, using the host's seed and input delay.
+    pub fn join<A: ToSocketAddrs>(addr: A) -> Result<(Self, u64), NetError> {
+        let mut stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        let mut buf = [0; Message::HELLO_LEN];
+        stream.read_exact(&mut buf)?;
+        match Message::decode(&buf)? {
+            Some((Message::Hello { seed, input_delay }, _)) => {
+                Ok((Self::new(stream, 1, input_delay)?, seed))
+            }
+            _ => Err(NetError::Protocol),
+        }
+    }
+
+    fn new(stream: TcpStream, local_player: usize, input_delay: u32) -> Result<Self, NetError> {
+        stream.set_nonblocking(true)?;
+        let mut inputs: [BTreeMap<u32, Input>; PLAYERS] = Default::default();
+        for frame_inputs in inputs.iter_mut() {
+            for frame in 0..input_delay {
+                frame_inputs.insert(frame, Input::default());
+            }
+        }
+        Ok(Self {
+            stream,
+            recv_buffer: vec![],
+            local_player,
+            input_delay,
+            frame: 0,
+            next_local_frame: input_delay,
+            disconnected: false,
+            inputs,
+            garbage: BTreeMap::new(),
+            local_checksums: BTreeMap::new(),
+            remote_checksums: BTreeMap::new(),
+        })
+    }
+
+    pub fn get_local_player(&self) -> usize {
+        self.local_player
+    }
+
+    pub fn get_frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn get_input_delay(&self) -> u32 {
+        self.input_delay
+    }
+
+    /// True while the local input buffer has room, i.e. less than `input_delay` frames ahead.
+    pub fn wants_local_input(&self) -> bool {
+        self.next_local_frame <= self.frame + self.input_delay
+    }
+
+    /// Schedules `input` `input_delay` frames ahead and sends it to the peer.
+    pub fn add_local_input(&mut self, input: Input) -> Result<(), NetError> {
+        let frame = self.next_local_frame;
+        self.inputs[self.local_player].insert(frame, input);
+        self.next_local_frame += 1;
+        self.send(Message::Input { frame, input })
+    }
+
+    /// Reads everything the peer has sent so far without blocking.
+    pub fn poll(&mut self) -> Result<(), NetError> {
+        let mut buf = [0; 1024];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    self.disconnected = true;
+                    break;
+                }
+                Ok(n) => self.recv_buffer.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        while let Some((message, len)) = Message::decode(&self.recv_buffer)? {
+            self.recv_buffer.drain(..len);
+            match message {
+                Message::Input { frame, input } => {
+                    self.inputs[1 - self.local_player].insert(frame, input);
+                }
+                Message::Checksum { frame, checksum } => {
+                    self.remote_checksums.insert(frame, checksum);
+                }
+                Message::Garbage { frame, rows, hole } => {
+                    if frame < self.frame || hole >= 10 {
+                        return Err(NetError::Protocol);
+                    }
+                    self.garbage.entry(frame).or_default().push(Garbage {
+                        from: 1 - self.local_player,
+                        rows: rows as usize,
+                        hole: hole as usize,
+                    });
+                }
+                Message::Hello { .. } => return Err(NetError::Protocol),
+            }
+        }
+        self.check_desync()
+    }
+
+    /// Simulates one frame if both inputs for it have arrived. Returns false while stalled.
+    pub fn advance(&mut self, versus: &mut Versus) -> Result<bool, NetError> {
+        let (Some(&first), Some(&second)) = (
+            self.inputs[0].get(&self.frame),
+            self.inputs[1].get(&self.frame),
+        ) else {
+            if self.disconnected {
+                return Err(NetError::Disconnected);
+            }
+            return Ok(false);
+        };
+        versus.set_deliver_garbage(false);
+        for garbage in self.garbage.remove(&self.frame).unwrap_or_default() {
+            versus.receive_garbage(garbage);
+        }
+        versus.step([first, second]);
+        // the peer sends its own player's garbage
+        for garbage in versus.take_sent_garbage() {
+            if garbage.from != self.local_player {
+                continue;
+            }
+            let frame = self.next_local_frame;
+            self.garbage.entry(frame).or_default().push(garbage);
+            self.send(Message::Garbage {
+                frame,
+                rows: garbage.rows as u32,
+                hole: garbage.hole as u8,
+            })?;
+        }
+
+        for frame_inputs in self.inputs.iter_mut() {
+            frame_inputs.remove(&self.frame);
+        }
+        self.frame += 1;
+        if self.frame.is_multiple_of(CHECKSUM_INTERVAL) {
+            let checksum = versus_checksum(versus);
+            self.local_checksums.insert(self.frame, checksum);
+            self.send(Message::Checksum {
+                frame: self.frame,
+                checksum,
+            })?;
+            self.check_desync()?;
+        }
+        Ok(true)
+    }
+
+    fn check_desync(&mut self) -> Result<(), NetError> {
+        let frames = self
+            .local_checksums
+            .keys()
+            .filter(|frame| self.remote_checksums.contains_key(frame))
+            .copied()
+            .collect::<Vec<_>>();
+        for frame in frames {
+            let local = self.local_checksums.remove(&frame);
+            let remote = self.remote_checksums.remove(&frame);
+            if local != remote {
+                return Err(NetError::Desync(frame));
+            }
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, message: Message) -> Result<(), NetError> {
+        let buf = message.encode();
+        let mut written = 0;
+        while written < buf.len() {
+            match self.stream.write(&buf[written..]) {
+                Ok(0) => return Err(NetError::Disconnected),
+                Ok(n) => written += n,
+                Err(e)
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::Interrupted =>
+                {
+                    std::thread::yield_now()
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
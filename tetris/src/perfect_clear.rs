@@ -0,0 +1,487 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+
+use crate::{find_placements, Board, Piece, PieceState, Placement, Rotation};
+
+/// The solver only looks at boards with no more filled rows than this, and
+/// only for perfect clears at most this high.
+pub const PC_MAX_HEIGHT: usize = 4;
+
+/// One piece of a perfect clear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcStep {
+    /// Hold before placing, so the piece comes out of hold, or is the one
+    /// after the current piece when hold is empty.
+    pub hold: bool,
+    /// On the board as it is after the previous steps, cleared lines removed.
+    pub placement: Placement,
+}
+
+const PIECES: [Piece; 7] = [
+    Piece::I,
+    Piece::O,
+    Piece::T,
+    Piece::L,
+    Piece::J,
+    Piece::S,
+    Piece::Z,
+];
+
+// cells as (x, row), rows counted from the top of the rows being cleared.
+// A piece can skip rows that are cleared before it comes, so its cells don't
+// have to be its shape on this board.
+type Cells = [(usize, usize); 4];
+
+// cells of a placement on the board as it is when placing
+type BoardCells = Vec<(usize, usize)>;
+
+// a placed piece as whether it came from hold, the piece and its cells
+type Step = (bool, Piece, BoardCells);
+
+// a piece in one rotation as (x, row) offsets from the top of its leftmost
+// column, and the row of its first cell going down that column
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Shape {
+    piece: Piece,
+    cells: [(usize, usize); 4],
+    first_row: usize,
+}
+
+struct Search<'a> {
+    board: Board,
+    queue: &'a [Piece],
+    hold: Option<Piece>,
+    hold_enabled: bool,
+    // the current piece came out of hold already
+    hold_used: bool,
+    height: usize,
+    // let pieces skip rows, see `Cells`, and how many in the tiling do
+    skip_rows: bool,
+    skipping: usize,
+    shapes: Vec<Shape>,
+    // pieces left to tile with
+    counts: [usize; 7],
+    tiling: Vec<(Piece, Cells)>,
+    // whether a tiling got complete since the last node, and the partial
+    // tilings that can't be completed at all
+    tiled: bool,
+    dead_ends: BTreeSet<(Vec<u16>, [usize; 7])>,
+    // the cells of every placement, for boards tilings have in common
+    placements: BTreeMap<(Vec<u16>, Piece), Vec<BoardCells>>,
+}
+
+/// Searches for placements that leave `board` empty, taking pieces from
+/// `queue` in order with `queue[0]` as the current piece and `hold` as the
+/// held piece. Lower perfect clears are tried first, and a solution always
+/// places at least one piece.
+///
+/// Returns `None` when there is no perfect clear with these pieces or the
+/// board has more than `PC_MAX_HEIGHT` filled rows.
+pub fn find_perfect_clear(
+    board: &Board,
+    queue: &[Piece],
+    hold: Option<Piece>,
+    hold_enabled: bool,
+) -> Option<Vec<PcStep>> {
+    solve(board, queue, hold, hold_enabled, false)
+}
+
+/// Like `find_perfect_clear` with hold enabled, for when hold was already
+/// used this turn so `queue[0]` has to be placed as it is.
+pub fn find_perfect_clear_after_hold(
+    board: &Board,
+    queue: &[Piece],
+    hold: Option<Piece>,
+) -> Option<Vec<PcStep>> {
+    solve(board, queue, hold, true, true)
+}
+
+fn solve(
+    board: &Board,
+    queue: &[Piece],
+    hold: Option<Piece>,
+    hold_enabled: bool,
+    hold_used: bool,
+) -> Option<Vec<PcStep>> {
+    let rows = bit_rows(board);
+    let stack = rows.iter().position(|&row| row != 0).map_or(0, |y| 40 - y);
+    if stack > PC_MAX_HEIGHT {
+        return None;
+    }
+    let filled = rows
+        .iter()
+        .map(|row| row.count_ones() as usize)
+        .sum::<usize>();
+    let hold = hold.filter(|_| hold_enabled);
+
+    let mut board = board.clone();
+    board.next_pieces.clear();
+    board.hold_piece = None;
+    for height in stack.max(1)..=PC_MAX_HEIGHT {
+        let empty = 10 * height - filled;
+        let pieces = empty / 4;
+        if !empty.is_multiple_of(4) || pieces > queue.len() + hold.is_some() as usize {
+            continue;
+        }
+        // with hold, one piece can be left over
+        let reach = if hold_enabled && hold.is_none() {
+            pieces + 1
+        } else {
+            pieces
+        };
+        let mut counts = [0; 7];
+        for &piece in queue.iter().take(reach).chain(hold.iter()) {
+            counts[piece as usize] += 1;
+        }
+        // most perfect clears are found without pieces skipping rows, and
+        // that search can rule out far more tilings early
+        for skip_rows in [false, true] {
+            let mut search = Search {
+                board: board.clone(),
+                queue,
+                hold,
+                hold_enabled,
+                hold_used,
+                height,
+                skip_rows,
+                skipping: 0,
+                shapes: shapes(),
+                counts,
+                tiling: vec![],
+                tiled: false,
+                dead_ends: BTreeSet::new(),
+                placements: BTreeMap::new(),
+            };
+            let steps = search.tile(&mut rows[40 - height..].to_vec());
+            if steps.is_some() {
+                return steps;
+            }
+        }
+    }
+    None
+}
+
+impl Search<'_> {
+    /// Covers the empty cells of `area` with pieces, ignoring the order they
+    /// come in, and checks each complete tiling against the queue.
+    fn tile(&mut self, area: &mut [u16]) -> Option<Vec<PcStep>> {
+        let Some((x, y)) = first_empty(area) else {
+            self.tiled = true;
+            // tilings without skips were all tried before
+            if self.skip_rows && self.skipping == 0 {
+                return None;
+            }
+            return self.order();
+        };
+        let key = (area.to_vec(), self.counts);
+        if self.dead_ends.contains(&key) {
+            return None;
+        }
+        let tiled = core::mem::replace(&mut self.tiled, false);
+        for i in 0..self.shapes.len() {
+            let Shape {
+                piece,
+                cells: shape,
+                first_row,
+            } = self.shapes[i];
+            if self.counts[piece as usize] == 0 {
+                continue;
+            }
+            let depth = shape.iter().map(|&(_, row)| row).max().unwrap_or(0);
+            // the rows it takes, as a bit set
+            for taken in 0..1_u16 << area.len() {
+                if taken.count_ones() as usize != depth + 1 {
+                    continue;
+                }
+                let mut rows = [0; 4];
+                for (row, y) in (0..area.len())
+                    .filter(|y| taken & 1 << y != 0)
+                    .zip(&mut rows)
+                {
+                    *y = row;
+                }
+                let skips = rows[depth] - rows[0] != depth;
+                if rows[first_row] != y || skips && !self.skip_rows {
+                    continue;
+                }
+                let mut cells = [(0, 0); 4];
+                let fits = shape
+                    .iter()
+                    .zip(cells.iter_mut())
+                    .all(|(&(dx, row), cell)| {
+                        let (x, y) = (x + dx, rows[row]);
+                        *cell = (x, y);
+                        x < 10 && area[y] & 1 << x == 0
+                    });
+                if !fits {
+                    continue;
+                }
+                for &(x, y) in &cells {
+                    area[y] |= 1 << x;
+                }
+                self.counts[piece as usize] -= 1;
+                self.skipping += skips as usize;
+                self.tiling.push((piece, cells));
+                // areas cut off from each other stay that way without skips
+                if self.skip_rows || fillable(area) {
+                    if let Some(steps) = self.tile(area) {
+                        return Some(steps);
+                    }
+                }
+                self.tiling.pop();
+                self.skipping -= skips as usize;
+                self.counts[piece as usize] += 1;
+                for &(x, y) in &cells {
+                    area[y] &= !(1 << x);
+                }
+            }
+        }
+        if !self.tiled {
+            self.dead_ends.insert(key);
+        }
+        self.tiled |= tiled;
+        None
+    }
+
+    /// Whether the queue can place the tiling, and how.
+    fn order(&mut self) -> Option<Vec<PcStep>> {
+        let rows = (0..self.height).collect::<Vec<_>>();
+        let mut failed = BTreeSet::new();
+        let mut steps = vec![];
+        let board = self.board.clone();
+        if !self.place(&board, &rows, 0, 0, self.hold, &mut failed, &mut steps) {
+            return None;
+        }
+
+        // find the routes, which the search above skips where it can
+        let mut board = self.board.clone();
+        let mut placements = vec![];
+        for (hold, piece, cells) in steps {
+            let placement = find_placements(&board, piece)
+                .into_iter()
+                .find(|placement| placement.get_cells() == cells)?;
+            board.set_piece(&placement.piece).ok()?;
+            board.line_clear();
+            board.line_shrink();
+            placements.push(PcStep { hold, placement });
+        }
+        Some(placements)
+    }
+
+    // `rows` are the tiling's rows not cleared yet, `placed` has a bit per
+    // placed piece of the tiling, and `next` indexes the current piece
+    #[allow(clippy::too_many_arguments)]
+    fn place(
+        &mut self,
+        board: &Board,
+        rows: &[usize],
+        placed: u16,
+        next: usize,
+        hold: Option<Piece>,
+        failed: &mut BTreeSet<(u16, usize, Option<Piece>)>,
+        steps: &mut Vec<Step>,
+    ) -> bool {
+        if placed.count_ones() as usize == self.tiling.len() {
+            return true;
+        }
+        if failed.contains(&(placed, next, hold)) {
+            return false;
+        }
+
+        let queue = self.queue;
+        let mut choices = vec![];
+        if let Some(&current) = queue.get(next) {
+            choices.push((false, current, next + 1, hold));
+        }
+        if self.hold_enabled && !(self.hold_used && placed == 0) {
+            match (hold, queue.get(next)) {
+                (Some(held), Some(&current)) if held != current => {
+                    choices.push((true, held, next + 1, Some(current)))
+                }
+                // the hold piece can still be placed once the queue runs out
+                (Some(held), None) => choices.push((true, held, next, None)),
+                (None, Some(&current)) => {
+                    if let Some(&after) = queue.get(next + 1) {
+                        choices.push((true, after, next + 2, Some(current)));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let top = 40 - rows.len();
+        for (use_hold, piece, next, hold) in choices {
+            for i in 0..self.tiling.len() {
+                let (kind, cells) = self.tiling[i];
+                if kind != piece || placed & 1 << i != 0 {
+                    continue;
+                }
+                // rows only go once they're full, so the rows of a piece
+                // still to come are all there
+                let Some(mut cells) = cells
+                    .iter()
+                    .map(|&(x, y)| Some((x, top + rows.iter().position(|&row| row == y)?)))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+                cells.sort_unstable();
+                // rows it skips have to be cleared by now
+                let mut ys = cells.iter().map(|&(_, y)| y).collect::<Vec<_>>();
+                ys.sort_unstable();
+                ys.dedup();
+                if ys[ys.len() - 1] - ys[0] + 1 != ys.len() || !self.placeable(board, piece, &cells)
+                {
+                    continue;
+                }
+
+                let mut board = board.clone();
+                for &(x, y) in &cells {
+                    board.cells[y][x] = Some(piece.into());
+                }
+                // empty rows above shrink too, so tell the cleared ones apart first
+                let full = (top..40)
+                    .map(|y| board.cells[y].iter().all(Option::is_some))
+                    .collect::<Vec<_>>();
+                board.line_clear();
+                board.line_shrink();
+                let rows = rows
+                    .iter()
+                    .zip(full)
+                    .filter(|&(_, full)| !full)
+                    .map(|(&row, _)| row)
+                    .collect::<Vec<_>>();
+                steps.push((use_hold, piece, cells));
+                if self.place(&board, &rows, placed | 1 << i, next, hold, failed, steps) {
+                    return true;
+                }
+                steps.pop();
+            }
+        }
+        failed.insert((placed, next, hold));
+        false
+    }
+
+    /// Whether `piece` can come to rest on `cells`, sorted as
+    /// `Placement::get_cells` sorts them.
+    fn placeable(&mut self, board: &Board, piece: Piece, cells: &[(usize, usize)]) -> bool {
+        let supported = cells
+            .iter()
+            .any(|&(x, y)| y + 1 == board.cells.len() || board.cells[y + 1][x].is_some());
+        if !supported {
+            return false;
+        }
+        // nothing above, so a hard drop gets there
+        let open = cells
+            .iter()
+            .all(|&(x, y)| board.cells[..y].iter().all(|cells_x| cells_x[x].is_none()));
+        open || self
+            .placements
+            .entry((bit_rows(board), piece))
+            .or_insert_with(|| {
+                find_placements(board, piece)
+                    .iter()
+                    .map(Placement::get_cells)
+                    .collect()
+            })
+            .iter()
+            .any(|placement| placement == cells)
+    }
+}
+
+fn shapes() -> Vec<Shape> {
+    let rotations = [
+        Rotation::North,
+        Rotation::East,
+        Rotation::South,
+        Rotation::West,
+    ];
+    let mut shapes = vec![];
+    for piece in PIECES {
+        for rotation in rotations {
+            let mut cells = PieceState::with_rotation(piece, rotation)
+                .get_cells()
+                .into_iter()
+                .map(|(x, y)| (x, -y))
+                .collect::<Vec<_>>();
+            cells.sort_unstable();
+            let left = cells[0].0;
+            let top = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+            let mut shape = Shape {
+                piece,
+                cells: [(0, 0); 4],
+                first_row: (cells[0].1 - top) as usize,
+            };
+            for (&(x, y), offset) in cells.iter().zip(shape.cells.iter_mut()) {
+                *offset = ((x - left) as usize, (y - top) as usize);
+            }
+            if !shapes.contains(&shape) {
+                shapes.push(shape);
+            }
+        }
+    }
+    shapes
+}
+
+// going down each column from the left
+fn first_empty(area: &[u16]) -> Option<(usize, usize)> {
+    (0..10)
+        .flat_map(|x| (0..area.len()).map(move |y| (x, y)))
+        .find(|&(x, y)| area[y] & 1 << x == 0)
+}
+
+fn bit_rows(board: &Board) -> Vec<u16> {
+    board
+        .cells
+        .iter()
+        .map(|cells_x| {
+            cells_x
+                .iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_some())
+                .fold(0, |row, (x, _)| row | 1 << x)
+        })
+        .collect()
+}
+
+/// Whether every empty area of `rows` could take a whole number of pieces.
+fn fillable(rows: &[u16]) -> bool {
+    let mut rows = rows.to_vec();
+    for y in 0..rows.len() {
+        for x in 0..10 {
+            if rows[y] & 1 << x == 0 && !flood(&mut rows, x, y).is_multiple_of(4) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+// fills the empty area around (x, y) and returns its size
+fn flood(rows: &mut [u16], x: usize, y: usize) -> usize {
+    let mut stack = vec![(x, y)];
+    let mut size = 0;
+    while let Some((x, y)) = stack.pop() {
+        if rows[y] & 1 << x != 0 {
+            continue;
+        }
+        rows[y] |= 1 << x;
+        size += 1;
+        if x > 0 {
+            stack.push((x - 1, y));
+        }
+        if x < 9 {
+            stack.push((x + 1, y));
+        }
+        if y > 0 {
+            stack.push((x, y - 1));
+        }
+        if y + 1 < rows.len() {
+            stack.push((x, y + 1));
+        }
+    }
+    size
+}
@@ -1,5 +1,8 @@
+use alloc::{vec, vec::Vec};
+
 use num_enum::IntoPrimitive;
 use rand::{distributions::Standard, prelude::Distribution, Rng};
+use serde_derive::{Deserialize, Serialize};
 
 use crate::Board;
 
@@ -47,10 +50,11 @@ impl FallingPiece {
         let initial_offsets = self.piece_state.kicks();
         self.piece_state.cw();
         let target_offsets = self.piece_state.kicks();
+        let step = self.piece_state.get_step();
         let kicks = initial_offsets
             .iter()
             .zip(target_offsets.iter())
-            .map(|(&(x1, y1), &(x2, y2))| (x1 - x2, y1 - y2));
+            .map(|(&(x1, y1), &(x2, y2))| ((x1 - x2) * step, (y1 - y2) * step));
 
         for (dx, dy) in kicks {
             if !self.check_shift_collision(board, dx, dy) {
@@ -66,10 +70,11 @@ impl FallingPiece {
         let initial_offsets = self.piece_state.kicks();
         self.piece_state.ccw();
         let target_offsets = self.piece_state.kicks();
+        let step = self.piece_state.get_step();
         let kicks = initial_offsets
             .iter()
             .zip(target_offsets.iter())
-            .map(|(&(x1, y1), &(x2, y2))| (x1 - x2, y1 - y2));
+            .map(|(&(x1, y1), &(x2, y2))| ((x1 - x2) * step, (y1 - y2) * step));
 
         for (dx, dy) in kicks {
             if !self.check_shift_collision(board, dx, dy) {
@@ -82,7 +87,8 @@ impl FallingPiece {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Rotation {
     North,
     East,
@@ -122,7 +128,9 @@ const STANDS: [[(i16, i16); 4]; 7] = [
     [(-1, 1), (0, 1), (0, 0), (1, 0)],  // Z
 ];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, IntoPrimitive)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, IntoPrimitive, Serialize, Deserialize,
+)]
 #[repr(u8)]
 pub enum Piece {
     I,
@@ -138,6 +146,32 @@ impl Piece {
     pub fn get_cells(&self) -> Vec<(i16, i16)> {
         STANDS[*self as usize].to_vec()
     }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Piece::I => 'I',
+            Piece::O => 'O',
+            Piece::T => 'T',
+            Piece::L => 'L',
+            Piece::J => 'J',
+            Piece::S => 'S',
+            Piece::Z => 'Z',
+        }
+    }
+
+    /// Either case.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'I' => Some(Piece::I),
+            'O' => Some(Piece::O),
+            'T' => Some(Piece::T),
+            'L' => Some(Piece::L),
+            'J' => Some(Piece::J),
+            'S' => Some(Piece::S),
+            'Z' => Some(Piece::Z),
+            _ => None,
+        }
+    }
 }
 
 impl Distribution<Piece> for Standard {
@@ -163,6 +197,7 @@ impl Distribution<Piece> for Standard {
 pub struct PieceState {
     kind: Piece,
     rotation: Rotation,
+    big: bool,
 }
 
 impl Distribution<PieceState> for Standard {
@@ -173,6 +208,7 @@ impl Distribution<PieceState> for Standard {
         PieceState {
             kind: rng.gen(),
             rotation: Rotation::North,
+            big: false,
         }
     }
 }
@@ -182,6 +218,34 @@ impl PieceState {
         Self {
             kind: piece,
             rotation: Rotation::North,
+            big: false,
+        }
+    }
+    pub fn with_rotation(piece: Piece, rotation: Rotation) -> Self {
+        Self {
+            kind: piece,
+            rotation,
+            big: false,
+        }
+    }
+    /// The same piece with every mino doubled to 2x2, as in TGM's big mode.
+    pub fn to_big(self) -> Self {
+        Self { big: true, ..self }
+    }
+    /// The same piece, big or not.
+    pub fn with_big(self, big: bool) -> Self {
+        Self { big, ..self }
+    }
+    pub fn is_big(&self) -> bool {
+        self.big
+    }
+    /// Columns a shift moves the piece, which keeps big pieces on their 2x2
+    /// grid.
+    pub fn get_step(&self) -> i32 {
+        if self.big {
+            2
+        } else {
+            1
         }
     }
     pub fn get_cells(&self) -> Vec<(i16, i16)> {
@@ -202,13 +266,25 @@ impl PieceState {
                 )
             })
             .collect::<Vec<_>>();
+        if !self.big {
+            return rotated;
+        }
         rotated
+            .into_iter()
+            .flat_map(|(x, y)| {
+                [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(dx, dy)| (2 * x + dx, 2 * y + dy))
+            })
+            .collect()
     }
 
     pub fn get_kind(&self) -> Piece {
         self.kind.clone()
     }
 
+    pub fn get_rotation(&self) -> Rotation {
+        self.rotation
+    }
+
     pub fn get_initial_position(&self) -> (usize, usize) {
         match self.kind {
             Piece::I => (4, 20),
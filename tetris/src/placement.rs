@@ -0,0 +1,216 @@
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque},
+    vec,
+    vec::Vec,
+};
+
+use crate::{Board, FallingPiece, Piece, PieceState, TSpin};
+
+/// One step of a route to a placement. The final hard drop isn't included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Left,
+    Right,
+    Cw,
+    Ccw,
+    /// Soft drop one row, for tucks and spins partway down.
+    Down,
+    /// Soft drop all the way down without locking.
+    SonicDrop,
+}
+
+impl Move {
+    pub const ALL: [Move; 6] = [
+        Move::Left,
+        Move::Right,
+        Move::Cw,
+        Move::Ccw,
+        Move::Down,
+        Move::SonicDrop,
+    ];
+
+    /// Plays the move on `piece`, false if it couldn't move.
+    pub fn apply(self, board: &Board, piece: &mut FallingPiece) -> bool {
+        let step = piece.piece_state.get_step();
+        match self {
+            Move::Left => piece.shift(board, -step, 0),
+            Move::Right => piece.shift(board, step, 0),
+            Move::Cw => piece.cw(board),
+            Move::Ccw => piece.ccw(board),
+            Move::Down => piece.shift(board, 0, 1),
+            Move::SonicDrop => sonic_drop(board, piece),
+        }
+    }
+}
+
+/// Somewhere a piece can come to rest, and the shortest route there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    pub piece: FallingPiece,
+    pub moves: Vec<Move>,
+    /// The route ends with a rotation, which is what makes a T-spin count.
+    pub last_rotation: bool,
+    pub t_spin: Option<TSpin>,
+}
+
+impl Placement {
+    /// Board coordinates the piece occupies once locked, sorted.
+    pub fn get_cells(&self) -> Vec<(usize, usize)> {
+        piece_cells(&self.piece)
+    }
+
+    /// Whether `piece` fills the same cells as this placement.
+    pub fn is_at(&self, piece: &FallingPiece) -> bool {
+        piece_cells(piece) == self.get_cells()
+    }
+}
+
+// the last move being a rotation changes the outcome, so it is part of the state
+type SearchState = (FallingPiece, bool);
+type Parents = BTreeMap<SearchState, Option<(SearchState, Move)>>;
+
+/// Every distinct resting placement for `piece` spawned on `board`.
+pub fn find_placements(board: &Board, piece: Piece) -> Vec<Placement> {
+    let start = FallingPiece::from_piece_state(PieceState::from_piece(piece));
+    find_placements_from(board, start)
+}
+
+/// Breadth-first search over `shift`, `cw` and `ccw` from `start`, so tucks,
+/// spins and kicks are all found. Placements that fill the same cells are
+/// only reported once, unless one of them is a T-spin and the other isn't.
+pub fn find_placements_from(board: &Board, start: FallingPiece) -> Vec<Placement> {
+    search(board, start, false)
+}
+
+/// Like `find_placements_from`, but the piece drops to the floor after every
+/// move as it does at 20G.
+pub fn find_placements_at_20g(board: &Board, start: FallingPiece) -> Vec<Placement> {
+    search(board, start, true)
+}
+
+/// Shortest moves from `from` to a placement filling the same cells as
+/// `target`, ending in a rotation if `target` is a T-spin.
+pub fn find_route(
+    board: &Board,
+    from: FallingPiece,
+    target: &Placement,
+    twenty_g: bool,
+) -> Option<Vec<Move>> {
+    let cells = target.get_cells();
+    search(board, from, twenty_g)
+        .into_iter()
+        .find(|p| p.t_spin == target.t_spin && p.get_cells() == cells)
+        .map(|p| p.moves)
+}
+
+fn search(board: &Board, start: FallingPiece, twenty_g: bool) -> Vec<Placement> {
+    let (x, y) = start.piece_position;
+    if board.check_collision(start.piece_state, x, y) {
+        return vec![];
+    }
+    let mut start = FallingPiece {
+        previous_lock_y: 0,
+        ..start
+    };
+    if twenty_g {
+        sonic_drop(board, &mut start);
+    }
+    let open_rows = board
+        .cells
+        .iter()
+        .take_while(|cells_x| cells_x.iter().all(Option::is_none))
+        .count();
+    let start = (start, false);
+    let mut parents = Parents::new();
+    parents.insert(start, None);
+    let mut queue = VecDeque::from([start]);
+    let mut seen = BTreeSet::new();
+    let mut placements = vec![];
+
+    while let Some(state) = queue.pop_front() {
+        let (piece, last_rotation) = state;
+        if piece.check_shift_collision(board, 0, 1) {
+            let t_spin = if last_rotation {
+                board.get_t_spin(&piece)
+            } else {
+                None
+            };
+            if seen.insert((piece_cells(&piece), t_spin)) {
+                placements.push(Placement {
+                    piece,
+                    moves: route(&parents, state),
+                    last_rotation,
+                    t_spin,
+                });
+            }
+        }
+
+        for m in Move::ALL {
+            let mut next = piece;
+            if !m.apply(board, &mut next) {
+                continue;
+            }
+            if m == Move::Down {
+                // every height above the stack plays the same, so skip down
+                // to where it starts to matter
+                let mut lower = next;
+                while lower.shift(board, 0, 1) && in_open_air(&lower, open_rows) {
+                    next = lower;
+                }
+            }
+            let mut last_rotation = matches!(m, Move::Cw | Move::Ccw);
+            if twenty_g && sonic_drop(board, &mut next) {
+                last_rotation = false;
+            }
+            let next = (next, last_rotation);
+            if let Entry::Vacant(entry) = parents.entry(next) {
+                entry.insert(Some((state, m)));
+                queue.push_back(next);
+            }
+        }
+    }
+    placements
+}
+
+fn sonic_drop(board: &Board, piece: &mut FallingPiece) -> bool {
+    let mut moved = false;
+    while piece.shift(board, 0, 1) {
+        moved = true;
+    }
+    moved
+}
+
+// no move from here can reach the stack
+fn in_open_air(piece: &FallingPiece, open_rows: usize) -> bool {
+    // rotating moves the lowest mino at most 2 rows and kicking at most 2 more
+    let margin = 4 * piece.piece_state.get_step() as usize;
+    let lowest = piece_cells(piece).into_iter().map(|(_, y)| y).max();
+    lowest.is_some_and(|y| y + margin < open_rows)
+}
+
+fn route(parents: &Parents, mut state: SearchState) -> Vec<Move> {
+    let mut moves = vec![];
+    while let Some(&Some((parent, m))) = parents.get(&state) {
+        // a skip through open air is still one row at a time
+        let rows = match m {
+            Move::Down => state.0.piece_position.1 - parent.0.piece_position.1,
+            _ => 1,
+        };
+        moves.extend(core::iter::repeat_n(m, rows));
+        state = parent;
+    }
+    moves.reverse();
+    moves
+}
+
+fn piece_cells(piece: &FallingPiece) -> Vec<(usize, usize)> {
+    let (x, y) = piece.piece_position;
+    let mut cells = piece
+        .piece_state
+        .get_cells()
+        .into_iter()
+        .map(|(rel_x, rel_y)| ((rel_x + x as i16) as usize, (-rel_y + y as i16) as usize))
+        .collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells
+}
@@ -0,0 +1,90 @@
+use alloc::{vec, vec::Vec};
+
+use rand::{prelude::SliceRandom, Rng};
+
+use crate::Piece;
+
+const PIECES: [Piece; 7] = [
+    Piece::I,
+    Piece::O,
+    Piece::T,
+    Piece::L,
+    Piece::J,
+    Piece::S,
+    Piece::Z,
+];
+
+#[derive(Debug, Clone)]
+pub enum Randomizer {
+    Bag(Vec<Piece>),
+    History {
+        history: [Piece; 4],
+        rolls: usize,
+        first: bool,
+    },
+}
+
+impl Default for Randomizer {
+    fn default() -> Self {
+        Self::bag()
+    }
+}
+
+impl Randomizer {
+    pub fn bag() -> Self {
+        Self::Bag(vec![])
+    }
+
+    pub fn tgm1() -> Self {
+        use Piece::*;
+        Self::History {
+            history: [Z, Z, Z, Z],
+            rolls: 4,
+            first: true,
+        }
+    }
+
+    pub fn tgm2() -> Self {
+        use Piece::*;
+        Self::History {
+            history: [Z, S, S, Z],
+            rolls: 6,
+            first: true,
+        }
+    }
+
+    pub fn next<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Piece {
+        match self {
+            Self::Bag(bag) => {
+                if bag.is_empty() {
+                    *bag = PIECES.to_vec();
+                    bag.shuffle(rng);
+                }
+                bag.remove(0)
+            }
+            Self::History {
+                history,
+                rolls,
+                first,
+            } => {
+                let piece = if *first {
+                    *first = false;
+                    use Piece::*;
+                    *[I, T, L, J].choose(rng).unwrap()
+                } else {
+                    let mut piece = *PIECES.choose(rng).unwrap();
+                    for _ in 1..*rolls {
+                        if !history.contains(&piece) {
+                            break;
+                        }
+                        piece = *PIECES.choose(rng).unwrap();
+                    }
+                    piece
+                };
+                history.rotate_left(1);
+                history[3] = piece;
+                piece
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use crate::{versus_checksum, Input, Versus, PLAYERS};
+
+/// How many frames the predicted state may run ahead of the last confirmed one.
+pub const DEFAULT_MAX_PREDICTION: u32 = 8;
+
+/// GGPO-style rollback for `Versus`. The local game runs ahead on a guess of
+/// the remote input (its last known one), and when a real input disagrees
+/// with the guess the last confirmed snapshot is restored and re-simulated.
+///
+/// The session doesn't own a transport: send what `add_local_input` returns
+/// to the peer and feed what arrives from it to `add_remote_input`.
+pub struct Rollback {
+    local_player: usize,
+    input_delay: u32,
+    max_prediction: u32,
+    frame: u32,
+    next_local_frame: u32,
+    confirmed_frame: u32,
+    confirmed: Versus,
+    versus: Versus,
+    inputs: [BTreeMap<u32, Input>; PLAYERS],
+    predictions: BTreeMap<u32, Input>,
+    last_remote_input: Input,
+    rollback_pending: bool,
+    rollbacks: u32,
+}
+
+impl Rollback {
+    pub fn new(versus: Versus, local_player: usize, input_delay: u32) -> Self {
+        let mut inputs: [BTreeMap<u32, Input>; PLAYERS] = Default::default();
+        for frame_inputs in inputs.iter_mut() {
+            for frame in 0..input_delay {
+                frame_inputs.insert(frame, Input::default());
+            }
+        }
+        Self {
+            local_player,
+            input_delay,
+            max_prediction: DEFAULT_MAX_PREDICTION,
+            frame: 0,
+            next_local_frame: input_delay,
+            confirmed_frame: 0,
+            confirmed: versus.clone(),
+            versus,
+            inputs,
+            predictions: BTreeMap::new(),
+            last_remote_input: Input::default(),
+            rollback_pending: false,
+            rollbacks: 0,
+        }
+    }
+
+    pub fn set_max_prediction(&mut self, max_prediction: u32) {
+        self.max_prediction = max_prediction;
+    }
+
+    pub fn get_local_player(&self) -> usize {
+        self.local_player
+    }
+
+    pub fn get_frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn get_confirmed_frame(&self) -> u32 {
+        self.confirmed_frame
+    }
+
+    pub fn get_rollbacks(&self) -> u32 {
+        self.rollbacks
+    }
+
+    /// The predicted state, i.e. what should be drawn.
+    pub fn get_versus(&self) -> &Versus {
+        &self.versus
+    }
+
+    /// The state at `get_confirmed_frame`, which both peers agree on.
+    pub fn get_confirmed_versus(&self) -> &Versus {
+        &self.confirmed
+    }
+
+    pub fn get_confirmed_checksum(&self) -> u64 {
+        versus_checksum(&self.confirmed)
+    }
+
+    /// True while the local input buffer has room, i.e. less than `input_delay` frames ahead.
+    pub fn wants_local_input(&self) -> bool {
+        self.next_local_frame <= self.frame + self.input_delay
+    }
+
+    /// Schedules `input` `input_delay` frames ahead. Returns the frame it
+    /// belongs to, which has to be sent to the peer along with it.
+    pub fn add_local_input(&mut self, input: Input) -> u32 {
+        let frame = self.next_local_frame;
+        self.inputs[self.local_player].insert(frame, input);
+        self.next_local_frame += 1;
+        frame
+    }
+
+    /// Records the peer's input for `frame`. Inputs may arrive late or out of
+    /// order; a late one that differs from its prediction schedules a rollback.
+    pub fn add_remote_input(&mut self, frame: u32, input: Input) {
+        let remote = 1 - self.local_player;
+        if frame < self.confirmed_frame || self.inputs[remote].contains_key(&frame) {
+            return;
+        }
+        self.inputs[remote].insert(frame, input);
+        if frame < self.frame && self.predictions.get(&frame) != Some(&input) {
+            self.rollback_pending = true;
+        }
+    }
+
+    /// Confirms what it can, rolls back if needed and simulates one predicted
+    /// frame. Returns false while stalled on local input or the prediction limit.
+    pub fn advance(&mut self) -> bool {
+        self.confirm();
+        if self.rollback_pending {
+            self.rollback_pending = false;
+            self.rollbacks += 1;
+            self.versus = self.confirmed.clone();
+            for frame in self.confirmed_frame..self.frame {
+                let inputs = self.frame_inputs(frame);
+                self.versus.step(inputs);
+            }
+        }
+
+        if self.frame >= self.confirmed_frame + self.max_prediction
+            || !self.inputs[self.local_player].contains_key(&self.frame)
+        {
+            return false;
+        }
+        let inputs = self.frame_inputs(self.frame);
+        self.versus.step(inputs);
+        self.frame += 1;
+        true
+    }
+
+    /// Steps the confirmed snapshot over every simulated frame whose inputs are all in.
+    fn confirm(&mut self) {
+        let remote = 1 - self.local_player;
+        while self.confirmed_frame < self.frame {
+            let frame = self.confirmed_frame;
+            let (Some(&local), Some(&remote_input)) = (
+                self.inputs[self.local_player].get(&frame),
+                self.inputs[remote].get(&frame),
+            ) else {
+                break;
+            };
+            let mut inputs = [local; PLAYERS];
+            inputs[remote] = remote_input;
+            self.confirmed.step(inputs);
+
+            for frame_inputs in self.inputs.iter_mut() {
+                frame_inputs.remove(&frame);
+            }
+            self.predictions.remove(&frame);
+            self.last_remote_input = remote_input;
+            self.confirmed_frame += 1;
+        }
+    }
+
+    /// Inputs for `frame`, guessing the remote one from the latest input known before it.
+    fn frame_inputs(&mut self, frame: u32) -> [Input; PLAYERS] {
+        let remote = 1 - self.local_player;
+        let remote_input = match self.inputs[remote].get(&frame) {
+            Some(&input) => {
+                self.predictions.remove(&frame);
+                input
+            }
+            None => {
+                let input = self.inputs[remote]
+                    .range(..frame)
+                    .next_back()
+                    .map_or(self.last_remote_input, |(_, &input)| input);
+                self.predictions.insert(frame, input);
+                input
+            }
+        };
+        let mut inputs = [self.inputs[self.local_player][&frame]; PLAYERS];
+        inputs[remote] = remote_input;
+        inputs
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{Board, TSpin, TetrisEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClearType {
+    Single,
+    Double,
+    Triple,
+    Tetris,
+    TSpinMini,
+    TSpinMiniSingle,
+    TSpinMiniDouble,
+    TSpin,
+    TSpinSingle,
+    TSpinDouble,
+    TSpinTriple,
+    PerfectClear,
+}
+
+impl ClearType {
+    pub const ALL: [ClearType; 12] = [
+        ClearType::Single,
+        ClearType::Double,
+        ClearType::Triple,
+        ClearType::Tetris,
+        ClearType::TSpinMini,
+        ClearType::TSpinMiniSingle,
+        ClearType::TSpinMiniDouble,
+        ClearType::TSpin,
+        ClearType::TSpinSingle,
+        ClearType::TSpinDouble,
+        ClearType::TSpinTriple,
+        ClearType::PerfectClear,
+    ];
+
+    fn from_lock(t_spin: Option<TSpin>, lines: usize) -> Option<Self> {
+        match (t_spin, lines) {
+            (None, 0) => None,
+            (None, 1) => Some(ClearType::Single),
+            (None, 2) => Some(ClearType::Double),
+            (None, 3) => Some(ClearType::Triple),
+            (None, _) => Some(ClearType::Tetris),
+            (Some(TSpin::Mini), 0) => Some(ClearType::TSpinMini),
+            (Some(TSpin::Mini), 1) => Some(ClearType::TSpinMiniSingle),
+            (Some(TSpin::Mini), _) => Some(ClearType::TSpinMiniDouble),
+            (Some(TSpin::Full), 0) => Some(ClearType::TSpin),
+            (Some(TSpin::Full), 1) => Some(ClearType::TSpinSingle),
+            (Some(TSpin::Full), 2) => Some(ClearType::TSpinDouble),
+            (Some(TSpin::Full), _) => Some(ClearType::TSpinTriple),
+        }
+    }
+
+    fn base_score(&self) -> usize {
+        match self {
+            ClearType::Single => 100,
+            ClearType::Double => 300,
+            ClearType::Triple => 500,
+            ClearType::Tetris => 800,
+            ClearType::TSpinMini => 100,
+            ClearType::TSpinMiniSingle => 200,
+            ClearType::TSpinMiniDouble => 400,
+            ClearType::TSpin => 400,
+            ClearType::TSpinSingle => 800,
+            ClearType::TSpinDouble => 1200,
+            ClearType::TSpinTriple => 1600,
+            ClearType::PerfectClear => 0,
+        }
+    }
+
+    fn is_difficult(&self) -> bool {
+        matches!(
+            self,
+            ClearType::Tetris
+                | ClearType::TSpinMiniSingle
+                | ClearType::TSpinMiniDouble
+                | ClearType::TSpinSingle
+                | ClearType::TSpinDouble
+                | ClearType::TSpinTriple
+        )
+    }
+}
+
+/// What a single piece lock did, as seen by `GuidelineScore`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockResult {
+    pub clear_type: Option<ClearType>,
+    pub lines: usize,
+    pub combo: Option<usize>,
+    /// The clear continued a back-to-back chain.
+    pub back_to_back: bool,
+    pub perfect_clear: bool,
+}
+
+/// Guideline scoring driven by the `TetrisEvent`s of a `Game`.
+#[derive(Debug, Clone)]
+pub struct GuidelineScore {
+    score: usize,
+    level: usize,
+    lines: usize,
+    combo: Option<usize>,
+    back_to_back: bool,
+    clears: [usize; ClearType::ALL.len()],
+}
+
+impl Default for GuidelineScore {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            level: 1,
+            lines: 0,
+            combo: None,
+            back_to_back: false,
+            clears: [0; ClearType::ALL.len()],
+        }
+    }
+}
+
+impl GuidelineScore {
+    pub fn get_score(&self) -> usize {
+        self.score
+    }
+
+    pub fn get_level(&self) -> usize {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: usize) {
+        self.level = level;
+    }
+
+    pub fn get_lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn get_combo(&self) -> Option<usize> {
+        self.combo
+    }
+
+    pub fn is_back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+
+    pub fn get_clear_count(&self, clear_type: ClearType) -> usize {
+        self.clears[clear_type as usize]
+    }
+
+    /// Feeds one frame worth of events. `board` is the board after that frame.
+    /// Returns the lock result if a piece locked during the frame.
+    pub fn update(&mut self, events: &[TetrisEvent], board: &Board) -> Option<LockResult> {
+        let mut t_spin = None;
+        let mut locked = false;
+        let mut lines = 0;
+        for e in events.iter() {
+            match e {
+                TetrisEvent::SoftDropped(n) => self.score += n,
+                TetrisEvent::HardDropped(n) => self.score += 2 * n,
+                TetrisEvent::TSpin(t) => t_spin = Some(*t),
+                TetrisEvent::PieceLocked(_) => locked = true,
+                TetrisEvent::LineCleared(n) => lines = *n,
+                _ => {}
+            }
+        }
+        if !locked {
+            return None;
+        }
+
+        let clear_type = ClearType::from_lock(t_spin, lines);
+        let mut back_to_back = false;
+        if let Some(clear_type) = clear_type {
+            self.clears[clear_type as usize] += 1;
+            let mut score = clear_type.base_score() * self.level;
+            if lines > 0 {
+                if clear_type.is_difficult() {
+                    if self.back_to_back {
+                        score = score * 3 / 2;
+                        back_to_back = true;
+                    }
+                    self.back_to_back = true;
+                } else {
+                    self.back_to_back = false;
+                }
+            }
+            self.score += score;
+        }
+
+        if lines == 0 {
+            self.combo = None;
+            return Some(LockResult {
+                clear_type,
+                lines,
+                combo: None,
+                back_to_back,
+                perfect_clear: false,
+            });
+        }
+        let combo = self.combo.map_or(0, |c| c + 1);
+        self.combo = Some(combo);
+        self.score += 50 * combo * self.level;
+        self.lines += lines;
+
+        let perfect_clear = board
+            .cells
+            .iter()
+            .all(|cells_x| cells_x.iter().all(|c| c.is_none()));
+        if perfect_clear {
+            self.clears[ClearType::PerfectClear as usize] += 1;
+            self.score += [800, 1200, 1800, 2000][lines.min(4) - 1] * self.level;
+        }
+        Some(LockResult {
+            clear_type,
+            lines,
+            combo: Some(combo),
+            back_to_back,
+            perfect_clear,
+        })
+    }
+}
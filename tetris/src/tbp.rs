@@ -0,0 +1,557 @@
+//! Frontend side of the Tetris Bot Protocol: external bots run as a child
+//! process and exchange one JSON message per line over stdin/stdout.
+
+use std::{
+    ffi::OsStr,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use serde_derive::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    bot::{occupancy, tap, Route},
+    find_route, Board, Cell, FallingPiece, GameState, Input, Piece, PieceState, Placement,
+    Rotation, TSpin,
+};
+
+#[derive(Error, Debug)]
+pub enum TbpError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("bot exited")]
+    Exited,
+    #[error("bot refused to play: {0}")]
+    Refused(String),
+    #[error("unexpected message")]
+    Protocol,
+}
+
+/// `G` for garbage, otherwise the piece letter.
+pub type TbpCell = Option<char>;
+
+/// Messages sent to the bot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TbpFrontendMessage {
+    Rules,
+    Start(TbpStart),
+    Stop,
+    Suggest,
+    Play {
+        #[serde(rename = "move")]
+        mv: TbpMove,
+    },
+    NewPiece {
+        piece: Piece,
+    },
+    Quit,
+}
+
+/// Messages sent by the bot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TbpBotMessage {
+    Info {
+        name: String,
+        version: String,
+        author: String,
+        features: Vec<String>,
+    },
+    Ready,
+    Error {
+        reason: String,
+    },
+    Suggestion {
+        moves: Vec<TbpMove>,
+    },
+    /// Anything newer than this frontend understands, ignored.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TbpStart {
+    pub hold: Option<Piece>,
+    /// The current piece first.
+    pub queue: Vec<Piece>,
+    pub combo: u32,
+    pub back_to_back: bool,
+    /// 40 rows of 10, bottom row first.
+    pub board: Vec<Vec<TbpCell>>,
+}
+
+/// Where a piece ends up. `x` and `y` are the rotation center counted from
+/// the bottom left, which is `piece_position` with y flipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TbpLocation {
+    #[serde(rename = "type")]
+    pub kind: Piece,
+    pub orientation: Rotation,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TbpSpin {
+    None,
+    Mini,
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TbpMove {
+    pub location: TbpLocation,
+    pub spin: TbpSpin,
+}
+
+impl TbpLocation {
+    pub fn from_piece(piece: &FallingPiece) -> Self {
+        let (x, y) = piece.piece_position;
+        Self {
+            kind: piece.piece_state.get_kind(),
+            orientation: piece.piece_state.get_rotation(),
+            x: x as i32,
+            y: 39 - y as i32,
+        }
+    }
+
+    /// `None` if the center is off the board. `big` reads the location as
+    /// a big piece, which TBP itself has no notion of.
+    pub fn to_piece(&self, big: bool) -> Option<FallingPiece> {
+        if !(0..10).contains(&self.x) || !(0..40).contains(&self.y) {
+            return None;
+        }
+        Some(FallingPiece {
+            piece_state: PieceState::with_rotation(self.kind, self.orientation).with_big(big),
+            piece_position: (self.x as usize, (39 - self.y) as usize),
+            previous_lock_y: 0,
+        })
+    }
+}
+
+impl TbpMove {
+    pub fn from_placement(placement: &Placement) -> Self {
+        Self {
+            location: TbpLocation::from_piece(&placement.piece),
+            spin: match placement.t_spin {
+                None => TbpSpin::None,
+                Some(TSpin::Mini) => TbpSpin::Mini,
+                Some(TSpin::Full) => TbpSpin::Full,
+            },
+        }
+    }
+
+    /// The placement this move asks for, `None` if the piece would overlap
+    /// something or float on `board`.
+    pub fn to_placement(&self, board: &Board, big: bool) -> Option<Placement> {
+        let piece = self.location.to_piece(big)?;
+        let (x, y) = piece.piece_position;
+        if board.check_collision(piece.piece_state, x, y)
+            || !piece.check_shift_collision(board, 0, 1)
+        {
+            return None;
+        }
+        // only T-spins are scored here, other pieces just land
+        let t_spin = match (self.location.kind, self.spin) {
+            (Piece::T, TbpSpin::Mini) => Some(TSpin::Mini),
+            (Piece::T, TbpSpin::Full) => Some(TSpin::Full),
+            _ => None,
+        };
+        Some(Placement {
+            piece,
+            moves: vec![],
+            last_rotation: t_spin.is_some(),
+            t_spin,
+        })
+    }
+}
+
+/// The board's 40 rows, bottom row first.
+pub fn board_to_tbp(board: &Board) -> Vec<Vec<TbpCell>> {
+    board
+        .cells
+        .iter()
+        .rev()
+        .map(|row| row.iter().map(|cell| cell.map(cell_to_tbp)).collect())
+        .collect()
+}
+
+/// Fills the bottom of `board` with `rows`, bottom row first. Anything past
+/// the board's size is dropped.
+pub fn board_from_tbp(board: &mut Board, rows: &[Vec<TbpCell>]) {
+    for (row, tbp_row) in board.cells.iter_mut().rev().zip(rows) {
+        for (cell, tbp_cell) in row.iter_mut().zip(tbp_row) {
+            *cell = tbp_cell.map(cell_from_tbp);
+        }
+    }
+}
+
+fn cell_to_tbp(cell: Cell) -> char {
+    match cell {
+        Cell::Cyan => 'I',
+        Cell::Yellow => 'O',
+        Cell::Purple => 'T',
+        Cell::Orange => 'L',
+        Cell::Blue => 'J',
+        Cell::Green => 'S',
+        Cell::Red => 'Z',
+        _ => 'G',
+    }
+}
+
+fn cell_from_tbp(c: char) -> Cell {
+    match c {
+        'I' => Piece::I.into(),
+        'O' => Piece::O.into(),
+        'T' => Piece::T.into(),
+        'L' => Piece::L.into(),
+        'J' => Piece::J.into(),
+        'S' => Piece::S.into(),
+        'Z' => Piece::Z.into(),
+        _ => Cell::Glay,
+    }
+}
+
+/// A running bot process. Its output is read on a separate thread so polling
+/// for a suggestion never blocks the game loop.
+pub struct TbpConnection {
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<Result<TbpBotMessage, TbpError>>,
+}
+
+impl TbpConnection {
+    pub fn spawn<I, S>(program: impl AsRef<OsStr>, args: I) -> Result<Self, TbpError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or(TbpError::Exited)?;
+        let stdout = child.stdout.take().ok_or(TbpError::Exited)?;
+        let (tx, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let message = line
+                    .map_err(TbpError::from)
+                    .and_then(|line| Ok(serde_json::from_str(&line)?));
+                let failed = message.is_err();
+                if tx.send(message).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            messages,
+        })
+    }
+
+    pub fn send(&mut self, message: &TbpFrontendMessage) -> Result<(), TbpError> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    /// The next message if one arrived already.
+    pub fn try_recv(&mut self) -> Result<Option<TbpBotMessage>, TbpError> {
+        match self.messages.try_recv() {
+            Ok(message) => message.map(Some),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(TbpError::Exited),
+        }
+    }
+
+    pub fn recv(&mut self) -> Result<TbpBotMessage, TbpError> {
+        self.messages.recv().map_err(|_| TbpError::Exited)?
+    }
+}
+
+impl Drop for TbpConnection {
+    fn drop(&mut self) {
+        let _ = self.send(&TbpFrontendMessage::Quit);
+        // don't hang on a bot that ignores quit
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// What the bot was told, to notice when the game went somewhere else.
+#[derive(Debug, Clone)]
+struct Mirror {
+    occupancy: Vec<u16>,
+    hold: Option<Piece>,
+    queue: Vec<Piece>,
+}
+
+#[derive(Debug, Clone)]
+struct Plan {
+    hold: bool,
+    route: Route,
+    occupancy: Vec<u16>,
+}
+
+/// Plays any `GameState` with moves suggested by an external TBP bot, fed one
+/// `Input` per frame like `Bot`. The bot is told about each piece as it shows
+/// up in the previews, and restarted from the actual position whenever
+/// garbage, a misdrop or a refused hold make its idea of the game drift.
+pub struct TbpBot {
+    connection: TbpConnection,
+    name: String,
+    author: String,
+    started: bool,
+    mirror: Option<Mirror>,
+    /// The position a suggestion was asked for.
+    waiting: Option<Vec<u16>>,
+    plan: Option<Plan>,
+    previous: Input,
+    hold_disabled: bool,
+    blocking: bool,
+}
+
+impl TbpBot {
+    /// Starts `program` and waits for it to accept the rules.
+    pub fn spawn<I, S>(program: impl AsRef<OsStr>, args: I) -> Result<Self, TbpError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        Self::new(TbpConnection::spawn(program, args)?)
+    }
+
+    pub fn new(mut connection: TbpConnection) -> Result<Self, TbpError> {
+        let TbpBotMessage::Info { name, author, .. } = connection.recv()? else {
+            return Err(TbpError::Protocol);
+        };
+        connection.send(&TbpFrontendMessage::Rules)?;
+        match connection.recv()? {
+            TbpBotMessage::Ready => {}
+            TbpBotMessage::Error { reason } => return Err(TbpError::Refused(reason)),
+            _ => return Err(TbpError::Protocol),
+        }
+        Ok(Self {
+            connection,
+            name,
+            author,
+            started: false,
+            mirror: None,
+            waiting: None,
+            plan: None,
+            previous: Input::default(),
+            hold_disabled: false,
+            blocking: false,
+        })
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_author(&self) -> &str {
+        &self.author
+    }
+
+    /// The placement it is heading for, if any.
+    pub fn get_target(&self) -> Option<&Placement> {
+        self.plan.as_ref().map(|plan| plan.route.get_target())
+    }
+
+    /// Wait for each suggestion instead of letting the piece fall meanwhile,
+    /// for headless runs that go faster than real time.
+    pub fn set_blocking(&mut self, blocking: bool) {
+        self.blocking = blocking;
+    }
+
+    pub fn next_input<G: GameState>(&mut self, game: &G) -> Result<Input, TbpError> {
+        let input = self.choose_input(game)?;
+        self.previous = input;
+        Ok(input)
+    }
+
+    fn choose_input<G: GameState>(&mut self, game: &G) -> Result<Input, TbpError> {
+        let Some(current) = game.get_current_piece() else {
+            self.plan = None;
+            return Ok(Input::default());
+        };
+        let board = game.get_board();
+        let occupancy = occupancy(&board);
+        if self.plan.as_ref().is_some_and(|p| p.occupancy != occupancy) {
+            self.plan = None;
+        }
+        if self.plan.is_none() {
+            if self.waiting.is_none() {
+                self.sync(game, current, &occupancy)?;
+                self.connection.send(&TbpFrontendMessage::Suggest)?;
+                self.waiting = Some(occupancy.clone());
+            }
+            while let Some(message) = if self.blocking {
+                Some(self.connection.recv()?)
+            } else {
+                self.connection.try_recv()?
+            } {
+                let TbpBotMessage::Suggestion { moves } = message else {
+                    continue;
+                };
+                if self.waiting.take().is_some_and(|asked| asked == occupancy) {
+                    self.plan = self.pick(game, &board, current, occupancy.clone(), &moves)?;
+                } else {
+                    // the piece locked while the bot was thinking
+                    self.mirror = None;
+                }
+                break;
+            }
+        }
+        if self.waiting.is_some() {
+            return Ok(Input::default());
+        }
+        let Some(plan) = self.plan.as_mut() else {
+            return Ok(tap(self.previous, None));
+        };
+
+        let target_kind = plan.route.get_target().piece.piece_state.get_kind();
+        if plan.hold && current.piece_state.get_kind() != target_kind {
+            if self.previous.hold {
+                // the mode has no hold, or it was used up already
+                self.hold_disabled = true;
+                self.plan = None;
+                self.mirror = None;
+                return Ok(Input::default());
+            }
+            return Ok(Input {
+                hold: true,
+                ..Default::default()
+            });
+        }
+        match plan.route.steer(&board, current, self.previous) {
+            Some(input) => Ok(input),
+            None => {
+                // the bot expects the planned spot, it'll get the real one on the next start
+                self.mirror = None;
+                Ok(tap(self.previous, None))
+            }
+        }
+    }
+
+    /// Brings the bot up to date with the visible queue, or restarts it if
+    /// the position isn't the one it expects.
+    fn sync<G: GameState>(
+        &mut self,
+        game: &G,
+        current: FallingPiece,
+        occupancy: &[u16],
+    ) -> Result<(), TbpError> {
+        let queue = [
+            current.piece_state.get_kind(),
+            game.get_next(),
+            game.get_next_next(),
+            game.get_next_next_next(),
+        ];
+        let hold = game.get_hold();
+        if let Some(mirror) = self.mirror.as_mut() {
+            let known = mirror.queue.len();
+            if mirror.occupancy == occupancy
+                && mirror.hold == hold
+                && known <= queue.len()
+                && mirror.queue == queue[..known]
+            {
+                for &piece in &queue[known..] {
+                    self.connection
+                        .send(&TbpFrontendMessage::NewPiece { piece })?;
+                }
+                mirror.queue = queue.to_vec();
+                return Ok(());
+            }
+        }
+        if self.started {
+            self.connection.send(&TbpFrontendMessage::Stop)?;
+        }
+        self.started = true;
+        let board = game.get_board();
+        self.connection.send(&TbpFrontendMessage::Start(TbpStart {
+            hold,
+            queue: queue.to_vec(),
+            // modes don't expose these, bots only use them for scoring
+            combo: 0,
+            back_to_back: false,
+            board: board_to_tbp(&board),
+        }))?;
+        self.mirror = Some(Mirror {
+            occupancy: occupancy.to_vec(),
+            hold,
+            queue: queue.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// The first suggested move the piece can actually get to. Tells the bot
+    /// which one was played.
+    fn pick<G: GameState>(
+        &mut self,
+        game: &G,
+        board: &Board,
+        current: FallingPiece,
+        before: Vec<u16>,
+        moves: &[TbpMove],
+    ) -> Result<Option<Plan>, TbpError> {
+        let kind = current.piece_state.get_kind();
+        let big = current.piece_state.is_big();
+        let twenty_g = current.check_shift_collision(board, 0, 1);
+        for mv in moves {
+            let Some(target) = mv.to_placement(board, big) else {
+                continue;
+            };
+            let hold = mv.location.kind != kind;
+            let start = if hold {
+                let held = game.get_hold().unwrap_or(game.get_next());
+                if self.hold_disabled || held != mv.location.kind {
+                    continue;
+                }
+                FallingPiece::from_piece_state(PieceState::from_piece(held).with_big(big))
+            } else {
+                current
+            };
+            if !target.is_at(&start) && find_route(board, start, &target, twenty_g).is_none() {
+                continue;
+            }
+            self.connection
+                .send(&TbpFrontendMessage::Play { mv: *mv })?;
+            if let Some(mirror) = self.mirror.as_mut() {
+                if hold {
+                    mirror.hold = Some(mirror.queue.remove(0));
+                    if game.get_hold().is_none() {
+                        mirror.queue.remove(0);
+                    }
+                } else {
+                    mirror.queue.remove(0);
+                }
+                let mut after = board.clone();
+                let _ = after.set_piece(&target.piece);
+                after.line_clear();
+                after.line_shrink();
+                mirror.occupancy = occupancy(&after);
+            }
+            return Ok(Some(Plan {
+                hold,
+                route: Route::new(target),
+                occupancy: before,
+            }));
+        }
+        // nothing usable, drop it where it is and start over next piece
+        self.mirror = None;
+        Ok(None)
+    }
+}
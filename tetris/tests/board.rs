@@ -0,0 +1,71 @@
+//! The `Display` form of a board and parsing it back.
+
+use tetris::*;
+
+#[test]
+fn round_trips_display() {
+    use Piece::*;
+
+    let mut board = Board::default();
+    board.hold_piece = Some(T);
+    board.set_queue(&[I, O, S, Z, L, J, T]);
+    board.cells[5][3] = Some(Cell::Glay);
+    board.cells[38][..4].fill(Some(Cell::Blue));
+    board.cells[39] = Board::garbage_row(6);
+    board.cells[39][0] = Some(Cell::Black);
+    board.cells[39][1] = Some(Cell::White);
+
+    let text = board.to_string();
+    // the rows above the visible field start at the highest filled one
+    assert_eq!(text.lines().count(), 2 + 40 - 5);
+    let parsed = text.parse::<Board>().unwrap();
+    assert_eq!(parsed.to_string(), text);
+    assert_eq!(parsed.hold_piece, Some(T));
+    assert_eq!(parsed.next_pieces, board.next_pieces);
+}
+
+#[test]
+fn round_trips_empty_board() {
+    let board = "next: IOSZLJT".parse::<Board>().unwrap();
+    let text = board.to_string();
+    assert_eq!(text.lines().count(), 1 + 20);
+    assert_eq!(text.parse::<Board>().unwrap().to_string(), text);
+}
+
+#[test]
+fn parses_aliases() {
+    let board = "
+        hold: t
+        next: iozsljt
+
+        #.........
+        #szljito.#"
+        .parse::<Board>()
+        .unwrap();
+    let expected = "
+        hold: T
+        next: IOZSLJT
+        G.........
+        GSZLJITO.G"
+        .parse::<Board>()
+        .unwrap();
+    assert_eq!(board.to_string(), expected.to_string());
+    assert!(board.to_string().ends_with("G.........\nGSZLJITO.G\n"));
+}
+
+#[test]
+fn rejects_bad_boards() {
+    let parse = |text: &str| text.parse::<Board>().unwrap_err();
+    assert!(matches!(parse("GGGGXGGGGG"), BoardParseError::Cell('X')));
+    assert!(matches!(parse("next: IOQ"), BoardParseError::Piece('Q')));
+    assert!(matches!(parse("hold: IO"), BoardParseError::Hold));
+    assert!(matches!(
+        parse("..........\nGGGGGGGGG\n.........."),
+        BoardParseError::Width(1)
+    ));
+    assert!(matches!(parse("GGGGGGGGGGG"), BoardParseError::Width(0)));
+    assert!(matches!(
+        parse(&"..........\n".repeat(41)),
+        BoardParseError::Height
+    ));
+}
@@ -0,0 +1,163 @@
+//! The reinforcement learning environment: seeding, rewards and when
+//! episodes end.
+
+use tetris::env::{Action, Env, Rewards, VecEnv};
+use tetris::*;
+
+fn press(previous: &mut Input, hard_drop: bool) -> Action {
+    *previous = Input {
+        hard_drop: hard_drop && !previous.hard_drop,
+        ..Default::default()
+    };
+    Action::Input(*previous)
+}
+
+#[test]
+fn resets_to_seeded_game() {
+    let mut env = Env::new();
+    let first = env.reset(5);
+    assert_eq!(Env::new().reset(5), first);
+    assert_ne!(env.reset(6), first);
+
+    // playing on changes nothing about the next episode
+    env.step(Action::Place(0));
+    assert_eq!(env.reset(5), first);
+    assert!(!env.is_done());
+    assert!(first.current.is_some());
+    assert!(first.board.iter().flatten().all(|filled| !filled));
+}
+
+#[test]
+fn rewards_line_clears() {
+    let mut env = Env::with_factory(|_| {
+        let board = "
+            next: I
+            GGGGGGGGG.
+            GGGGGGGGG.
+            GGGGGGGGG.
+            GGGGGGGGG."
+            .parse()
+            .unwrap();
+        Game::from_board(board)
+    });
+    env.set_rewards(Rewards {
+        holes: -0.5,
+        ..Default::default()
+    });
+    env.reset(0);
+    let well = [(9, 36), (9, 37), (9, 38), (9, 39)];
+    let index = env
+        .legal_placements()
+        .iter()
+        .position(|c| !c.hold && c.placement.get_cells() == well)
+        .unwrap();
+    let (observation, reward, done, info) = env.step(Action::Place(index));
+    assert_eq!(reward, 8.0 + 0.01);
+    assert!(!done);
+    assert_eq!(info.lines_cleared, 4);
+    assert_eq!(info.pieces_locked, 1);
+    assert_eq!(info.total_lines, 4);
+    assert!(observation.board.iter().flatten().all(|filled| !filled));
+    assert!(observation.current.is_some());
+
+    // an I lying across the top of the well leaves four holes under it
+    env.reset(0);
+    let lid = [(6, 35), (7, 35), (8, 35), (9, 35)];
+    let index = env
+        .legal_placements()
+        .iter()
+        .position(|c| !c.hold && c.placement.get_cells() == lid)
+        .unwrap();
+    let (_, reward, _, info) = env.step(Action::Place(index));
+    assert_eq!(info.lines_cleared, 0);
+    assert_eq!(reward, 0.01 - 0.5 * 4.0);
+}
+
+#[test]
+fn ends_on_top_out() {
+    let mut env = Env::new();
+    env.reset(3);
+    let mut previous = Input::default();
+    let mut steps = 0;
+    let (reward, info) = loop {
+        let (_, reward, done, info) = env.step(press(&mut previous, true));
+        steps += 1;
+        if done {
+            break (reward, info);
+        }
+        assert!(steps < 60 * 60, "never topped out");
+    };
+    assert!(reward <= -1.0 + 0.01);
+    assert!(!info.truncated);
+    assert!(matches!(env.get_game().get_status(), Status::End));
+
+    // a finished episode stays put
+    let before = env.step(Action::Input(Input::default()));
+    let after = env.step(Action::Input(Input::default()));
+    assert_eq!(before.0, after.0);
+    assert_eq!(after.1, 0.0);
+    assert!(after.2);
+}
+
+#[test]
+fn ends_with_the_mode() {
+    // an ultra run ends on its clock, long before anyone tops out
+    let mut env = Env::with_factory(|seed| Ultra::with_seed(120, seed));
+    env.reset(1);
+    let mut previous = Input::default();
+    let mut steps = 0;
+    loop {
+        let (_, _, done, info) = env.step(press(&mut previous, false));
+        steps += 1;
+        if done {
+            assert!(!info.truncated);
+            break;
+        }
+        assert!(steps < 600, "the clock ran out but the episode went on");
+    }
+    assert_eq!(steps, 120);
+    assert!(!env.get_game().inner.is_game_over());
+
+    // placements stop at the end too instead of waiting for a piece
+    env.reset(1);
+    let mut placed = 0;
+    while !env.step(Action::Place(0)).2 {
+        placed += 1;
+        assert!(placed < 120);
+    }
+    assert!(placed < 120);
+    assert!(matches!(env.get_game().get_status(), Status::End));
+}
+
+#[test]
+fn truncates_at_max_frames() {
+    let mut env = Env::new();
+    env.set_max_frames(Some(30));
+    env.reset(0);
+    let mut previous = Input::default();
+    for frame in 1..=30 {
+        let (_, _, done, info) = env.step(press(&mut previous, false));
+        assert_eq!(done, frame == 30);
+        assert_eq!(info.truncated, frame == 30);
+    }
+}
+
+#[test]
+fn vec_env_is_deterministic() {
+    let run = || {
+        let mut envs = VecEnv::new((0..8).map(|_| Env::new()).collect());
+        let mut results = vec![envs.reset(&[1, 2, 3, 4, 5, 6, 7, 8])];
+        for step in 0..40 {
+            let actions = envs
+                .legal_placements()
+                .iter()
+                .enumerate()
+                .map(|(i, legal)| Action::Place((step * 7 + i) % legal.len()))
+                .collect::<Vec<_>>();
+            let stepped = envs.step(&actions, |i| 100 + i as u64);
+            results.push(stepped.into_iter().map(|(obs, ..)| obs).collect());
+        }
+        results
+    };
+    assert_eq!(run(), run());
+}
@@ -0,0 +1,292 @@
+//! Fumen strings as the fumen editor writes them, and boards written out and
+//! read back.
+
+use tetris::*;
+
+fn board(rows: &str) -> Board {
+    rows.parse().unwrap()
+}
+
+/// Every row as `Display` writes it, to compare fields.
+fn rows(board: &Board) -> Vec<String> {
+    board
+        .cells
+        .iter()
+        .map(|cells| cells.iter().map(|c| c.map_or('.', Cell::to_char)).collect())
+        .collect()
+}
+
+/// The page's board with its piece drawn on.
+fn drawn(page: &FumenPage) -> Board {
+    let mut board = page.board.clone();
+    board.set_piece(&page.piece.unwrap()).unwrap();
+    board
+}
+
+fn piece_at(piece: Piece, rotation: Rotation, x: usize, y: usize) -> FallingPiece {
+    let mut piece = FallingPiece::from_piece_state(PieceState::with_rotation(piece, rotation));
+    piece.piece_position = (x, y);
+    piece
+}
+
+#[test]
+fn decodes_empty_field() {
+    let page = board_from_fumen("v115@vhAAgH").unwrap();
+    assert_eq!(rows(&page.board), rows(&Board::default()));
+    assert!(page.piece.is_none());
+    assert_eq!(page.comment, "");
+}
+
+#[test]
+fn decodes_garbage_rows() {
+    let page = board_from_fumen("v115@9gF8DeF8DeF8DeF8NeAgH").unwrap();
+    let expected = board(
+        "
+        GGGGGG....
+        GGGGGG....
+        GGGGGG....
+        GGGGGG....",
+    );
+    assert_eq!(rows(&page.board), rows(&expected));
+
+    // the garbage row under the field doesn't make it onto the board
+    let page = board_from_fumen("v115@BhywCezhAewwR4BeH8AeC8AeL8AeD8AgH").unwrap();
+    let expected = board(
+        "
+        ....TTT...
+        IIII.TSS..
+        GGGGGGGG.G
+        GG.GGGGGGG",
+    );
+    assert_eq!(rows(&page.board), rows(&expected));
+}
+
+#[test]
+fn decodes_locked_piece_onto_next_page() {
+    let pages = pages_from_fumen("v115@vhCVQJRmfAAA").unwrap();
+    assert_eq!(pages.len(), 3);
+
+    let t = board(
+        "
+        ....T.....
+        ...TTT....",
+    );
+    assert_eq!(rows(&drawn(&pages[0])), rows(&t));
+    assert_eq!(pages[0].board.next_pieces[0], Piece::T);
+    assert_eq!(rows(&pages[1].board), rows(&t));
+
+    // the I on the second page doesn't lock
+    let piece = pages[1].piece.unwrap();
+    assert_eq!(piece.piece_state.get_kind(), Piece::I);
+    assert_eq!(piece.piece_state.get_rotation(), Rotation::North);
+    let i = board(
+        "
+        ...IIII...
+        ....T.....
+        ...TTT....",
+    );
+    assert_eq!(rows(&drawn(&pages[1])), rows(&i));
+    assert_eq!(rows(&pages[2].board), rows(&t));
+    assert!(pages[2].piece.is_none());
+}
+
+#[test]
+fn decodes_line_clears_and_rising_garbage() {
+    let pages = pages_from_fumen("v115@RhI8AeH8Le+NJvhAAAA").unwrap();
+    let piece = pages[0].piece.unwrap();
+    assert_eq!(piece.piece_state.get_rotation(), Rotation::West);
+    let expected = board(
+        "
+        .........J
+        GGGGGGGGGJ
+        GGGGGGGGJJ",
+    );
+    assert_eq!(rows(&drawn(&pages[0])), rows(&expected));
+    assert_eq!(rows(&pages[1].board), rows(&board(".........J")));
+
+    let pages = pages_from_fumen("v115@ehywDeI8AeAYJvhAAAA").unwrap();
+    assert_eq!(rows(&pages[0].board), rows(&board("...TTT....")));
+    let expected = board(
+        "
+        ...TTT....
+        GGGGGGGGG.",
+    );
+    assert_eq!(rows(&pages[1].board), rows(&expected));
+}
+
+#[test]
+fn decodes_comments_across_pages() {
+    let pages = pages_from_fumen(
+        "v115@vhCAgWFAIoMDEPBAAAFrQeAUE7QEJG98AwXv/AxFhR?ASom2AwngHBFbcRAG1AAAAAA",
+    )
+    .unwrap();
+    let comments = pages.iter().map(|page| page.comment.as_str());
+    assert_eq!(
+        comments.collect::<Vec<_>>(),
+        ["Hello", "T-spin → 100% ü", "T-spin → 100% ü"]
+    );
+    let expected = board(
+        "
+        ...TTT....
+        ....T.....",
+    );
+    assert_eq!(rows(&drawn(&pages[1])), rows(&expected));
+    assert_eq!(rows(&pages[2].board), rows(&expected));
+
+    let long = "a comment long enough that the data gets broken up with question marks";
+    let page = board_from_fumen(
+        "v115@vhAAgWeBhoo2AjHnGEF22TASYTNEu788AwHZNE1rbT?ASY91DBP98AQ+brDFbEmDBPMTASIYrD0N98AQDiNErIpTAS?o/QEFbMmEJ/bTASouhEl9W5DPG98AwDEXEr9BAA",
+    )
+    .unwrap();
+    assert_eq!(page.comment, long);
+}
+
+#[test]
+fn decodes_quiz_played_through() {
+    // T straight away, O by holding the I, then the I out of hold
+    let pages = pages_from_fumen("v115@vhD1OYXAFLDmClcJSAVDEHBEooRBUoAVBJ3zBATtB5?lBAAA").unwrap();
+    let comments = pages.iter().map(|page| page.comment.as_str());
+    assert_eq!(
+        comments.collect::<Vec<_>>(),
+        ["#Q=[](T)IOS", "#Q=[](I)OS", "#Q=[I](S)", "#Q=[S]()"]
+    );
+    let queues = [
+        (None, &[Piece::T, Piece::I, Piece::O, Piece::S][..]),
+        (None, &[Piece::I, Piece::O, Piece::S]),
+        (Some(Piece::I), &[Piece::S]),
+        (Some(Piece::S), &[]),
+    ];
+    for (page, (hold, queue)) in pages.iter().zip(queues) {
+        assert_eq!(page.board.hold_piece, hold);
+        assert!(page.board.next_pieces.starts_with(queue));
+    }
+    let expected = board(
+        "
+        ...I......
+        ...I......
+        .T.I....OO
+        TTTI....OO",
+    );
+    assert_eq!(rows(&pages[3].board), rows(&expected));
+}
+
+#[test]
+fn rejects_bad_data() {
+    assert!(matches!(
+        board_from_fumen("v110@vhAAgH"),
+        Err(FumenError::Version)
+    ));
+    assert!(matches!(
+        board_from_fumen("v115@vh!AgH"),
+        Err(FumenError::Character('!'))
+    ));
+    assert!(matches!(
+        board_from_fumen("v115@vhAAg"),
+        Err(FumenError::Truncated)
+    ));
+    assert!(matches!(
+        board_from_fumen("v115@"),
+        Err(FumenError::Truncated)
+    ));
+}
+
+#[test]
+fn round_trips_board_with_piece_and_queue() {
+    let mut board = board(
+        "
+        hold: L
+        next: SZOJI
+        ZZ........
+        SZZ....GGG
+        SS..GGGGGG
+        GSGGGGGG.G",
+    );
+    let piece = piece_at(Piece::T, Rotation::East, 4, 36);
+    let fumen = board_to_fumen(&board, Some(&piece), true);
+    let page = board_from_fumen(&fumen).unwrap();
+    assert_eq!(rows(&page.board), rows(&board));
+    assert_eq!(page.piece, Some(piece));
+    assert_eq!(page.board.hold_piece, Some(Piece::L));
+    let queue = [Piece::T].iter().chain(&board.next_pieces);
+    assert_eq!(page.board.next_pieces, queue.copied().collect::<Vec<_>>());
+
+    // rows past the top of the fumen field are dropped
+    board.cells[10][0] = Some(Cell::Glay);
+    let page = board_from_fumen(&board_to_fumen(&board, None, false)).unwrap();
+    assert!(page.board.cells[10][0].is_none());
+    assert_eq!(rows(&page.board)[11..], rows(&board)[11..]);
+    assert_eq!(page.comment, "");
+}
+
+#[test]
+fn round_trips_pages() {
+    let mut board = board(
+        "
+        next: IOT
+        GGGG..GGGG",
+    );
+    let pieces = [
+        piece_at(Piece::O, Rotation::North, 4, 39),
+        piece_at(Piece::I, Rotation::North, 1, 39),
+        piece_at(Piece::T, Rotation::South, 7, 38),
+    ];
+    let mut pages = vec![];
+    for (i, piece) in pieces.into_iter().enumerate() {
+        pages.push(FumenPage {
+            board: board.clone(),
+            piece: Some(piece),
+            comment: format!("page {i}"),
+        });
+        board.set_piece(&piece).unwrap();
+        board.line_clear();
+    }
+    // the same field twice in a row, and a page carrying a comment on
+    pages.push(FumenPage {
+        board: board.clone(),
+        piece: None,
+        comment: "page 2".into(),
+    });
+    pages.push(FumenPage {
+        board: board.clone(),
+        piece: None,
+        comment: "last".into(),
+    });
+
+    let decoded = pages_from_fumen(&pages_to_fumen(&pages)).unwrap();
+    assert_eq!(decoded.len(), pages.len());
+    for (page, decoded) in pages.iter().zip(&decoded) {
+        assert_eq!(rows(&decoded.board), rows(&page.board));
+        assert_eq!(decoded.piece, page.piece);
+        assert_eq!(decoded.comment, page.comment);
+    }
+}
+
+#[test]
+fn round_trips_quiz_pages() {
+    let mut board = board("next: TIOS");
+    let pieces = [
+        piece_at(Piece::T, Rotation::North, 1, 39),
+        piece_at(Piece::O, Rotation::North, 8, 39),
+        piece_at(Piece::I, Rotation::West, 3, 38),
+    ];
+    let comments = ["#Q=[](T)IOS", "#Q=[](I)OS", "#Q=[I](S)"];
+    let mut pages = vec![];
+    for (piece, comment) in pieces.into_iter().zip(comments) {
+        pages.push(FumenPage {
+            board: board.clone(),
+            piece: Some(piece),
+            comment: comment.into(),
+        });
+        board.set_piece(&piece).unwrap();
+        board.line_clear();
+    }
+
+    // the quiz carries on by itself, so only the first comment is written
+    let fumen = pages_to_fumen(&pages);
+    assert_eq!(fumen, "v115@vhC1OYXAFLDmClcJSAVDEHBEooRBUoAVBJ3zBATtB5?lB");
+    let decoded = pages_from_fumen(&fumen).unwrap();
+    for (page, decoded) in pages.iter().zip(&decoded) {
+        assert_eq!(rows(&decoded.board), rows(&page.board));
+        assert_eq!(decoded.comment, page.comment);
+    }
+}
@@ -0,0 +1,96 @@
+//! Garbage rows pushed in from the bottom, right away or once the next piece
+//! locks, and topping out when they push the stack out of the buffer.
+
+use tetris::*;
+
+fn row(board: &Board, y: usize) -> String {
+    board.cells[y]
+        .iter()
+        .map(|c| c.map_or('.', Cell::to_char))
+        .collect()
+}
+
+/// Drops the falling piece and plays on until it has locked.
+fn hard_drop(game: &mut Game) {
+    game.set_input(Input {
+        hard_drop: true,
+        ..Default::default()
+    });
+    game.update();
+    game.set_input(Input::default());
+    while game.get_locked_piece().is_none() {
+        game.update();
+    }
+}
+
+#[test]
+fn pushes_garbage_under_the_stack() {
+    let mut board = "
+        ..........
+        ...TTT....
+        ....T....."
+        .parse::<Board>()
+        .unwrap();
+    assert!(!board.push_garbage(2, 7));
+    assert_eq!(row(&board, 36), "...TTT....");
+    assert_eq!(row(&board, 37), "....T.....");
+    assert_eq!(row(&board, 38), "GGGGGGG.GG");
+    assert_eq!(row(&board, 39), "GGGGGGG.GG");
+
+    // holes past the wall go in the last column
+    board.push_garbage(1, 10);
+    assert_eq!(row(&board, 38), "GGGGGGG.GG");
+    assert_eq!(row(&board, 39), "GGGGGGGGG.");
+    assert!(Board::garbage_row(usize::MAX)[9].is_none());
+}
+
+#[test]
+fn overflows_out_of_the_buffer() {
+    let mut board = Board::default();
+    board.cells[1][4] = Some(Cell::Red);
+    assert!(!board.push_garbage(1, 0));
+    assert!(board.cells[0][4].is_some());
+    assert!(board.push_garbage(1, 0));
+    assert_eq!(row(&board, 0), "..........");
+    assert_eq!(row(&board, 39), ".GGGGGGGGG");
+}
+
+#[test]
+fn receives_garbage_on_lock() {
+    let mut game = Game::with_seed(1);
+    game.receive_garbage(2, 3);
+    game.receive_garbage(1, 42);
+    assert_eq!(game.get_incoming_garbage(), 3);
+    assert!(game.get_board().cells[39].iter().all(Option::is_none));
+
+    hard_drop(&mut game);
+    assert_eq!(game.get_incoming_garbage(), 0);
+    let events = game.get_event_queue().drain(..).collect::<Vec<_>>();
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, TetrisEvent::GarbageReceived(3))));
+    let board = game.get_board();
+    assert_eq!(row(&board, 39), "GGGGGGGGG.");
+    assert_eq!(row(&board, 38), "GGG.GGGGGG");
+    assert_eq!(row(&board, 37), "GGG.GGGGGG");
+    assert!(!game.is_game_over());
+}
+
+#[test]
+fn tops_out_pushing_the_piece_off_the_top() {
+    // the falling piece rides the garbage up to row 0 and then has nowhere
+    // left to go
+    let mut game = Game::with_seed(0);
+    assert!(game.push_garbage(40, 0));
+    assert!(game.is_game_over());
+    let topped_out = game
+        .get_event_queue()
+        .iter()
+        .filter(|e| matches!(e, TetrisEvent::ToppedOut))
+        .count();
+    assert_eq!(topped_out, 1);
+    assert!(game
+        .get_event_queue()
+        .iter()
+        .any(|e| matches!(e, TetrisEvent::GarbageInserted(40))));
+}
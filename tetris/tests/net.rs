@@ -0,0 +1,92 @@
+//! Lockstep versus between two in-process peers over 127.0.0.1, and the
+//! messages they send each other.
+
+use std::{net::TcpListener, thread};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tetris::*;
+
+const FRAMES: u32 = 3600;
+const INPUT_DELAY: u32 = 2;
+
+fn run(mut session: Lockstep, seed: u64) -> Result<(u64, Lockstep), NetError> {
+    let mut versus = Versus::with_seed(AttackTable::default(), 3, seed);
+    let mut rng = StdRng::seed_from_u64(session.get_local_player() as u64);
+    while session.get_frame() < FRAMES {
+        if session.wants_local_input() {
+            session.add_local_input(Input::from_bits(rng.gen()))?;
+        }
+        session.poll()?;
+        if !session.advance(&mut versus)? {
+            thread::yield_now();
+        }
+    }
+    // hand the session back so the socket stays open until both sides are done
+    Ok((versus_checksum(&versus), session))
+}
+
+#[test]
+fn peers_end_on_same_state() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = thread::spawn(move || {
+        let (session, seed) = Lockstep::host_on(listener, INPUT_DELAY).unwrap();
+        assert_eq!(session.get_local_player(), 0);
+        run(session, seed).unwrap()
+    });
+    let (session, seed) = Lockstep::join(addr).unwrap();
+    assert_eq!(session.get_local_player(), 1);
+    assert_eq!(session.get_input_delay(), INPUT_DELAY);
+    let (joined, _session) = run(session, seed).unwrap();
+    let (hosted, _) = host.join().unwrap();
+    assert_eq!(hosted, joined);
+}
+
+#[test]
+fn round_trips_messages() {
+    let input = Input {
+        left: true,
+        hard_drop: true,
+        hold: true,
+        ..Default::default()
+    };
+    let messages = [
+        Message::Hello {
+            seed: 0x0123_4567_89ab_cdef,
+            input_delay: 3,
+        },
+        Message::Input {
+            frame: 70000,
+            input,
+        },
+        Message::Checksum {
+            frame: 120,
+            checksum: u64::MAX - 1,
+        },
+        Message::Garbage {
+            frame: 42,
+            rows: 4,
+            hole: 9,
+        },
+    ];
+    let mut stream = vec![];
+    for message in messages {
+        let buf = message.encode();
+        let (decoded, len) = Message::decode(&buf).unwrap().unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(len, buf.len());
+        // anything short of the whole message waits for more
+        assert!(Message::decode(&buf[..len - 1]).unwrap().is_none());
+        stream.extend(buf);
+    }
+
+    // back to back, as they come off the socket
+    let mut rest = &stream[..];
+    for message in messages {
+        let (decoded, len) = Message::decode(rest).unwrap().unwrap();
+        assert_eq!(decoded, message);
+        rest = &rest[len..];
+    }
+    assert!(Message::decode(rest).unwrap().is_none());
+    assert!(matches!(Message::decode(&[4]), Err(NetError::Protocol)));
+}
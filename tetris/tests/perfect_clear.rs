@@ -0,0 +1,109 @@
+//! Perfect clears the solver has to find or rule out, with each solution
+//! played out on the board.
+
+use tetris::*;
+use Piece::*;
+
+/// Plays `steps` with the pieces `queue` and `hold` hand out, following each
+/// placement's moves from spawn, and asserts the board ends up empty.
+fn replay(board: &Board, queue: &[Piece], mut hold: Option<Piece>, steps: &[PcStep]) {
+    let mut board = board.clone();
+    let mut queue = queue.iter().copied();
+    for step in steps {
+        let current = queue.next();
+        let piece = match (step.hold, hold) {
+            (false, _) => current.unwrap(),
+            (true, Some(held)) => {
+                hold = current;
+                held
+            }
+            (true, None) => {
+                hold = current;
+                queue.next().unwrap()
+            }
+        };
+        assert_eq!(step.placement.piece.piece_state.get_kind(), piece);
+
+        let mut falling = FallingPiece::from_piece_state(PieceState::from_piece(piece));
+        for m in &step.placement.moves {
+            assert!(m.apply(&board, &mut falling), "{m:?} didn't move the piece");
+        }
+        assert!(falling.check_shift_collision(&board, 0, 1));
+        assert!(step.placement.is_at(&falling));
+        board.set_piece(&falling).unwrap();
+        board.line_clear();
+        board.line_shrink();
+    }
+    assert!(board.cells.iter().flatten().all(Option::is_none));
+}
+
+fn solve(board: &Board, queue: &[Piece], hold: Option<Piece>, hold_enabled: bool) -> bool {
+    let steps = find_perfect_clear(board, queue, hold, hold_enabled);
+    if let Some(steps) = &steps {
+        replay(board, queue, hold, steps);
+    }
+    steps.is_some()
+}
+
+#[test]
+fn finds_perfect_clear_on_empty_board() {
+    let board = Board::default();
+    let queue = [I, I, O, J, J];
+    let steps = find_perfect_clear(&board, &queue, None, false).unwrap();
+    assert_eq!(steps.len(), 5);
+    replay(&board, &queue, None, &steps);
+}
+
+#[test]
+fn finds_perfect_clear_without_hold() {
+    let board = "
+        GGGG......
+        GGGG......"
+        .parse::<Board>()
+        .unwrap();
+    assert!(solve(&board, &[I, L, J], None, false));
+    assert!(solve(&board, &[I, L, J], None, true));
+    assert!(solve(&board, &[L, J, I], None, false));
+    // two O's and an I can't tile six by two
+    assert!(!solve(&board, &[I, O, O], None, true));
+}
+
+#[test]
+fn finds_perfect_clear_with_hold() {
+    let board = "
+        GGGGGG....
+        GGGGGG...."
+        .parse::<Board>()
+        .unwrap();
+    assert!(!solve(&board, &[O, I, O], None, false));
+    assert!(solve(&board, &[O, I, O], None, true));
+    let steps = find_perfect_clear(&board, &[O, I, O], None, true).unwrap();
+    assert!(steps.iter().any(|step| step.hold));
+
+    // the I waiting in hold is the only way
+    let board = "GGGGGG....".parse::<Board>().unwrap();
+    assert!(!solve(&board, &[O], Some(I), false));
+    assert!(solve(&board, &[O], Some(I), true));
+    assert!(find_perfect_clear_after_hold(&board, &[O], Some(I)).is_none());
+    assert!(find_perfect_clear_after_hold(&board, &[I], Some(O)).is_some());
+}
+
+#[test]
+fn rules_out_impossible_boards() {
+    // three empty cells can't take whole pieces at any height
+    let board = "GGGGGGG...".parse::<Board>().unwrap();
+    assert!(!solve(&board, &[I, O, T, L, J, S, Z], None, true));
+
+    let board = "
+        G.........
+        GGGGGGGGG.
+        GGGGGGGGG.
+        GGGGGGGGG.
+        GGGGGGGGG."
+        .parse::<Board>()
+        .unwrap();
+    assert!(find_perfect_clear(&board, &[I, I], None, true).is_none());
+
+    // too few pieces
+    assert!(!solve(&Board::default(), &[I, I, O, J], None, false));
+}
@@ -0,0 +1,64 @@
+//! Placements that are only reachable by stopping partway down a well.
+
+use tetris::*;
+
+/// Plays `moves` from the spawn position and hard drops, like a bot would.
+fn replay(board: &Board, piece: Piece, moves: &[Move]) -> FallingPiece {
+    let mut falling = FallingPiece::from_piece_state(PieceState::from_piece(piece));
+    for m in moves {
+        assert!(m.apply(board, &mut falling), "{m:?} didn't move the piece");
+    }
+    assert!(falling.check_shift_collision(board, 0, 1));
+    falling
+}
+
+fn find(board: &Board, piece: Piece, cells: &[(usize, usize)]) -> Option<Placement> {
+    find_placements(board, piece)
+        .into_iter()
+        .find(|p| p.get_cells() == cells)
+}
+
+#[test]
+fn finds_tuck_halfway_down_a_well() {
+    // the O has to stop level with the cave, slide over and drop into the pit
+    let board = "
+        ..GGGGGGGG
+        ..GGGGGGGG
+        ......GGGG
+        ......GGGG
+        ..GG..GGGG
+        ..GG..GGGG
+        ..GG..GGGG
+        ..GG..GGGG"
+        .parse::<Board>()
+        .unwrap();
+    let placement = find(&board, Piece::O, &[(4, 38), (4, 39), (5, 38), (5, 39)]).unwrap();
+    assert!(placement.moves.contains(&Move::Down));
+    assert!(placement.is_at(&replay(&board, Piece::O, &placement.moves)));
+
+    let cave = find(&board, Piece::O, &[(2, 34), (2, 35), (3, 34), (3, 35)]);
+    assert!(cave.is_some());
+}
+
+#[test]
+fn finds_t_spin_under_a_roof() {
+    // the slot on the left is only open from the well on the right
+    let board = "
+        GGGGGGG...
+        GGGGGGG...
+        G.........
+        G.........
+        ...GGGG...
+        G.GGGGG...
+        GGGGGGG..."
+        .parse::<Board>()
+        .unwrap();
+    let placement = find(&board, Piece::T, &[(0, 37), (1, 37), (1, 38), (2, 37)]).unwrap();
+    assert_eq!(placement.t_spin, Some(TSpin::Full));
+    assert!(placement.last_rotation);
+    assert!(placement.moves.contains(&Move::Down));
+
+    let piece = replay(&board, Piece::T, &placement.moves);
+    assert!(placement.is_at(&piece));
+    assert_eq!(board.get_t_spin(&piece), Some(TSpin::Full));
+}
@@ -0,0 +1,250 @@
+//! The bundled puzzle pack, and puzzles played through with scripted
+//! solutions.
+
+use tetris::*;
+
+const PACK: &str = include_str!("../../standalone/assets/puzzles.txt");
+const FRAMES: usize = 3600;
+
+/// One piece of a solution: whether to hold first and the cells it locks on.
+type Step = (bool, [(usize, usize); 4]);
+
+/// Presses the keys the placement search finds towards each step, one piece
+/// at a time, until the puzzle ends.
+fn play(spec: PuzzleSpec, solution: &[Step]) -> Puzzle {
+    let mut puzzle = Puzzle::new(vec![spec]);
+    let mut steps = solution.iter();
+    let mut step = None;
+    let mut held = false;
+    let mut previous = Input::default();
+    for _ in 0..FRAMES {
+        if !matches!(puzzle.get_status(), Status::Game) {
+            break;
+        }
+        let mut input = Input::default();
+        match puzzle.get_current_piece() {
+            None => step = None,
+            Some(current) => {
+                let (hold, mut cells) = *step.get_or_insert_with(|| {
+                    held = false;
+                    *steps.next().expect("the puzzle wants more pieces")
+                });
+                cells.sort();
+                if hold && !held {
+                    input.hold = !previous.hold;
+                    held = input.hold;
+                } else {
+                    // the shortest route is the empty one once the piece is
+                    // in place, spun in or not
+                    let board = puzzle.get_board();
+                    let placement = find_placements_from(&board, current)
+                        .into_iter()
+                        .filter(|p| p.get_cells() == cells)
+                        .min_by_key(|p| p.moves.len())
+                        .expect("the step can't be reached");
+                    match placement.moves[..] {
+                        [] | [Move::SonicDrop] => input.hard_drop = !previous.hard_drop,
+                        [Move::Left, ..] => input.left = !previous.left,
+                        [Move::Right, ..] => input.right = !previous.right,
+                        [Move::Cw, ..] => input.cw = !previous.cw,
+                        [Move::Ccw, ..] => input.ccw = !previous.ccw,
+                        [Move::Down | Move::SonicDrop, ..] => input.soft_drop = true,
+                    }
+                }
+            }
+        }
+        puzzle.set_input(input);
+        puzzle.update();
+        // as the frontend does, or the puzzle counts events again
+        puzzle.get_event_queue().clear();
+        previous = input;
+    }
+    puzzle
+}
+
+fn bundled(name: &str) -> PuzzleSpec {
+    puzzles_from_str(PACK)
+        .unwrap()
+        .into_iter()
+        .find(|spec| spec.name == name)
+        .unwrap()
+}
+
+#[test]
+fn parses_bundled_pack() {
+    let pack = puzzles_from_str(PACK).unwrap();
+    let names = pack.iter().map(|spec| spec.name.as_str());
+    assert_eq!(
+        names.collect::<Vec<_>>(),
+        [
+            "well",
+            "spin single",
+            "spin double",
+            "first perfect clear",
+            "flat bottom",
+            "hold on",
+            "square tower"
+        ]
+    );
+    assert_eq!(pack[0].goal, PuzzleGoal::Lines(4));
+    assert_eq!(pack[1].goal, PuzzleGoal::TSpin(1));
+    assert_eq!(pack[2].goal, PuzzleGoal::TSpin(2));
+    assert_eq!(pack[3].goal, PuzzleGoal::PerfectClear);
+    assert!(!pack[0].hold_enabled);
+    assert!(pack[3].hold_enabled);
+    assert_eq!(pack[3].pieces, Some(3));
+    assert_eq!(pack[5].board.hold_piece, Some(Piece::I));
+    assert_eq!(
+        pack[6].board.next_pieces[..4],
+        [Piece::I, Piece::O, Piece::O, Piece::O]
+    );
+}
+
+#[test]
+fn solves_lines() {
+    let puzzle = play(
+        bundled("well"),
+        &[(false, [(9, 36), (9, 37), (9, 38), (9, 39)])],
+    );
+    assert!(puzzle.is_solved());
+    assert_eq!(puzzle.get_lines(), 4);
+
+    // the I starts in hold
+    let puzzle = play(
+        bundled("hold on"),
+        &[(true, [(5, 36), (5, 37), (5, 38), (5, 39)])],
+    );
+    assert!(puzzle.is_solved());
+}
+
+#[test]
+fn solves_perfect_clear() {
+    let puzzle = play(
+        bundled("flat bottom"),
+        &[
+            (false, [(5, 39), (6, 39), (7, 39), (8, 39)]),
+            (false, [(4, 38), (5, 38), (6, 38), (4, 39)]),
+            (false, [(7, 38), (8, 38), (9, 38), (9, 39)]),
+        ],
+    );
+    assert!(puzzle.is_solved());
+
+    // both O's, holding the I in between
+    let puzzle = play(
+        bundled("first perfect clear"),
+        &[
+            (false, [(6, 38), (7, 38), (6, 39), (7, 39)]),
+            (true, [(8, 38), (9, 38), (8, 39), (9, 39)]),
+        ],
+    );
+    assert!(puzzle.is_solved());
+}
+
+#[test]
+fn holds_into_empty_slot() {
+    // the I goes into hold for good, taking the first O out of the queue
+    let spec = "
+        name: hold first
+        goal: pc
+        next: IOO
+        GGGGGG....
+        GGGGGG...."
+        .parse()
+        .unwrap();
+    let puzzle = play(
+        spec,
+        &[
+            (true, [(6, 38), (7, 38), (6, 39), (7, 39)]),
+            (false, [(8, 38), (9, 38), (8, 39), (9, 39)]),
+        ],
+    );
+    assert!(puzzle.is_solved());
+    assert_eq!(puzzle.get_hold(), Some(Piece::I));
+
+    // holding the first O leaves the other one stuck in hold, and the
+    // sequence ends on the last lock instead of dealing a random piece
+    let puzzle = play(
+        bundled("first perfect clear"),
+        &[
+            (true, [(9, 36), (9, 37), (9, 38), (9, 39)]),
+            (false, [(6, 38), (7, 38), (6, 39), (7, 39)]),
+        ],
+    );
+    assert!(!puzzle.is_solved());
+    assert!(matches!(puzzle.get_status(), Status::End));
+    assert!(puzzle.get_current_piece().is_none());
+    assert_eq!(puzzle.get_pieces_remaining(), Some(0));
+    assert!(puzzle.get_queue().is_empty());
+}
+
+#[test]
+fn shows_only_the_sequence() {
+    let mut puzzle = Puzzle::new(vec![bundled("first perfect clear")]);
+    assert_eq!(puzzle.get_queue(), [Piece::I, Piece::O]);
+    assert_eq!(puzzle.get_pieces_remaining(), Some(3));
+
+    // the hold slot is the only place left for a piece, so holding into it
+    // is turned off rather than dealing a random piece
+    let spec = "
+        name: last piece
+        goal: lines 1
+        next: O
+        GGGGGGGG.."
+        .parse()
+        .unwrap();
+    puzzle = Puzzle::new(vec![spec]);
+    assert!(puzzle.get_queue().is_empty());
+    puzzle.set_input(Input {
+        hold: true,
+        ..Default::default()
+    });
+    puzzle.update();
+    assert_eq!(puzzle.get_hold(), None);
+    assert_eq!(
+        puzzle.get_current_piece().unwrap().piece_state.get_kind(),
+        Piece::O
+    );
+}
+
+#[test]
+fn solves_t_spins() {
+    let slot = [(3, 38), (4, 38), (5, 38), (4, 39)];
+    let puzzle = play(bundled("spin single"), &[(false, slot)]);
+    assert!(puzzle.is_solved());
+    let puzzle = play(bundled("spin double"), &[(false, slot)]);
+    assert!(puzzle.is_solved());
+    assert_eq!(puzzle.get_lines(), 2);
+
+    let spec = "
+        name: triple
+        goal: tst
+        allow hold: no
+        next: T
+        GG........
+        G.........
+        G.GGGGGGGG
+        G..GGGGGGG
+        G.GGGGGGGG"
+        .parse()
+        .unwrap();
+    let puzzle = play(spec, &[(false, [(1, 37), (1, 38), (1, 39), (2, 38)])]);
+    assert!(puzzle.is_solved());
+    assert_eq!(puzzle.get_lines(), 3);
+}
+
+#[test]
+fn fails_without_the_goal() {
+    // a plain drop into the slot clears two lines but isn't a t-spin
+    let spec = "
+        name: not a spin
+        goal: tss
+        allow hold: no
+        next: T
+        GGG...GGGG
+        GGGG.GGGGG"
+        .parse()
+        .unwrap();
+    let puzzle = play(spec, &[(false, [(3, 38), (4, 38), (5, 38), (4, 39)])]);
+    assert!(!puzzle.is_solved());
+    assert!(matches!(puzzle.get_status(), Status::End));
+}
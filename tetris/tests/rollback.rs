@@ -0,0 +1,125 @@
+//! Rollback versus between two in-process peers whose inputs go through a
+//! fake link with latency and jitter. Both ends and a plain lockstep replay of
+//! the same inputs have to end on the same state.
+
+use std::collections::VecDeque;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tetris::*;
+
+const SEED: u64 = 0x6e61617269;
+const FRAMES: u32 = 3600;
+
+/// One direction of the link. Packets may overtake each other.
+struct Link {
+    rng: StdRng,
+    latency: u32,
+    jitter: u32,
+    in_flight: VecDeque<(u32, u32, Input)>,
+}
+
+impl Link {
+    fn new(seed: u64, latency: u32, jitter: u32) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            latency,
+            jitter,
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    fn send(&mut self, tick: u32, frame: u32, input: Input) {
+        let arrival = tick + self.latency + self.rng.gen_range(0..=self.jitter);
+        self.in_flight.push_back((arrival, frame, input));
+    }
+
+    fn deliver(&mut self, tick: u32, peer: &mut Rollback) {
+        self.in_flight.retain(|&(arrival, frame, input)| {
+            if arrival <= tick {
+                peer.add_remote_input(frame, input);
+            }
+            arrival > tick
+        });
+    }
+}
+
+/// Plays `FRAMES` of random inputs and returns the rollbacks each peer made.
+fn assert_converges(input_delay: u32, latency: u32, jitter: u32) -> [u32; PLAYERS] {
+    let new_versus = || Versus::with_seed(AttackTable::default(), 3, SEED);
+    let mut peers = [
+        Rollback::new(new_versus(), 0, input_delay),
+        Rollback::new(new_versus(), 1, input_delay),
+    ];
+    let mut links = [Link::new(2, latency, jitter), Link::new(3, latency, jitter)];
+    let mut rngs = [StdRng::seed_from_u64(0), StdRng::seed_from_u64(1)];
+    let mut sent: [Vec<Input>; PLAYERS] = [
+        vec![Input::default(); input_delay as usize],
+        vec![Input::default(); input_delay as usize],
+    ];
+
+    let mut tick = 0;
+    while peers.iter().any(|peer| peer.get_confirmed_frame() < FRAMES) {
+        for player in 0..PLAYERS {
+            let peer = &mut peers[player];
+            if peer.wants_local_input() && sent[player].len() < FRAMES as usize {
+                let input = Input::from_bits(rngs[player].gen());
+                let frame = peer.add_local_input(input);
+                links[player].send(tick, frame, input);
+                sent[player].push(input);
+            }
+        }
+        let [first, second] = &mut peers;
+        links[0].deliver(tick, second);
+        links[1].deliver(tick, first);
+        for peer in peers.iter_mut() {
+            peer.advance();
+        }
+        tick += 1;
+        assert!(tick < FRAMES * 10, "peers stopped advancing");
+    }
+
+    let mut replay = new_versus();
+    for frame in 0..FRAMES as usize {
+        replay.step([sent[0][frame], sent[1][frame]]);
+    }
+    let expected = versus_checksum(&replay);
+
+    for peer in peers.iter() {
+        assert_eq!(peer.get_frame(), FRAMES);
+        assert_eq!(peer.get_confirmed_checksum(), expected);
+        assert_eq!(versus_checksum(peer.get_versus()), expected);
+        for player in 0..PLAYERS {
+            assert_eq!(
+                checksum(&peer.get_versus().get_player(player).inner),
+                checksum(&replay.get_player(player).inner)
+            );
+        }
+    }
+    peers.each_ref().map(Rollback::get_rollbacks)
+}
+
+#[test]
+fn converges_without_latency() {
+    assert_eq!(assert_converges(0, 0, 0), [0, 0]);
+}
+
+#[test]
+fn converges_when_input_delay_covers_latency() {
+    assert_eq!(assert_converges(3, 2, 0), [0, 0]);
+}
+
+#[test]
+fn converges_with_low_latency() {
+    let rollbacks = assert_converges(1, 3, 5);
+    assert!(rollbacks.iter().all(|&rollbacks| rollbacks > 0));
+}
+
+#[test]
+fn converges_with_high_latency() {
+    assert_converges(2, 10, 8);
+}
+
+#[test]
+fn converges_past_max_prediction() {
+    assert_converges(0, 20, 20);
+}
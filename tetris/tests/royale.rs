@@ -0,0 +1,115 @@
+//! Knock-outs and badges in a royale against bots.
+
+use tetris::*;
+
+/// Only perfect clears send anything, and enough to top anyone out.
+fn perfect_clears_only() -> AttackTable {
+    AttackTable {
+        single: 0,
+        double: 0,
+        triple: 0,
+        tetris: 0,
+        t_spin_mini_single: 0,
+        t_spin_mini_double: 0,
+        t_spin_single: 0,
+        t_spin_double: 0,
+        t_spin_triple: 0,
+        perfect_clear: 40,
+        back_to_back: 0,
+        combo: vec![0],
+    }
+}
+
+/// Gives player 0 an O that perfect clears as soon as it lands.
+fn perfect_clear_royale(players: usize) -> Royale {
+    let mut royale = Royale::with_seed(players, perfect_clears_only(), 3);
+    let board = "
+        next: O
+        GGGG..GGGG
+        GGGG..GGGG"
+        .parse()
+        .unwrap();
+    royale.inner = Game::from_board(board);
+    royale
+}
+
+/// Plays on until `done`, giving up after a few minutes of frames.
+fn play_until(royale: &mut Royale, done: impl Fn(&Royale) -> bool) {
+    for _ in 0..60 * 60 * 5 {
+        if done(royale) {
+            return;
+        }
+        royale.update();
+        royale.get_event_queue().clear();
+        royale.get_sound_queue().clear();
+        royale.get_tgm3events().clear();
+        royale.get_tgm3sounds().clear();
+    }
+    panic!("royale never got there");
+}
+
+#[test]
+fn knocks_out_the_last_opponent() {
+    let mut royale = perfect_clear_royale(2);
+    assert_eq!(royale.get_player(0).get_target(), Some(1));
+    play_until(&mut royale, |royale| {
+        matches!(royale.get_status(), Status::End)
+    });
+
+    assert_eq!(royale.get_player(0).get_sent(), 40);
+    assert_eq!(royale.get_player(0).get_kos(), 1);
+    assert_eq!(royale.get_player(0).get_badge_points(), 1);
+    assert_eq!(royale.get_player(0).get_place(), Some(1));
+    assert_eq!(royale.get_player(1).get_place(), Some(2));
+    assert_eq!(royale.get_alive(), 1);
+}
+
+#[test]
+fn credits_the_last_attacker() {
+    let mut royale = perfect_clear_royale(3);
+    let target = royale.get_player(0).get_target().unwrap();
+    play_until(&mut royale, |royale| royale.get_alive() == 2);
+
+    assert!(matches!(royale.get_status(), Status::Game));
+    assert!(!royale.get_player(target).is_alive());
+    assert_eq!(royale.get_player(target).get_place(), Some(3));
+    assert_eq!(royale.get_player(0).get_kos(), 1);
+    assert_eq!(royale.get_player(0).get_badge_points(), 1);
+    assert_eq!(royale.get_player(0).get_badges(), 0);
+    // nobody targets the knocked out player
+    let survivor = 3 - target;
+    assert_ne!(royale.get_player(survivor).get_target(), Some(target));
+    assert_eq!(royale.get_player(0).get_place(), None);
+}
+
+#[test]
+fn plays_seeded_match() {
+    let play = || {
+        let mut royale = Royale::with_seed(4, AttackTable::default(), 11);
+        play_until(&mut royale, |royale| {
+            matches!(royale.get_status(), Status::End)
+        });
+        royale
+    };
+    let royale = play();
+    let again = play();
+    assert_eq!(royale.get_frames(), again.get_frames());
+
+    let players = royale.get_players();
+    let mut places = vec![];
+    let mut kos = 0;
+    for (player, other) in players.iter().zip(again.get_players()) {
+        assert_eq!(player.get_place(), other.get_place());
+        assert_eq!(player.get_kos(), other.get_kos());
+        assert_eq!(player.get_badge_points(), other.get_badge_points());
+        assert!(player.get_badge_points() >= player.get_kos());
+        places.extend(player.get_place());
+        kos += player.get_kos();
+    }
+    // player 0 left standing still, so it went out in the match
+    assert!(!players[0].is_alive());
+    places.sort();
+    places.dedup();
+    assert_eq!(places.len(), players.len() - royale.get_alive());
+    assert!(kos <= players.len() - royale.get_alive());
+}
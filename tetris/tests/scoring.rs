@@ -0,0 +1,132 @@
+//! Guideline scoring fed the events of a lock.
+
+use tetris::*;
+
+fn lock(t_spin: Option<TSpin>, lines: usize) -> Vec<TetrisEvent> {
+    let piece = FallingPiece::from_piece_state(PieceState::from_piece(Piece::T));
+    let mut events = vec![];
+    if let Some(t_spin) = t_spin {
+        events.push(TetrisEvent::TSpin(t_spin));
+    }
+    events.push(TetrisEvent::PieceLocked(piece));
+    if lines > 0 {
+        events.push(TetrisEvent::LineCleared(lines));
+    }
+    events
+}
+
+/// A board with something left on it after the clear.
+fn stack() -> Board {
+    "GGGGGGGGG.".parse().unwrap()
+}
+
+fn empty() -> Board {
+    "..........".parse().unwrap()
+}
+
+#[test]
+fn scores_clears() {
+    let clears = [
+        (None, 0, None, 0),
+        (None, 1, Some(ClearType::Single), 100),
+        (None, 2, Some(ClearType::Double), 300),
+        (None, 3, Some(ClearType::Triple), 500),
+        (None, 4, Some(ClearType::Tetris), 800),
+        (Some(TSpin::Mini), 0, Some(ClearType::TSpinMini), 100),
+        (Some(TSpin::Mini), 1, Some(ClearType::TSpinMiniSingle), 200),
+        (Some(TSpin::Mini), 2, Some(ClearType::TSpinMiniDouble), 400),
+        (Some(TSpin::Full), 0, Some(ClearType::TSpin), 400),
+        (Some(TSpin::Full), 1, Some(ClearType::TSpinSingle), 800),
+        (Some(TSpin::Full), 2, Some(ClearType::TSpinDouble), 1200),
+        (Some(TSpin::Full), 3, Some(ClearType::TSpinTriple), 1600),
+    ];
+    for (t_spin, lines, clear_type, score) in clears {
+        let mut scoring = GuidelineScore::default();
+        scoring.set_level(2);
+        let result = scoring.update(&lock(t_spin, lines), &stack()).unwrap();
+        assert_eq!(result.clear_type, clear_type);
+        assert_eq!(result.lines, lines);
+        assert_eq!(scoring.get_score(), score * 2, "{clear_type:?}");
+        assert_eq!(scoring.get_lines(), lines);
+        if let Some(clear_type) = clear_type {
+            assert_eq!(scoring.get_clear_count(clear_type), 1);
+        }
+    }
+}
+
+#[test]
+fn scores_drops_without_lock() {
+    let mut scoring = GuidelineScore::default();
+    let events = [TetrisEvent::SoftDropped(3), TetrisEvent::HardDropped(5)];
+    assert_eq!(scoring.update(&events, &stack()), None);
+    assert_eq!(scoring.get_score(), 3 + 2 * 5);
+}
+
+#[test]
+fn scores_back_to_back() {
+    let mut scoring = GuidelineScore::default();
+    let result = scoring.update(&lock(None, 4), &stack()).unwrap();
+    assert!(!result.back_to_back);
+    assert!(scoring.is_back_to_back());
+
+    // a spin without lines keeps the chain, and a single breaks the combo
+    scoring.update(&lock(Some(TSpin::Full), 0), &stack());
+    assert!(scoring.is_back_to_back());
+    let result = scoring
+        .update(&lock(Some(TSpin::Full), 2), &stack())
+        .unwrap();
+    assert!(result.back_to_back);
+    assert_eq!(result.combo, Some(0));
+    assert_eq!(scoring.get_score(), 800 + 400 + 1200 * 3 / 2);
+
+    // an easy clear breaks the chain
+    scoring.update(&lock(None, 1), &stack());
+    assert!(!scoring.is_back_to_back());
+    let result = scoring.update(&lock(None, 4), &stack()).unwrap();
+    assert!(!result.back_to_back);
+}
+
+#[test]
+fn scores_combos() {
+    let mut scoring = GuidelineScore::default();
+    for combo in 0..3 {
+        let result = scoring.update(&lock(None, 1), &stack()).unwrap();
+        assert_eq!(result.combo, Some(combo));
+        assert_eq!(scoring.get_combo(), Some(combo));
+    }
+    assert_eq!(scoring.get_score(), 3 * 100 + 50 * (0 + 1 + 2));
+
+    let result = scoring.update(&lock(None, 0), &stack()).unwrap();
+    assert_eq!(result.combo, None);
+    assert_eq!(scoring.get_combo(), None);
+    assert_eq!(scoring.get_lines(), 3);
+}
+
+#[test]
+fn scores_perfect_clears() {
+    let mut scoring = GuidelineScore::default();
+    let result = scoring.update(&lock(None, 2), &empty()).unwrap();
+    assert_eq!(
+        result,
+        LockResult {
+            clear_type: Some(ClearType::Double),
+            lines: 2,
+            combo: Some(0),
+            back_to_back: false,
+            perfect_clear: true,
+        }
+    );
+    assert_eq!(scoring.get_clear_count(ClearType::PerfectClear), 1);
+    assert_eq!(scoring.get_score(), 300 + 1200);
+
+    let mut scoring = GuidelineScore::default();
+    scoring.set_level(3);
+    scoring.update(&lock(None, 4), &empty());
+    assert_eq!(scoring.get_score(), (800 + 2000) * 3);
+
+    // locking without a clear on an empty board is no perfect clear
+    let mut scoring = GuidelineScore::default();
+    let result = scoring.update(&lock(None, 0), &empty()).unwrap();
+    assert!(!result.perfect_clear);
+    assert_eq!(scoring.get_clear_count(ClearType::PerfectClear), 0);
+}
@@ -0,0 +1,38 @@
+//! The stub bot from `src/bin` playing through `TbpBot` as a child process.
+
+use tetris::*;
+
+const PIECES: usize = 40;
+const FRAMES: usize = 60 * 60;
+
+#[test]
+fn stub_bot_plays_marathon() {
+    let mut bot = TbpBot::spawn(env!("CARGO_BIN_EXE_tbp_stub"), [] as [&str; 0]).unwrap();
+    bot.set_blocking(true);
+    assert_eq!(bot.get_name(), "naaris stub");
+    assert_eq!(bot.get_author(), "naaris");
+
+    let mut game = Marathon::with_seed(true, 7);
+    let mut pieces = 0;
+    for _ in 0..FRAMES {
+        let input = bot.next_input(&game).unwrap();
+        game.set_input(input);
+        game.update();
+        for event in game.get_event_queue().drain(..) {
+            match event {
+                TetrisEvent::PieceLocked(_) => pieces += 1,
+                TetrisEvent::ToppedOut => panic!("topped out after {pieces} pieces"),
+                _ => {}
+            }
+        }
+        game.get_sound_queue().clear();
+        game.get_tgm3events().clear();
+        if pieces == PIECES {
+            break;
+        }
+    }
+    assert_eq!(pieces, PIECES);
+    assert!(matches!(game.get_status(), Status::Game));
+    // a stub that only stacked would be near the top by now
+    assert!(game.get_lines() > 0);
+}
@@ -0,0 +1,206 @@
+//! Garbage sent per clear, cancelling it against what is on the way in,
+//! and best-of matches between two players.
+
+use tetris::*;
+
+fn result(clear_type: Option<ClearType>, lines: usize) -> LockResult {
+    LockResult {
+        clear_type,
+        lines,
+        combo: None,
+        back_to_back: false,
+        perfect_clear: false,
+    }
+}
+
+/// Drops the falling piece and plays both sides on until it has locked.
+fn hard_drop(versus: &mut Versus, player: usize) {
+    versus.set_input(
+        player,
+        Input {
+            hard_drop: true,
+            ..Default::default()
+        },
+    );
+    versus.update();
+    versus.set_input(player, Input::default());
+    while versus.get_player(player).inner.get_locked_piece().is_none() {
+        versus.update();
+    }
+}
+
+/// Two rows an O dropped from spawn clears, short of a perfect clear.
+fn o_double() -> Game {
+    let board = "
+        next: O
+        GGGG..GGGG
+        GGGG..GGGG
+        GGGGGGGGG."
+        .parse()
+        .unwrap();
+    Game::from_board(board)
+}
+
+#[test]
+fn attacks_by_clear() {
+    let table = AttackTable::default();
+    let lines = [
+        (ClearType::Single, 1, 0),
+        (ClearType::Double, 2, 1),
+        (ClearType::Triple, 3, 2),
+        (ClearType::Tetris, 4, 4),
+        (ClearType::TSpinMiniSingle, 1, 0),
+        (ClearType::TSpinMiniDouble, 2, 1),
+        (ClearType::TSpinSingle, 1, 2),
+        (ClearType::TSpinDouble, 2, 4),
+        (ClearType::TSpinTriple, 3, 6),
+    ];
+    for (clear_type, cleared, attack) in lines {
+        assert_eq!(
+            table.attack(&result(Some(clear_type), cleared)),
+            attack,
+            "{clear_type:?}"
+        );
+    }
+    // spins without lines send nothing
+    assert_eq!(table.attack(&result(Some(ClearType::TSpin), 0)), 0);
+    assert_eq!(table.attack(&result(None, 0)), 0);
+}
+
+#[test]
+fn attacks_with_bonuses() {
+    let table = AttackTable::default();
+    let tetris = result(Some(ClearType::Tetris), 4);
+    let back_to_back = LockResult {
+        back_to_back: true,
+        ..tetris
+    };
+    assert_eq!(table.attack(&back_to_back), 4 + 1);
+
+    let combo = |combo| {
+        table.attack(&LockResult {
+            combo: Some(combo),
+            ..result(Some(ClearType::Single), 1)
+        })
+    };
+    assert_eq!(combo(0), 0);
+    assert_eq!(combo(3), 2);
+    assert_eq!(combo(10), 5);
+    // the last entry repeats
+    assert_eq!(combo(50), 5);
+
+    let perfect_clear = LockResult {
+        perfect_clear: true,
+        combo: Some(1),
+        ..result(Some(ClearType::Double), 2)
+    };
+    assert_eq!(table.attack(&perfect_clear), 1 + 1 + 10);
+
+    let flat = AttackTable {
+        combo: vec![],
+        ..Default::default()
+    };
+    assert_eq!(
+        flat.attack(&LockResult {
+            combo: Some(5),
+            ..tetris
+        }),
+        4
+    );
+}
+
+#[test]
+fn cancels_incoming_garbage() {
+    let mut game = Game::with_seed(0);
+    assert_eq!(game.cancel_garbage(3), 3);
+
+    game.receive_garbage(3, 0);
+    game.receive_garbage(2, 5);
+    // oldest first, a batch at a time
+    assert_eq!(game.cancel_garbage(4), 0);
+    assert_eq!(game.get_incoming_garbage(), 1);
+    assert_eq!(game.cancel_garbage(3), 2);
+    assert_eq!(game.get_incoming_garbage(), 0);
+    assert_eq!(game.cancel_garbage(0), 0);
+}
+
+#[test]
+fn sends_attack_net_of_incoming() {
+    let mut versus = Versus::with_seed(AttackTable::default(), 1, 0);
+    versus.get_player_mut(0).inner = o_double();
+    hard_drop(&mut versus, 0);
+    assert_eq!(versus.get_player(0).get_sent(), 1);
+    assert_eq!(versus.get_incoming_garbage(1), 1);
+
+    // the double only cancels what is coming the other way
+    let mut versus = Versus::with_seed(AttackTable::default(), 1, 0);
+    versus.get_player_mut(0).inner = o_double();
+    versus.receive_garbage(Garbage {
+        from: 1,
+        rows: 3,
+        hole: 0,
+    });
+    hard_drop(&mut versus, 0);
+    assert_eq!(versus.get_player(0).get_sent(), 0);
+    assert_eq!(versus.get_incoming_garbage(0), 2);
+    assert_eq!(versus.get_incoming_garbage(1), 0);
+
+    // held back for the network while delivery is off
+    let mut versus = Versus::with_seed(AttackTable::default(), 1, 0);
+    versus.set_deliver_garbage(false);
+    versus.get_player_mut(0).inner = o_double();
+    hard_drop(&mut versus, 0);
+    let sent = versus.take_sent_garbage();
+    assert_eq!(sent.len(), 1);
+    assert_eq!((sent[0].from, sent[0].rows), (0, 1));
+    assert_eq!(versus.get_incoming_garbage(1), 0);
+}
+
+#[test]
+fn plays_best_of() {
+    let mut versus = Versus::with_seed(AttackTable::default(), 3, 1);
+    assert_eq!(versus.get_best_of(), 3);
+    let knock_out = |versus: &mut Versus, player: usize| {
+        versus.get_player_mut(player).inner.push_garbage(40, 0);
+        versus.update();
+        assert!(versus.is_round_over());
+        // nothing moves until the next round
+        let wins = versus.get_wins();
+        versus.update();
+        assert_eq!(versus.get_wins(), wins);
+    };
+
+    knock_out(&mut versus, 1);
+    assert_eq!(versus.get_round_winner(), Some(0));
+    assert_eq!(versus.get_wins(), [1, 0]);
+    assert_eq!(versus.get_match_winner(), None);
+
+    versus.next_round();
+    assert!(!versus.is_round_over());
+    knock_out(&mut versus, 0);
+    assert_eq!(versus.get_round_winner(), Some(1));
+    assert_eq!(versus.get_wins(), [1, 1]);
+
+    // a draw counts for nobody
+    versus.next_round();
+    versus.get_player_mut(1).inner.push_garbage(40, 0);
+    knock_out(&mut versus, 0);
+    assert_eq!(versus.get_round_winner(), None);
+    assert_eq!(versus.get_wins(), [1, 1]);
+
+    versus.next_round();
+    knock_out(&mut versus, 0);
+    assert_eq!(versus.get_wins(), [1, 2]);
+    assert_eq!(versus.get_match_winner(), Some(1));
+
+    // the next round starts a new match
+    versus.next_round();
+    assert_eq!(versus.get_wins(), [0, 0]);
+    assert_eq!(versus.get_match_winner(), None);
+
+    // a single round decides a best of one
+    let mut versus = Versus::with_seed(AttackTable::default(), 0, 1);
+    assert_eq!(versus.get_best_of(), 1);
+    knock_out(&mut versus, 1);
+    assert_eq!(versus.get_match_winner(), Some(0));
+}